@@ -0,0 +1,66 @@
+//! Compares one-at-a-time API calls with `LogseqClient::call_api_batch`
+//! against a mock Logseq server that takes a few milliseconds per request.
+//! A concurrency of 1 is the sequential baseline.
+//!
+//! Run with `cargo bench --bench call_api_batch`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use mcp_logseq_rust::{config::Config, logseq_client::LogseqClient};
+use serde_json::{Value, json};
+use std::hint::black_box;
+use std::time::Duration;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Simulated Logseq latency per request
+const LATENCY: Duration = Duration::from_millis(2);
+const CALLS: usize = 20;
+
+fn calls() -> Vec<(String, Vec<Value>)> {
+    (0..CALLS)
+        .map(|i| {
+            (
+                "logseq.Editor.getPage".to_string(),
+                vec![json!(format!("page-{i}"))],
+            )
+        })
+        .collect()
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"name": "page"}))
+                    .set_delay(LATENCY),
+            )
+            .mount(&server)
+            .await;
+        server
+    });
+
+    let mut group = c.benchmark_group("call_api_batch");
+    for concurrency in [1, 5, 10] {
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            max_concurrent_requests: concurrency,
+            ..Config::default()
+        })
+        .unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("concurrency", concurrency),
+            &client,
+            |b, client| {
+                b.to_async(&rt)
+                    .iter(|| async { black_box(client.call_api_batch(calls()).await.unwrap()) })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch);
+criterion_main!(benches);
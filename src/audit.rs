@@ -0,0 +1,297 @@
+//! # Audit Log
+//!
+//! Records every mutation tool call as one line of JSON in an append-only
+//! file (`AUDIT_LOG_FILE`), so changes made to the graph through the server
+//! can be reviewed later:
+//!
+//! ```json
+//! {"timestamp":"2025-01-15T10:30:00.123+00:00","tool":"update_block","params":{...},"result":{"success":true},"request_id":7}
+//! ```
+//!
+//! Failed calls are recorded too, with `"success": false` and the error
+//! message. So are calls that never finish because the request was
+//! cancelled or the tool panicked; their error says so, since the change
+//! may or may not have reached Logseq.
+//!
+//! Parameters are sanitized before they are written: the Logseq API token
+//! is masked, and values of keys that look like credentials are replaced.
+
+use crate::{error::McpResult, utils::mask_token};
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Placeholder written in place of sensitive values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Appends mutation records to the audit log file.
+///
+/// Cloning is cheap and clones share the file, so one logger can be handed
+/// to every request task. Writes are serialized by a mutex and flushed
+/// immediately, so a record is on disk before the tool's response is sent.
+#[derive(Clone)]
+pub struct AuditLogger {
+    out: Arc<Mutex<BufWriter<File>>>,
+    /// The Logseq API token, masked wherever it appears in parameters
+    token: String,
+}
+
+impl AuditLogger {
+    /// Opens `path` for appending, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the path if the file can't be opened.
+    pub fn open(path: &Path, token: &str) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Cannot open audit log {}", path.display()))?;
+        Ok(Self {
+            out: Arc::new(Mutex::new(BufWriter::new(file))),
+            token: token.to_string(),
+        })
+    }
+
+    /// Records one mutation call and its outcome.
+    ///
+    /// A failed write is logged rather than returned: the mutation has
+    /// already happened, so failing the request would only hide that.
+    pub fn record(
+        &self,
+        tool: &str,
+        params: &Value,
+        request_id: &Value,
+        result: &McpResult<Value>,
+    ) {
+        let outcome = match result {
+            Ok(_) => json!({ "success": true }),
+            Err(e) => json!({
+                "success": false,
+                "error": mask_token(&e.to_string(), &self.token)
+            }),
+        };
+        self.write_entry(tool, params, request_id, outcome);
+    }
+
+    /// Starts recording a mutation call that is about to run.
+    ///
+    /// Pass the outcome to [`PendingRecord::finish`]. If the record is
+    /// dropped unfinished, because the request task was aborted or the tool
+    /// panicked, that is recorded instead.
+    pub fn start(&self, tool: &str, params: &Value, request_id: &Value) -> PendingRecord<'_> {
+        PendingRecord {
+            logger: self,
+            call: Some((tool.to_string(), params.clone(), request_id.clone())),
+        }
+    }
+
+    fn write_entry(&self, tool: &str, params: &Value, request_id: &Value, outcome: Value) {
+        let entry = json!({
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            "tool": tool,
+            "params": sanitize_params(params, &self.token),
+            "result": outcome,
+            "request_id": request_id
+        });
+
+        if let Err(e) = self.write_line(&entry.to_string()) {
+            tracing::error!("Failed to write audit log entry for '{}': {}", tool, e);
+        }
+    }
+
+    fn write_line(&self, line: &str) -> std::io::Result<()> {
+        // A panic mid-write can't leave a partial line worse than the file
+        // already has, so recover from a poisoned lock
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(out, "{}", line)?;
+        out.flush()
+    }
+}
+
+/// A mutation call that has started but not yet been recorded; see
+/// [`AuditLogger::start`].
+pub struct PendingRecord<'a> {
+    logger: &'a AuditLogger,
+    /// Tool name, parameters and request id, taken once recorded
+    call: Option<(String, Value, Value)>,
+}
+
+impl PendingRecord<'_> {
+    /// Records the call's outcome.
+    pub fn finish(mut self, result: &McpResult<Value>) {
+        if let Some((tool, params, request_id)) = self.call.take() {
+            self.logger.record(&tool, &params, &request_id, result);
+        }
+    }
+}
+
+impl Drop for PendingRecord<'_> {
+    fn drop(&mut self) {
+        let Some((tool, params, request_id)) = self.call.take() else {
+            return;
+        };
+        let reason = if std::thread::panicking() {
+            "Tool panicked"
+        } else {
+            "Request cancelled"
+        };
+        let outcome = json!({
+            "success": false,
+            "error": format!("{} before completing; the change may have been applied", reason)
+        });
+        self.logger
+            .write_entry(&tool, &params, &request_id, outcome);
+    }
+}
+
+/// Copies tool parameters for the audit log, masking the API token and
+/// redacting values whose key mentions a token, password or secret.
+fn sanitize_params(params: &Value, token: &str) -> Value {
+    match params {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if is_sensitive_key(key) {
+                        json!(REDACTED)
+                    } else {
+                        sanitize_params(value, token)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| sanitize_params(item, token))
+                .collect(),
+        ),
+        Value::String(s) => Value::String(mask_token(s, token)),
+        other => other.clone(),
+    }
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    ["token", "password", "secret"]
+        .iter()
+        .any(|word| key.contains(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::McpError;
+
+    #[test]
+    fn test_sanitize_params() {
+        let params = json!({
+            "uuid": "abc",
+            "content": "uses secret-token inline",
+            "api_token": "anything",
+            "nested": [{"Password": "hunter2", "ok": 1}]
+        });
+        assert_eq!(
+            sanitize_params(&params, "secret-token"),
+            json!({
+                "uuid": "abc",
+                "content": "uses [REDACTED] inline",
+                "api_token": "[REDACTED]",
+                "nested": [{"Password": "[REDACTED]", "ok": 1}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_record_appends_json_lines() {
+        let path = std::env::temp_dir().join(format!(
+            "mcp-logseq-audit-{}-{:?}.log",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let logger = AuditLogger::open(&path, "tok").unwrap();
+        logger.record(
+            "update_block",
+            &json!({"uuid": "u1", "content": "new"}),
+            &json!(7),
+            &Ok(json!({"success": true})),
+        );
+        // Reopening appends rather than truncating
+        let logger = AuditLogger::open(&path, "tok").unwrap();
+        logger.record(
+            "delete_block",
+            &json!({"uuid": "u2"}),
+            &json!("req-2"),
+            &Err(McpError::Internal("Block not found: u2".to_string())),
+        );
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries: Vec<Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0]["tool"], "update_block");
+        assert_eq!(entries[0]["params"]["content"], "new");
+        assert_eq!(entries[0]["result"], json!({"success": true}));
+        assert_eq!(entries[0]["request_id"], 7);
+        let timestamp = entries[0]["timestamp"].as_str().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok());
+
+        assert_eq!(
+            entries[1]["result"],
+            json!({"success": false, "error": "Block not found: u2"})
+        );
+        assert_eq!(entries[1]["request_id"], "req-2");
+    }
+
+    #[test]
+    fn test_unfinished_record_is_written_on_drop() {
+        let path = std::env::temp_dir().join(format!(
+            "mcp-logseq-audit-pending-{}-{:?}.log",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let logger = AuditLogger::open(&path, "tok").unwrap();
+
+        let params = json!({"uuid": "u1"});
+        logger
+            .start("update_block", &params, &json!(1))
+            .finish(&Ok(json!({"success": true})));
+        drop(logger.start("delete_block", &params, &json!(2)));
+        let panicked = std::panic::catch_unwind(|| {
+            let _pending = logger.start("move_block", &params, &json!(3));
+            panic!("tool bug");
+        });
+        assert!(panicked.is_err());
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries: Vec<Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0]["result"], json!({"success": true}));
+        assert_eq!(entries[1]["request_id"], 2);
+        assert_eq!(
+            entries[1]["result"]["error"],
+            "Request cancelled before completing; the change may have been applied"
+        );
+        assert_eq!(entries[2]["tool"], "move_block");
+        assert_eq!(
+            entries[2]["result"]["error"],
+            "Tool panicked before completing; the change may have been applied"
+        );
+    }
+}
@@ -9,6 +9,41 @@
 //! - `LOGSEQ_API_TOKEN`: Authentication token for Logseq HTTP API
 //! - `LOGSEQ_API_URL`: Base URL for Logseq API (defaults to localhost:12315)
 //!
+//! ## Optional Tuning
+//!
+//! - `LOGSEQ_POOL_SIZE`: Max idle HTTP connections kept per host (default 10)
+//! - `LOGSEQ_KEEPALIVE_SECS`: TCP keep-alive interval in seconds (default 30)
+//! - `LOGSEQ_MAX_RETRIES`: Retries for transient API failures (default 3)
+//! - `LOGSEQ_RETRY_DELAY_MS`: Initial retry backoff in milliseconds (default 200)
+//! - `LOGSEQ_REQUEST_TIMEOUT_MS`: Per-request timeout in milliseconds (default 30000)
+//! - `LOGSEQ_MAX_CONCURRENT_REQUESTS`: Most API calls a batch keeps in flight
+//!   at once (default 5)
+//! - `LOGSEQ_ACCEPT_ENCODING`: `Accept-Encoding` sent with API requests
+//!   (default `gzip, br`); empty sends none
+//! - `LOGSEQ_REQUEST_COMPRESSION`: Set to `true` to gzip request bodies
+//!   (default false)
+//! - `LOGSEQ_READ_ONLY`: Set to `true` to disable all mutation tools (default false)
+//! - `ALLOWED_TOOLS`: Comma-separated tool names to expose; unset exposes every tool
+//! - `LOGSEQ_SKIP_STARTUP_CHECK`: Set to `true` to start without first checking
+//!   that Logseq is reachable (default false)
+//! - `SHUTDOWN_TIMEOUT_MS`: How long in-flight requests get to finish after
+//!   SIGTERM/SIGINT, in milliseconds (default 5000)
+//! - `MAX_REQUEST_SIZE_BYTES`: Longest stdin request line accepted, in bytes
+//!   (default 10 MB)
+//! - `MCP_INCLUDE_TIMING`: Set to `true` to add a `_timing` breakdown to
+//!   successful tool responses (default false)
+//! - `AUDIT_LOG_FILE`: Path of a file to append a JSON line to for every
+//!   mutation tool call; unset disables the audit log
+//!
+//! ## Transport
+//!
+//! - `TRANSPORT`: `stdio` (default) or `http`
+//! - `HTTP_HOST`: Address the HTTP transport binds to (default 127.0.0.1)
+//! - `HTTP_PORT`: Port the HTTP transport listens on (default 3000)
+//!
+//! The HTTP transport has no authentication of its own; only expose it on
+//! trusted networks.
+//!
 //! ## Environment Setup
 //!
 //! The server loads configuration from environment variables, with automatic
@@ -17,18 +52,140 @@
 
 use anyhow::Result;
 use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// How the server exchanges JSON-RPC messages with its client.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// Newline-delimited JSON-RPC over stdin/stdout
+    Stdio,
+    /// JSON-RPC over HTTP `POST /`
+    Http,
+}
+
+impl FromStr for Transport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "stdio" => Ok(Transport::Stdio),
+            "http" => Ok(Transport::Http),
+            other => Err(anyhow::anyhow!(
+                "TRANSPORT must be 'stdio' or 'http', got '{}'",
+                other
+            )),
+        }
+    }
+}
 
 /// Configuration structure for the MCP Logseq server.
 ///
 /// Contains all the settings needed to connect to and authenticate with
 /// a Logseq instance via its HTTP API. Configuration values are loaded
 /// from environment variables during server startup.
-#[derive(Debug, Deserialize, Clone)]
+///
+/// `Debug` output redacts the API token so configs can be logged safely.
+#[derive(Deserialize, Clone)]
 pub struct Config {
     /// Bearer token for authenticating with the Logseq HTTP API
     pub logseq_api_token: String,
     /// Base URL for the Logseq HTTP API endpoint
     pub logseq_api_url: String,
+    /// Maximum idle connections kept open to the Logseq server
+    pub connection_pool_size: usize,
+    /// TCP keep-alive interval for pooled connections, in seconds
+    pub keepalive_secs: u64,
+    /// How many times a transient API failure is retried before giving up
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each subsequent attempt
+    pub initial_retry_delay_ms: u64,
+    /// Timeout applied to each HTTP request to Logseq, in milliseconds
+    pub request_timeout_ms: u64,
+    /// Most API calls `call_api_batch` runs at once; 0 is treated as 1
+    pub max_concurrent_requests: usize,
+    /// `Accept-Encoding` header for API requests; empty leaves it unset
+    pub accept_encoding: String,
+    /// Gzip request bodies and send `Content-Encoding: gzip`
+    pub request_compression: bool,
+    /// Hide and refuse every tool that modifies the graph
+    pub read_only: bool,
+    /// Tool names clients may see and call; `None` allows every tool
+    pub allowed_tools: Option<HashSet<String>>,
+    /// Which transport the server listens on
+    pub transport: Transport,
+    /// Bind address for the HTTP transport
+    pub http_host: String,
+    /// Listening port for the HTTP transport
+    pub http_port: u16,
+    /// How long in-flight requests may run after a shutdown signal, in milliseconds
+    pub shutdown_timeout_ms: u64,
+    /// Start without verifying that Logseq is reachable
+    pub skip_startup_check: bool,
+    /// Longest request line read from stdin; longer lines are rejected unparsed
+    pub max_request_size_bytes: usize,
+    /// Add per-phase timings to successful tool call responses
+    pub include_timing: bool,
+    /// File that mutation tool calls are appended to; `None` disables auditing
+    pub audit_log_file: Option<PathBuf>,
+}
+
+impl Default for Config {
+    /// Defaults for everything except the API token, which has no sensible default.
+    fn default() -> Self {
+        Config {
+            logseq_api_token: String::new(),
+            logseq_api_url: "http://localhost:12315".to_string(),
+            connection_pool_size: 10,
+            keepalive_secs: 30,
+            max_retries: 3,
+            initial_retry_delay_ms: 200,
+            request_timeout_ms: 30_000,
+            max_concurrent_requests: 5,
+            accept_encoding: "gzip, br".to_string(),
+            request_compression: false,
+            read_only: false,
+            allowed_tools: None,
+            transport: Transport::Stdio,
+            http_host: "127.0.0.1".to_string(),
+            http_port: 3000,
+            shutdown_timeout_ms: 5_000,
+            skip_startup_check: false,
+            max_request_size_bytes: 10 * 1024 * 1024,
+            include_timing: false,
+            audit_log_file: None,
+        }
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("logseq_api_token", &"[REDACTED]")
+            .field("logseq_api_url", &self.logseq_api_url)
+            .field("connection_pool_size", &self.connection_pool_size)
+            .field("keepalive_secs", &self.keepalive_secs)
+            .field("max_retries", &self.max_retries)
+            .field("initial_retry_delay_ms", &self.initial_retry_delay_ms)
+            .field("request_timeout_ms", &self.request_timeout_ms)
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .field("accept_encoding", &self.accept_encoding)
+            .field("request_compression", &self.request_compression)
+            .field("read_only", &self.read_only)
+            .field("allowed_tools", &self.allowed_tools)
+            .field("transport", &self.transport)
+            .field("http_host", &self.http_host)
+            .field("http_port", &self.http_port)
+            .field("shutdown_timeout_ms", &self.shutdown_timeout_ms)
+            .field("skip_startup_check", &self.skip_startup_check)
+            .field("max_request_size_bytes", &self.max_request_size_bytes)
+            .field("include_timing", &self.include_timing)
+            .field("audit_log_file", &self.audit_log_file)
+            .finish()
+    }
 }
 
 impl Config {
@@ -42,6 +199,24 @@ impl Config {
     ///
     /// - `LOGSEQ_API_TOKEN` (required): Bearer token for API authentication
     /// - `LOGSEQ_API_URL` (optional): API base URL, defaults to localhost:12315
+    /// - `LOGSEQ_POOL_SIZE` (optional): Idle connection pool size, defaults to 10
+    /// - `LOGSEQ_KEEPALIVE_SECS` (optional): TCP keep-alive seconds, defaults to 30
+    /// - `LOGSEQ_MAX_RETRIES` (optional): Transient failure retries, defaults to 3
+    /// - `LOGSEQ_RETRY_DELAY_MS` (optional): Initial backoff, defaults to 200
+    /// - `LOGSEQ_REQUEST_TIMEOUT_MS` (optional): Request timeout, defaults to 30000
+    /// - `LOGSEQ_MAX_CONCURRENT_REQUESTS` (optional): Batch concurrency, defaults to 5
+    /// - `LOGSEQ_ACCEPT_ENCODING` (optional): Accepted response encodings, defaults to `gzip, br`
+    /// - `LOGSEQ_REQUEST_COMPRESSION` (optional): Gzip request bodies, defaults to false
+    /// - `LOGSEQ_READ_ONLY` (optional): Disable mutation tools, defaults to false
+    /// - `ALLOWED_TOOLS` (optional): Comma-separated tool allowlist, defaults to all tools
+    /// - `TRANSPORT` (optional): `stdio` or `http`, defaults to stdio
+    /// - `HTTP_HOST` (optional): HTTP bind address, defaults to 127.0.0.1
+    /// - `HTTP_PORT` (optional): HTTP listening port, defaults to 3000
+    /// - `SHUTDOWN_TIMEOUT_MS` (optional): Drain time after a shutdown signal, defaults to 5000
+    /// - `LOGSEQ_SKIP_STARTUP_CHECK` (optional): Skip the startup connection check, defaults to false
+    /// - `MAX_REQUEST_SIZE_BYTES` (optional): Stdin request size limit, defaults to 10 MB
+    /// - `MCP_INCLUDE_TIMING` (optional): Add `_timing` to tool responses, defaults to false
+    /// - `AUDIT_LOG_FILE` (optional): Mutation audit log path, defaults to no audit log
     ///
     /// # Returns
     ///
@@ -51,21 +226,248 @@ impl Config {
     /// # Errors
     ///
     /// Returns an error if `LOGSEQ_API_TOKEN` is not set, as this is
-    /// required for API authentication.
+    /// required for API authentication, or if a numeric setting or `TRANSPORT`
+    /// can't be parsed.
     pub fn from_env() -> Result<Self> {
         // Load .env file if present (ignore if it doesn't exist)
         dotenvy::dotenv().ok();
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Builds the configuration from the variables `lookup` returns, with the
+    /// same names, defaults and errors as [`Config::from_env`].
+    ///
+    /// `lookup` returns a variable's value, or `None` if it's unset. Nothing
+    /// is read from the process environment or `.env`.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let logseq_api_token = lookup("LOGSEQ_API_TOKEN")
+            .ok_or_else(|| anyhow::anyhow!("LOGSEQ_API_TOKEN not set"))?;
 
-        let logseq_api_token = std::env::var("LOGSEQ_API_TOKEN")
-            .map_err(|_| anyhow::anyhow!("LOGSEQ_API_TOKEN not set"))?;
+        let defaults = Config::default();
 
         // Default to standard Logseq HTTP API port on localhost
-        let logseq_api_url = std::env::var("LOGSEQ_API_URL")
-            .unwrap_or_else(|_| "http://localhost:12315".to_string());
+        let logseq_api_url = lookup("LOGSEQ_API_URL").unwrap_or(defaults.logseq_api_url);
+
+        let transport = match lookup("TRANSPORT") {
+            Some(raw) => raw.parse()?,
+            None => defaults.transport,
+        };
 
         Ok(Config {
             logseq_api_token,
             logseq_api_url,
+            connection_pool_size: env_or(
+                &lookup,
+                "LOGSEQ_POOL_SIZE",
+                defaults.connection_pool_size,
+            )?,
+            keepalive_secs: env_or(&lookup, "LOGSEQ_KEEPALIVE_SECS", defaults.keepalive_secs)?,
+            max_retries: env_or(&lookup, "LOGSEQ_MAX_RETRIES", defaults.max_retries)?,
+            initial_retry_delay_ms: env_or(
+                &lookup,
+                "LOGSEQ_RETRY_DELAY_MS",
+                defaults.initial_retry_delay_ms,
+            )?,
+            request_timeout_ms: env_or(
+                &lookup,
+                "LOGSEQ_REQUEST_TIMEOUT_MS",
+                defaults.request_timeout_ms,
+            )?,
+            max_concurrent_requests: env_or(
+                &lookup,
+                "LOGSEQ_MAX_CONCURRENT_REQUESTS",
+                defaults.max_concurrent_requests,
+            )?,
+            accept_encoding: lookup("LOGSEQ_ACCEPT_ENCODING").unwrap_or(defaults.accept_encoding),
+            request_compression: env_or(
+                &lookup,
+                "LOGSEQ_REQUEST_COMPRESSION",
+                defaults.request_compression,
+            )?,
+            read_only: env_or(&lookup, "LOGSEQ_READ_ONLY", defaults.read_only)?,
+            allowed_tools: lookup("ALLOWED_TOOLS").map(|raw| parse_tool_list(&raw)),
+            transport,
+            http_host: lookup("HTTP_HOST").unwrap_or(defaults.http_host),
+            http_port: env_or(&lookup, "HTTP_PORT", defaults.http_port)?,
+            shutdown_timeout_ms: env_or(
+                &lookup,
+                "SHUTDOWN_TIMEOUT_MS",
+                defaults.shutdown_timeout_ms,
+            )?,
+            skip_startup_check: env_or(
+                &lookup,
+                "LOGSEQ_SKIP_STARTUP_CHECK",
+                defaults.skip_startup_check,
+            )?,
+            max_request_size_bytes: env_or(
+                &lookup,
+                "MAX_REQUEST_SIZE_BYTES",
+                defaults.max_request_size_bytes,
+            )?,
+            include_timing: env_or(&lookup, "MCP_INCLUDE_TIMING", defaults.include_timing)?,
+            audit_log_file: lookup("AUDIT_LOG_FILE")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+        })
+    }
+}
+
+/// Reads and parses an optional variable, falling back to `default` when unset.
+fn env_or<T: FromStr>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    default: T,
+) -> Result<T> {
+    match lookup(name) {
+        Some(raw) => raw
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("{} has an invalid value '{}'", name, raw)),
+        None => Ok(default),
+    }
+}
+
+/// Splits a comma-separated tool list, ignoring whitespace and empty entries.
+fn parse_tool_list(raw: &str) -> HashSet<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a `Config` with only `vars` set, without touching the process
+    /// environment.
+    fn from_env_with(vars: &[(&str, &str)]) -> Result<Config> {
+        Config::from_lookup(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
         })
     }
+
+    #[test]
+    fn test_transport_from_str() {
+        assert_eq!("stdio".parse::<Transport>().unwrap(), Transport::Stdio);
+        assert_eq!(" HTTP ".parse::<Transport>().unwrap(), Transport::Http);
+        assert!("websocket".parse::<Transport>().is_err());
+    }
+
+    #[test]
+    fn test_parse_tool_list() {
+        let tools = parse_tool_list(" search, get_page ,,");
+        assert_eq!(tools.len(), 2);
+        assert!(tools.contains("search") && tools.contains("get_page"));
+        assert!(parse_tool_list("").is_empty());
+    }
+
+    #[test]
+    fn test_debug_redacts_token() {
+        let config = Config {
+            logseq_api_token: "super-secret-token".to_string(),
+            ..Config::default()
+        };
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("super-secret-token"));
+        assert!(debug.contains(r#"logseq_api_token: "[REDACTED]""#));
+        assert!(debug.contains("http://localhost:12315"));
+    }
+
+    #[test]
+    fn test_from_env_requires_token() {
+        let err = from_env_with(&[]).unwrap_err();
+        assert_eq!(err.to_string(), "LOGSEQ_API_TOKEN not set");
+    }
+
+    #[test]
+    fn test_from_env_defaults() {
+        let config = from_env_with(&[("LOGSEQ_API_TOKEN", "secret")]).unwrap();
+        let defaults = Config::default();
+        assert_eq!(config.logseq_api_token, "secret");
+        assert_eq!(config.logseq_api_url, "http://localhost:12315");
+        assert_eq!(config.request_timeout_ms, defaults.request_timeout_ms);
+        assert_eq!(config.max_concurrent_requests, 5);
+        assert_eq!(config.accept_encoding, "gzip, br");
+        assert!(!config.request_compression);
+        assert_eq!(config.connection_pool_size, defaults.connection_pool_size);
+        assert_eq!(config.keepalive_secs, defaults.keepalive_secs);
+        assert_eq!(config.max_retries, defaults.max_retries);
+        assert_eq!(
+            config.initial_retry_delay_ms,
+            defaults.initial_retry_delay_ms
+        );
+        assert!(!config.read_only);
+        assert!(config.allowed_tools.is_none());
+        assert_eq!(config.transport, Transport::Stdio);
+        assert_eq!(config.http_host, "127.0.0.1");
+        assert_eq!(config.http_port, 3000);
+        assert_eq!(config.shutdown_timeout_ms, 5000);
+        assert!(!config.skip_startup_check);
+        assert_eq!(config.max_request_size_bytes, 10 * 1024 * 1024);
+        assert!(!config.include_timing);
+        assert!(config.audit_log_file.is_none());
+    }
+
+    #[test]
+    fn test_from_env_overrides() {
+        let config = from_env_with(&[
+            ("LOGSEQ_API_TOKEN", "secret"),
+            ("LOGSEQ_API_URL", "http://127.0.0.1:9999"),
+            ("LOGSEQ_REQUEST_TIMEOUT_MS", " 5000 "),
+            ("LOGSEQ_MAX_RETRIES", "0"),
+            ("LOGSEQ_MAX_CONCURRENT_REQUESTS", "2"),
+            ("LOGSEQ_ACCEPT_ENCODING", "gzip"),
+            ("LOGSEQ_REQUEST_COMPRESSION", "true"),
+            ("LOGSEQ_READ_ONLY", "true"),
+            ("ALLOWED_TOOLS", "search,get_page"),
+            ("TRANSPORT", "http"),
+            ("HTTP_PORT", "8080"),
+            ("SHUTDOWN_TIMEOUT_MS", "100"),
+            ("MAX_REQUEST_SIZE_BYTES", "1024"),
+            ("MCP_INCLUDE_TIMING", "true"),
+            ("AUDIT_LOG_FILE", "/var/log/mcp-audit.log"),
+        ])
+        .unwrap();
+        assert_eq!(config.logseq_api_url, "http://127.0.0.1:9999");
+        assert_eq!(config.request_timeout_ms, 5000);
+        assert_eq!(config.max_retries, 0);
+        assert_eq!(config.max_concurrent_requests, 2);
+        assert_eq!(config.accept_encoding, "gzip");
+        assert!(config.request_compression);
+        assert!(config.read_only);
+        assert_eq!(config.allowed_tools.unwrap().len(), 2);
+        assert_eq!(config.transport, Transport::Http);
+        assert_eq!(config.http_port, 8080);
+        assert_eq!(config.shutdown_timeout_ms, 100);
+        assert_eq!(config.max_request_size_bytes, 1024);
+        assert!(config.include_timing);
+        assert_eq!(
+            config.audit_log_file,
+            Some(PathBuf::from("/var/log/mcp-audit.log"))
+        );
+    }
+
+    #[test]
+    fn test_from_env_rejects_invalid_values() {
+        let err = from_env_with(&[
+            ("LOGSEQ_API_TOKEN", "secret"),
+            ("LOGSEQ_REQUEST_TIMEOUT_MS", "soon"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "LOGSEQ_REQUEST_TIMEOUT_MS has an invalid value 'soon'"
+        );
+        assert!(from_env_with(&[("LOGSEQ_API_TOKEN", "secret"), ("TRANSPORT", "ws")]).is_err());
+    }
+
+    #[test]
+    fn test_from_env_debug_hides_token() {
+        let config = from_env_with(&[("LOGSEQ_API_TOKEN", "from-env-secret")]).unwrap();
+        assert!(!format!("{:?}", config).contains("from-env-secret"));
+    }
 }
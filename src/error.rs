@@ -0,0 +1,92 @@
+//! # Error Types
+//!
+//! Typed errors returned by the Logseq client and the tool handlers.
+//! `handle_tool_call` matches on the variant to choose the JSON-RPC error
+//! code, so callers can tell bad input apart from Logseq or network failures.
+
+use serde_json::Value;
+use std::fmt;
+
+/// Errors with a specific meaning for MCP clients.
+#[derive(Debug)]
+pub enum McpError {
+    /// The tool was called with missing or invalid parameters (JSON-RPC -32602)
+    InvalidParams(String),
+    /// The Logseq API accepted the request but reported an error
+    LogseqApi {
+        /// The API method that failed, e.g. `logseq.Editor.getPage`
+        method: String,
+        /// The error reported by Logseq
+        message: String,
+    },
+    /// The HTTP request to Logseq failed (connection refused, bad status, ...)
+    Http {
+        source: reqwest::Error,
+        /// Response body of a bad status, with the API token masked
+        body: Option<String>,
+    },
+    /// A response could not be parsed as JSON
+    Json(serde_json::Error),
+    /// A Logseq API request did not complete within its timeout
+    Timeout(String),
+    /// Any other failure while executing a tool (missing page, git error, ...)
+    Internal(String),
+}
+
+/// Result alias used by the client and tool handlers.
+pub type McpResult<T> = Result<T, McpError>;
+
+impl McpError {
+    /// Structured details for the JSON-RPC `error.data` field, if any.
+    pub fn data(&self) -> Option<Value> {
+        match self {
+            McpError::LogseqApi { method, message } => Some(serde_json::json!({
+                "method": method,
+                "logseq_error": message
+            })),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for McpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            McpError::InvalidParams(msg) => write!(f, "Invalid params: {}", msg),
+            McpError::LogseqApi { message, .. } => write!(f, "Logseq API error: {}", message),
+            McpError::Http { source, body: None } => write!(f, "HTTP error: {}", source),
+            McpError::Http {
+                source,
+                body: Some(body),
+            } => write!(f, "HTTP error: {}: {}", source, body),
+            McpError::Json(e) => write!(f, "JSON error: {}", e),
+            McpError::Timeout(msg) => write!(f, "Timed out: {}", msg),
+            McpError::Internal(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for McpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            McpError::Http { source, .. } => Some(source),
+            McpError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for McpError {
+    fn from(e: reqwest::Error) -> Self {
+        McpError::Http {
+            source: e,
+            body: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for McpError {
+    fn from(e: serde_json::Error) -> Self {
+        McpError::Json(e)
+    }
+}
@@ -0,0 +1,21 @@
+//! # MCP Logseq Library
+//!
+//! The building blocks of the MCP Logseq server: configuration, the Logseq
+//! HTTP API client, the MCP protocol types and every tool, resource and
+//! prompt handler. The `mcp-logseq-rust` binary wires these up to a
+//! transport; integration tests use them directly.
+
+pub mod audit;
+pub mod config;
+pub mod error;
+pub mod logseq_client;
+pub mod models;
+pub mod org_serializer;
+pub mod progress;
+pub mod prompts;
+pub mod protocol;
+pub mod resources;
+pub mod tools;
+pub mod utils;
+pub mod validation;
+pub mod version;
@@ -20,15 +20,141 @@
 //!
 //! The client checks for API-level errors in responses and converts them
 //! to Result errors for consistent error handling throughout the application.
+//! Transient failures (connection errors, timeouts, 5xx responses) are retried
+//! with exponential backoff; everything else fails on the first attempt.
 
-use crate::{config::Config, models::LogseqApiRequest};
-use anyhow::Result;
+use crate::{
+    config::Config,
+    error::{McpError, McpResult},
+    models::LogseqApiRequest,
+    utils::{child_block_uuids, mask_token},
+    validation::require_block_uuid,
+};
+use chrono::NaiveDate;
+use flate2::{Compression, write::GzEncoder};
+use futures::stream::{self, StreamExt};
 use reqwest::{
     Client,
-    header::{AUTHORIZATION, HeaderMap, HeaderValue},
+    header::{
+        ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, HeaderMap, HeaderValue,
+    },
 };
 use serde_json::Value;
-use std::time::Duration;
+use std::cell::Cell;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+
+/// How much of an error response body is kept in `McpError::Http`.
+const MAX_ERROR_BODY_CHARS: usize = 500;
+
+tokio::task_local! {
+    /// Usage accumulated by the current [`measure_api_usage`] scope.
+    static API_USAGE: Cell<ApiUsage>;
+}
+
+/// Logseq API usage of one [`measure_api_usage`] scope.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApiUsage {
+    /// Time spent in API calls, including retries and backoff
+    pub time: Duration,
+    /// Retry attempts made after transient failures
+    pub retries: u32,
+}
+
+/// Runs `fut`, also returning how long it spent waiting on Logseq API calls
+/// and how many of them were retried.
+///
+/// Time is summed per call, so concurrent calls (e.g. under `join_all`) can
+/// add up to more than the wall-clock time. Calls made from tasks that `fut`
+/// spawns aren't counted.
+pub async fn measure_api_usage<F: Future>(fut: F) -> (F::Output, ApiUsage) {
+    API_USAGE
+        .scope(Cell::new(ApiUsage::default()), async {
+            let output = fut.await;
+            (output, API_USAGE.with(Cell::get))
+        })
+        .await
+}
+
+/// Applies `update` to the enclosing [`measure_api_usage`] scope, if any.
+fn record_usage(update: impl FnOnce(&mut ApiUsage)) {
+    let _ = API_USAGE.try_with(|usage| {
+        let mut current = usage.get();
+        update(&mut current);
+        usage.set(current);
+    });
+}
+
+/// Adds the time until it is dropped to the enclosing [`measure_api_usage`]
+/// scope, if any, so calls are counted however they return.
+struct ApiCallTimer(Instant);
+
+impl Drop for ApiCallTimer {
+    fn drop(&mut self) {
+        let elapsed = self.0.elapsed();
+        record_usage(|usage| usage.time += elapsed);
+    }
+}
+
+/// When a failed attempt may be retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Retry {
+    /// Retrying can't help (bad request, auth failure, bad response)
+    Never,
+    /// Logseq may have applied the request (timeout, 5xx), so only read
+    /// methods are safe to send again
+    IfReadOnly,
+    /// The request never reached Logseq (connection failed)
+    Always,
+}
+
+/// Outcome of a failed single HTTP attempt, tagged with whether retrying could help.
+struct AttemptError {
+    error: McpError,
+    retry: Retry,
+}
+
+impl AttemptError {
+    fn retryable(error: impl Into<McpError>) -> Self {
+        Self {
+            error: error.into(),
+            retry: Retry::IfReadOnly,
+        }
+    }
+
+    fn unsent(error: impl Into<McpError>) -> Self {
+        Self {
+            error: error.into(),
+            retry: Retry::Always,
+        }
+    }
+
+    fn fatal(error: impl Into<McpError>) -> Self {
+        Self {
+            error: error.into(),
+            retry: Retry::Never,
+        }
+    }
+
+    /// Whether `method` can be sent again after this failure.
+    fn can_retry(&self, method: &str) -> bool {
+        match self.retry {
+            Retry::Never => false,
+            Retry::IfReadOnly => is_read_method(method),
+            Retry::Always => true,
+        }
+    }
+}
+
+/// True for API methods that don't modify the graph, so sending one twice
+/// is harmless.
+fn is_read_method(method: &str) -> bool {
+    let name = method.rsplit('.').next().unwrap_or(method);
+    name.starts_with("get")
+        || name.starts_with("exist")
+        || matches!(name, "search" | "datascriptQuery" | "q")
+}
 
 /// HTTP client for interacting with the Logseq API.
 ///
@@ -53,25 +179,47 @@ impl LogseqClient {
     ///
     /// A configured client ready to make API requests, or an error if
     /// the HTTP client cannot be created.
-    pub fn new(config: Config) -> Result<Self> {
+    pub fn new(config: Config) -> McpResult<Self> {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", config.logseq_api_token))?,
-        );
+        let mut auth = HeaderValue::from_str(&format!("Bearer {}", config.logseq_api_token))
+            .map_err(|_| {
+                McpError::Internal("LOGSEQ_API_TOKEN contains invalid characters".into())
+            })?;
+        // Keeps the token out of reqwest/hyper debug and trace output
+        auth.set_sensitive(true);
+        headers.insert(AUTHORIZATION, auth);
+        // Decompression below works whatever is sent; this only controls
+        // which encodings the server is told it may use
+        if !config.accept_encoding.is_empty() {
+            let accept = HeaderValue::from_str(&config.accept_encoding).map_err(|_| {
+                McpError::Internal("LOGSEQ_ACCEPT_ENCODING contains invalid characters".into())
+            })?;
+            headers.insert(ACCEPT_ENCODING, accept);
+        }
         let client = Client::builder()
             .default_headers(headers)
-            .timeout(Duration::from_secs(10))
+            .gzip(true)
+            .brotli(true)
+            .timeout(Duration::from_millis(config.request_timeout_ms))
+            .pool_max_idle_per_host(config.connection_pool_size)
+            .tcp_keepalive(Duration::from_secs(config.keepalive_secs))
+            .connection_verbose(tracing::enabled!(tracing::Level::TRACE))
             .build()?;
         Ok(Self { client, config })
     }
 
+    /// The configuration this client was created with.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     /// Makes an authenticated API call to the Logseq HTTP API.
     ///
     /// This is the core method that all other API methods use. It handles:
     /// - Setting up authentication headers
-    /// - Formatting the request in Logseq's expected format
-    /// - Making the HTTP request
+    /// - Formatting the request in Logseq's expected format, gzipped when
+    ///   `request_compression` is set
+    /// - Making the HTTP request, retrying transient failures
     /// - Parsing and validating the response
     /// - Converting API errors to Rust Result errors
     ///
@@ -87,28 +235,168 @@ impl LogseqClient {
     ///
     /// # Error Handling
     ///
-    /// - Network errors are propagated as-is
-    /// - JSON parsing errors are propagated as-is  
-    /// - API-level errors (in response.error) are converted to anyhow errors
-    async fn call_api(&self, method: &str, args: Vec<Value>) -> Result<Value> {
+    /// - Connection errors are retried up to `max_retries` times with
+    ///   exponential backoff and jitter
+    /// - Timeouts and 5xx responses are retried the same way for read methods
+    ///   only: Logseq may already have applied a mutation, and sending it
+    ///   again could duplicate blocks or pages
+    /// - Retries are counted in the enclosing [`measure_api_usage`] scope
+    /// - A final timeout is reported as `McpError::Timeout`
+    /// - Other HTTP errors (400, 401, ...) fail immediately as `McpError::Http`,
+    ///   carrying the start of the response body
+    /// - JSON parsing errors fail immediately as `McpError::Json`
+    /// - API-level errors (in response.error) become `McpError::LogseqApi`
+    async fn call_api(&self, method: &str, args: Vec<Value>) -> McpResult<Value> {
+        self.call_api_with_timeout(method, args, None).await
+    }
+
+    /// Like `call_api`, but `timeout` (if set) overrides the client-wide
+    /// request timeout for this call.
+    async fn call_api_with_timeout(
+        &self,
+        method: &str,
+        args: Vec<Value>,
+        timeout: Option<Duration>,
+    ) -> McpResult<Value> {
+        let _timer = ApiCallTimer(Instant::now());
         let request = LogseqApiRequest {
             method: method.to_string(),
             args,
         };
 
-        let response = self
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            let args = serde_json::to_string(&request.args).unwrap_or_default();
+            tracing::debug!(method, args = %self.mask(&args), "Calling Logseq API");
+        }
+
+        let mut attempt = 0;
+        loop {
+            match self.send_request(&request, timeout).await {
+                Ok(result) => {
+                    if attempt > 0 {
+                        tracing::debug!(
+                            method,
+                            retries = attempt,
+                            "Logseq API call succeeded after retries"
+                        );
+                    }
+                    return Ok(result);
+                }
+                Err(e) if e.can_retry(method) && attempt < self.config.max_retries => {
+                    let delay = backoff_delay(self.config.initial_retry_delay_ms, attempt);
+                    attempt += 1;
+                    record_usage(|usage| usage.retries += 1);
+                    tracing::warn!(
+                        method,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %self.mask(&e.error.to_string()),
+                        "Retrying Logseq API call"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e.error),
+            }
+        }
+    }
+
+    /// Makes several API calls concurrently, returning results in input order.
+    ///
+    /// Logseq's HTTP API has no batch endpoint, so each call is still its own
+    /// request (with `call_api`'s retries), but at most
+    /// `max_concurrent_requests` of them are in flight at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `calls` - `(method, args)` pairs, as passed to `call_api`
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first error to arrive; calls still in flight are
+    /// cancelled and their results discarded.
+    pub async fn call_api_batch(&self, calls: Vec<(String, Vec<Value>)>) -> McpResult<Vec<Value>> {
+        let mut results = vec![Value::Null; calls.len()];
+        let mut responses = stream::iter(calls.into_iter().enumerate())
+            .map(|(i, (method, args))| async move { (i, self.call_api(&method, args).await) })
+            .buffer_unordered(self.config.max_concurrent_requests.max(1));
+        while let Some((i, result)) = responses.next().await {
+            results[i] = result?;
+        }
+        Ok(results)
+    }
+
+    /// Redacts the API token from text that is about to be logged.
+    fn mask(&self, s: &str) -> String {
+        mask_token(s, &self.config.logseq_api_token)
+    }
+
+    /// Performs a single HTTP round-trip and classifies any failure.
+    async fn send_request(
+        &self,
+        request: &LogseqApiRequest,
+        timeout: Option<Duration>,
+    ) -> std::result::Result<Value, AttemptError> {
+        let mut builder = self
             .client
-            .post(format!("{}/api", self.config.logseq_api_url))
-            .json(&request)
-            .send()
-            .await?;
+            .post(format!("{}/api", self.config.logseq_api_url));
+        builder = if self.config.request_compression {
+            builder
+                .header(CONTENT_TYPE, "application/json")
+                .header(CONTENT_ENCODING, "gzip")
+                .body(gzip_json(request).map_err(AttemptError::fatal)?)
+        } else {
+            builder.json(request)
+        };
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            if e.is_timeout() {
+                AttemptError::retryable(McpError::Timeout(format!(
+                    "{} did not respond in time",
+                    request.method
+                )))
+            } else if e.is_connect() {
+                AttemptError::unsent(e)
+            } else {
+                AttemptError::fatal(e)
+            }
+        })?;
+
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            let source = response.error_for_status_ref().unwrap_err();
+            // Logseq explains most rejections in the body, e.g. an unknown method
+            let body = response
+                .text()
+                .await
+                .ok()
+                .map(|text| {
+                    self.mask(text.trim())
+                        .chars()
+                        .take(MAX_ERROR_BODY_CHARS)
+                        .collect::<String>()
+                })
+                .filter(|text| !text.is_empty());
+            let error = McpError::Http { source, body };
+            return Err(if status.is_server_error() {
+                AttemptError::retryable(error)
+            } else {
+                AttemptError::fatal(error)
+            });
+        }
 
         // The Logseq API returns the result directly, not wrapped in an object
-        let result: Value = response.json().await?;
+        let body = response.bytes().await.map_err(AttemptError::fatal)?;
+        let result: Value = serde_json::from_slice(&body).map_err(AttemptError::fatal)?;
 
         // Check if it's an error response from the Logseq API
         if let Some(error) = result.get("error") {
-            anyhow::bail!("Logseq API error: {}", error);
+            return Err(AttemptError::fatal(McpError::LogseqApi {
+                method: request.method.clone(),
+                message: error.to_string(),
+            }));
         }
 
         Ok(result)
@@ -119,24 +407,70 @@ impl LogseqClient {
     // =============================================================================
     // These methods retrieve data from Logseq without modifying anything
 
+    /// Checks that the Logseq HTTP API is reachable and accepts our token.
+    ///
+    /// Used at startup to fail fast instead of erroring on the first tool call.
+    /// Returns the current graph on success.
+    pub async fn test_connection(&self) -> McpResult<Value> {
+        self.get_current_graph().await.map_err(|e| {
+            McpError::Internal(format!(
+                "Cannot connect to Logseq at {}. Ensure Logseq is running with HTTP API \
+                 enabled on that address. ({})",
+                self.config.logseq_api_url, e
+            ))
+        })
+    }
+
     /// Gets information about the currently active graph.
     ///
     /// Returns metadata about the current graph including its name, path,
     /// and other configuration details. Useful for understanding the context
     /// of other operations.
-    pub async fn get_current_graph(&self) -> Result<Value> {
+    pub async fn get_current_graph(&self) -> McpResult<Value> {
         self.call_api("logseq.App.getCurrentGraph", vec![]).await
     }
 
+    /// Gets information about the running Logseq app, such as its version.
+    pub async fn get_app_info(&self) -> McpResult<Value> {
+        self.call_api("logseq.App.getInfo", vec![]).await
+    }
+
+    /// Gets the current graph's favorite pages, in the user's order.
+    ///
+    /// Logseq returns page names; entries may be page objects on some
+    /// versions.
+    pub async fn get_favorites(&self) -> McpResult<Value> {
+        self.call_api("logseq.App.getCurrentGraphFavorites", vec![])
+            .await
+    }
+
+    /// Gets the user's Logseq settings.
+    ///
+    /// Returns the app-level configuration (preferred format, date format,
+    /// language, workflow, current graph, ...) as reported by
+    /// `logseq.App.getUserConfigs`.
+    pub async fn get_user_configs(&self) -> McpResult<Value> {
+        self.call_api("logseq.App.getUserConfigs", vec![]).await
+    }
+
     /// Retrieves a list of all pages in the current graph.
     ///
     /// Returns an array of page objects, each containing page metadata
     /// like name, creation date, and other properties. This is useful
     /// for getting an overview of all content in the graph.
-    pub async fn get_all_pages(&self) -> Result<Value> {
+    pub async fn get_all_pages(&self) -> McpResult<Value> {
         self.call_api("logseq.Editor.getAllPages", vec![]).await
     }
 
+    /// Retrieves all pages with a custom request timeout.
+    ///
+    /// Listing every page of a large graph can outlast `request_timeout_ms`;
+    /// see [`LogseqClient::search_with_timeout`].
+    pub async fn get_all_pages_with_timeout(&self, timeout: Duration) -> McpResult<Value> {
+        self.call_api_with_timeout("logseq.Editor.getAllPages", vec![], Some(timeout))
+            .await
+    }
+
     /// Gets detailed information about a specific page by name.
     ///
     /// # Arguments
@@ -147,7 +481,7 @@ impl LogseqClient {
     ///
     /// Page object containing metadata and properties, or an error if
     /// the page doesn't exist or cannot be accessed.
-    pub async fn get_page(&self, page_name: &str) -> Result<Value> {
+    pub async fn get_page(&self, page_name: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.getPage",
             vec![Value::String(page_name.to_string())],
@@ -164,7 +498,7 @@ impl LogseqClient {
     /// # Arguments
     ///
     /// * `page_name` - The name of the page whose blocks to retrieve
-    pub async fn get_page_blocks_tree(&self, page_name: &str) -> Result<Value> {
+    pub async fn get_page_blocks_tree(&self, page_name: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.getPageBlocksTree",
             vec![Value::String(page_name.to_string())],
@@ -182,7 +516,7 @@ impl LogseqClient {
     ///
     /// Block object containing content, properties, parent/child relationships,
     /// and other block metadata.
-    pub async fn get_block(&self, uuid: &str) -> Result<Value> {
+    pub async fn get_block(&self, uuid: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.getBlock",
             vec![Value::String(uuid.to_string())],
@@ -190,6 +524,75 @@ impl LogseqClient {
         .await
     }
 
+    /// Retrieves a block, optionally together with its full tree of child blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - The UUID of the root block to retrieve
+    /// * `include_children` - If true, `children` holds nested block objects;
+    ///   if false, children are only listed as `["uuid", ...]` references
+    pub async fn get_block_with_tree(
+        &self,
+        uuid: &str,
+        include_children: bool,
+    ) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.getBlock",
+            vec![
+                Value::String(uuid.to_string()),
+                serde_json::json!({ "includeChildren": include_children }),
+            ],
+        )
+        .await
+    }
+
+    /// Retrieves a block by its internal database id.
+    ///
+    /// Block objects reference their `parent` and `page` by db id
+    /// (`{"id": 123}`) rather than UUID, so this is needed to follow them.
+    pub async fn get_block_by_id(&self, id: i64) -> McpResult<Value> {
+        self.call_api("logseq.Editor.getBlock", vec![Value::from(id)])
+            .await
+    }
+
+    /// Retrieves a page by its internal database id.
+    ///
+    /// Blocks reference their page as `{"id": 123}`; this resolves that to
+    /// the full page entity, including its name.
+    pub async fn get_page_by_id(&self, id: i64) -> McpResult<Value> {
+        self.call_api("logseq.Editor.getPage", vec![Value::from(id)])
+            .await
+    }
+
+    /// Gets the immediate children of a block as full block objects.
+    ///
+    /// `getBlock` without `includeChildren` only lists child UUIDs, so each
+    /// child is then fetched with [`Self::get_blocks`].
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - The UUID of the parent block
+    pub async fn get_block_children(&self, uuid: &str) -> McpResult<Value> {
+        let block = self.get_block(uuid).await?;
+        let children = self.get_blocks(&child_block_uuids(&block)).await?;
+        Ok(Value::Array(children))
+    }
+
+    /// Fetches several blocks through [`Self::call_api_batch`], in the order
+    /// of `uuids`. Missing blocks come back as null.
+    pub async fn get_blocks(&self, uuids: &[String]) -> McpResult<Vec<Value>> {
+        let calls = uuids
+            .iter()
+            .map(|uuid| {
+                (
+                    "logseq.Editor.getBlock".to_string(),
+                    vec![Value::String(uuid.clone())],
+                )
+            })
+            .collect();
+        self.call_api_batch(calls).await
+    }
+
     /// Searches across all content in the current graph.
     ///
     /// Uses Logseq's built-in search functionality which provides better
@@ -204,11 +607,24 @@ impl LogseqClient {
     ///
     /// Array of search results with matching blocks and pages, ranked
     /// by relevance according to Logseq's search algorithm.
-    pub async fn search(&self, query: &str) -> Result<Value> {
+    pub async fn search(&self, query: &str) -> McpResult<Value> {
         self.call_api("logseq.App.search", vec![Value::String(query.to_string())])
             .await
     }
 
+    /// Performs a full-text search with a custom request timeout.
+    ///
+    /// Searches over large graphs can take much longer than other calls;
+    /// this lets a caller allow more (or less) time than `request_timeout_ms`.
+    pub async fn search_with_timeout(&self, query: &str, timeout: Duration) -> McpResult<Value> {
+        self.call_api_with_timeout(
+            "logseq.App.search",
+            vec![Value::String(query.to_string())],
+            Some(timeout),
+        )
+        .await
+    }
+
     // =============================================================================
     // Mutation Operations
     // =============================================================================
@@ -230,7 +646,7 @@ impl LogseqClient {
     ///
     /// Content is created in markdown format when provided. If no content
     /// is specified, an empty page is created.
-    pub async fn create_page(&self, page_name: &str, content: Option<&str>) -> Result<Value> {
+    pub async fn create_page(&self, page_name: &str, content: Option<&str>) -> McpResult<Value> {
         let mut args = vec![Value::String(page_name.to_string())];
         if let Some(content) = content {
             args.push(serde_json::json!({
@@ -241,6 +657,29 @@ impl LogseqClient {
         self.call_api("logseq.Editor.createPage", args).await
     }
 
+    /// Creates a journal page, flagged as a journal so it shows in the
+    /// Journals view and gets a `journalDay`.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_name` - The journal page name, already in the graph's date format
+    ///
+    /// # Returns
+    ///
+    /// The created page object. If the page already exists Logseq returns
+    /// the existing page.
+    pub async fn create_journal_page(&self, page_name: &str) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.createPage",
+            vec![
+                Value::String(page_name.to_string()),
+                serde_json::json!({}),
+                serde_json::json!({ "journal": true, "redirect": false }),
+            ],
+        )
+        .await
+    }
+
     /// Inserts a new block into the graph.
     ///
     /// # Arguments
@@ -262,13 +701,40 @@ impl LogseqClient {
         parent_uuid: &str,
         content: &str,
         sibling: bool,
-    ) -> Result<Value> {
+    ) -> McpResult<Value> {
+        self.insert_block_with_opts(
+            parent_uuid,
+            content,
+            serde_json::json!({ "sibling": sibling }),
+        )
+        .await
+    }
+
+    /// Inserts a new block with raw `insertBlock` options.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_uuid` - UUID of the block or page to insert relative to
+    /// * `content` - The text content for the new block
+    /// * `opts` - Options passed through to Logseq, e.g.
+    ///   `{"sibling": true, "before": true}` to insert directly before
+    ///   `parent_uuid` at the same level
+    ///
+    /// # Returns
+    ///
+    /// The created block object with its new UUID and metadata.
+    pub async fn insert_block_with_opts(
+        &self,
+        parent_uuid: &str,
+        content: &str,
+        opts: Value,
+    ) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.insertBlock",
             vec![
                 Value::String(parent_uuid.to_string()),
                 Value::String(content.to_string()),
-                serde_json::json!({ "sibling": sibling }),
+                opts,
             ],
         )
         .await
@@ -291,7 +757,7 @@ impl LogseqClient {
     /// This completely replaces the block's content. To append or modify
     /// part of the content, retrieve the current content first, modify it,
     /// then call this method with the full new content.
-    pub async fn update_block(&self, uuid: &str, content: &str) -> Result<Value> {
+    pub async fn update_block(&self, uuid: &str, content: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.updateBlock",
             vec![
@@ -302,6 +768,55 @@ impl LogseqClient {
         .await
     }
 
+    /// Moves an existing block to a new position, keeping its UUID.
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - The UUID of the block to move
+    /// * `target_uuid` - UUID of the block to move relative to
+    /// * `sibling` - If true, place after the target as a sibling; if false,
+    ///   place as a child of the target (same semantics as `insert_block`)
+    ///
+    /// # Notes
+    ///
+    /// Children of the moved block move with it. References to the block
+    /// stay valid because the UUID is unchanged.
+    pub async fn move_block(
+        &self,
+        uuid: &str,
+        target_uuid: &str,
+        sibling: bool,
+    ) -> McpResult<Value> {
+        self.move_block_with_opts(
+            uuid,
+            target_uuid,
+            serde_json::json!({ "before": false, "children": !sibling }),
+        )
+        .await
+    }
+
+    /// Moves an existing block with raw `moveBlock` options.
+    ///
+    /// `opts` is passed through to Logseq, e.g.
+    /// `{"before": true, "children": false}` to place the block directly
+    /// before `target_uuid` at the same level.
+    pub async fn move_block_with_opts(
+        &self,
+        uuid: &str,
+        target_uuid: &str,
+        opts: Value,
+    ) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.moveBlock",
+            vec![
+                Value::String(uuid.to_string()),
+                Value::String(target_uuid.to_string()),
+                opts,
+            ],
+        )
+        .await
+    }
+
     /// Deletes a block from the graph.
     ///
     /// # Arguments
@@ -317,7 +832,7 @@ impl LogseqClient {
     ///
     /// This operation is irreversible. The block and all its child blocks
     /// will be permanently removed from the graph.
-    pub async fn delete_block(&self, uuid: &str) -> Result<Value> {
+    pub async fn delete_block(&self, uuid: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.removeBlock",
             vec![Value::String(uuid.to_string())],
@@ -340,7 +855,7 @@ impl LogseqClient {
     ///
     /// This operation is irreversible. The page and all its blocks
     /// will be permanently removed from the graph.
-    pub async fn delete_page(&self, page_name: &str) -> Result<Value> {
+    pub async fn delete_page(&self, page_name: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.deletePage",
             vec![Value::String(page_name.to_string())],
@@ -348,6 +863,28 @@ impl LogseqClient {
         .await
     }
 
+    /// Renames a page, updating every `[[link]]` that points to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_name` - The current name of the page
+    /// * `new_name` - The name to rename the page to
+    ///
+    /// # Returns
+    ///
+    /// The raw API result, or an error if the page doesn't exist or
+    /// a page named `new_name` already exists.
+    pub async fn rename_page(&self, old_name: &str, new_name: &str) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.renamePage",
+            vec![
+                Value::String(old_name.to_string()),
+                Value::String(new_name.to_string()),
+            ],
+        )
+        .await
+    }
+
     /// Appends a new block to the end of a page.
     ///
     /// # Arguments
@@ -364,7 +901,7 @@ impl LogseqClient {
     ///
     /// This is a convenience method that adds a block at the bottom of
     /// a page without needing to know the UUIDs of existing blocks.
-    pub async fn append_block_in_page(&self, page_name: &str, content: &str) -> Result<Value> {
+    pub async fn append_block_in_page(&self, page_name: &str, content: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.appendBlockInPage",
             vec![
@@ -384,26 +921,92 @@ impl LogseqClient {
     /// Logseq is built on Datascript, and this exposes its full query engine.
     /// Queries use Datalog syntax, e.g.:
     /// `[:find ?n :where [?b :block/name ?n]]` — finds all page names.
-    pub async fn datascript_query(&self, query: &str) -> Result<Value> {
+    ///
+    /// `bindings` are passed as extra inputs after the query, matching the
+    /// query's `:in` clause. String inputs are read as EDN by Logseq.
+    pub async fn datascript_query(&self, query: &str, bindings: Vec<Value>) -> McpResult<Value> {
+        let mut args = vec![Value::String(query.to_string())];
+        args.extend(bindings);
+        self.call_api("logseq.DB.datascriptQuery", args).await
+    }
+
+    /// Gets all blocks scheduled or with a deadline within a date range.
+    ///
+    /// Logseq stores `SCHEDULED:` and `DEADLINE:` dates as integers in
+    /// `yyyyMMdd` form, so the bounds are compared numerically. Both bounds
+    /// are inclusive. Each result is a pulled block with its page name.
+    pub async fn get_scheduled_blocks(&self, start: NaiveDate, end: NaiveDate) -> McpResult<Value> {
+        let start = start.format("%Y%m%d");
+        let end = end.format("%Y%m%d");
+        let query = format!(
+            "[:find (pull ?b [* {{:block/page [:block/original-name]}}]) \
+             :where (or [?b :block/scheduled ?d] [?b :block/deadline ?d]) \
+             [(>= ?d {start})] [(<= ?d {end})]]"
+        );
+        self.datascript_query(&query, Vec::new()).await
+    }
+
+    /// Checks whether a template with this name exists in the graph.
+    pub async fn template_exists(&self, template_name: &str) -> McpResult<bool> {
+        let exists = self
+            .call_api(
+                "logseq.App.existTemplate",
+                vec![Value::String(template_name.to_string())],
+            )
+            .await?;
+        Ok(exists.as_bool().unwrap_or(false))
+    }
+
+    /// Inserts a template's blocks after the target block.
+    ///
+    /// Returns whatever Logseq reports for the insertion, which may be null;
+    /// use [`Self::template_exists`] to check the template first.
+    pub async fn insert_template(
+        &self,
+        target_uuid: &str,
+        template_name: &str,
+    ) -> McpResult<Value> {
         self.call_api(
-            "logseq.DB.datascriptQuery",
-            vec![Value::String(query.to_string())],
+            "logseq.App.insertTemplate",
+            vec![
+                Value::String(target_uuid.to_string()),
+                Value::String(template_name.to_string()),
+            ],
         )
         .await
     }
 
+    /// Gets the blocks that reference a block via `((uuid))`.
+    ///
+    /// Logseq's block object only lists outgoing `:block/refs`, so incoming
+    /// references are found with a Datascript query. `uuid` is spliced into
+    /// the query as a `#uuid` literal, so it is checked to be a valid UUID
+    /// first. Each result is a pulled block with its page name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `McpError::InvalidParams` if `uuid` isn't a block UUID.
+    pub async fn get_block_refs(&self, uuid: &str) -> McpResult<Value> {
+        require_block_uuid("uuid", uuid)?;
+        let query = format!(
+            "[:find (pull ?b [:block/uuid :block/content {{:block/page [:block/original-name]}}]) \
+             :where [?t :block/uuid #uuid \"{uuid}\"] [?b :block/refs ?t]]"
+        );
+        self.datascript_query(&query, Vec::new()).await
+    }
+
     /// Gets the graph's configured date formatter string.
     ///
     /// Used to determine the correct page name for journal pages.
     /// Returns a format string like "MMM do, yyyy" or "yyyy-MM-dd".
-    pub async fn get_date_formatter(&self) -> Result<Value> {
+    pub async fn get_date_formatter(&self) -> McpResult<Value> {
         let config = self.call_api("logseq.App.getUserConfigs", vec![]).await?;
         // Extract the preferredDateFormat field from the config
         Ok(config["preferredDateFormat"].clone())
     }
 
     /// Gets all properties for a specific block.
-    pub async fn get_block_properties(&self, uuid: &str) -> Result<Value> {
+    pub async fn get_block_properties(&self, uuid: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.getBlockProperties",
             vec![Value::String(uuid.to_string())],
@@ -412,7 +1015,12 @@ impl LogseqClient {
     }
 
     /// Sets (upserts) a property on a block.
-    pub async fn upsert_block_property(&self, uuid: &str, key: &str, value: &str) -> Result<Value> {
+    pub async fn upsert_block_property(
+        &self,
+        uuid: &str,
+        key: &str,
+        value: &str,
+    ) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.upsertBlockProperty",
             vec![
@@ -424,8 +1032,24 @@ impl LogseqClient {
         .await
     }
 
+    /// Sets (inserts or updates) several page-level properties at once.
+    ///
+    /// `properties` is a JSON object of key → value pairs; keys not present
+    /// are left untouched.
+    pub async fn upsert_page_properties(
+        &self,
+        page_name: &str,
+        properties: Value,
+    ) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.upsertPageProperties",
+            vec![Value::String(page_name.to_string()), properties],
+        )
+        .await
+    }
+
     /// Removes a property from a block.
-    pub async fn remove_block_property(&self, uuid: &str, key: &str) -> Result<Value> {
+    pub async fn remove_block_property(&self, uuid: &str, key: &str) -> McpResult<Value> {
         self.call_api(
             "logseq.Editor.removeBlockProperty",
             vec![
@@ -436,12 +1060,672 @@ impl LogseqClient {
         .await
     }
 
-    /// Gets all blocks that link to the given page (backlinks).
-    pub async fn get_page_linked_references(&self, page_name: &str) -> Result<Value> {
+    /// Sets the `icon` property on a page (an emoji or icon code).
+    pub async fn set_page_icon(&self, page_name: &str, icon: &str) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.setPageProperty",
+            vec![
+                Value::String(page_name.to_string()),
+                Value::String("icon".to_string()),
+                Value::String(icon.to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// Gets all page-level properties (the first block's `key:: value` lines).
+    ///
+    /// Returns null when the page has no properties or doesn't exist.
+    pub async fn get_page_properties(&self, page_name: &str) -> McpResult<Value> {
         self.call_api(
-            "logseq.Editor.getPageLinkedReferences",
+            "logseq.Editor.getPageProperties",
             vec![Value::String(page_name.to_string())],
         )
         .await
     }
+
+    /// Gets the `icon` property of a page, or null if it has none.
+    pub async fn get_page_icon(&self, page_name: &str) -> McpResult<Value> {
+        self.call_api(
+            "logseq.Editor.getPageProperty",
+            vec![
+                Value::String(page_name.to_string()),
+                Value::String("icon".to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// Gets all blocks that link to the given page (backlinks).
+    ///
+    /// Logseq returns null for pages nobody links to; that is normalized
+    /// to an empty array so callers only ever see `[[page, [blocks]], ...]`.
+    pub async fn get_linked_references(&self, page_name: &str) -> McpResult<Value> {
+        let refs = self
+            .call_api(
+                "logseq.Editor.getPageLinkedReferences",
+                vec![Value::String(page_name.to_string())],
+            )
+            .await?;
+        Ok(if refs.is_null() {
+            Value::Array(Vec::new())
+        } else {
+            refs
+        })
+    }
+}
+
+/// Serializes an API request as gzip-compressed JSON.
+fn gzip_json(request: &LogseqApiRequest) -> McpResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, request)?;
+    encoder
+        .finish()
+        .map_err(|e| McpError::Internal(format!("Failed to compress request: {}", e)))
+}
+
+/// Exponential backoff for retry `attempt` (0-based), plus up to 50% jitter
+/// so concurrent callers don't retry in lockstep.
+fn backoff_delay(initial_ms: u64, attempt: u32) -> Duration {
+    let base = initial_ms.saturating_mul(1 << attempt.min(16));
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let jitter = if base == 0 { 0 } else { nanos % (base / 2 + 1) };
+    Duration::from_millis(base + jitter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_client(server: &MockServer) -> LogseqClient {
+        LogseqClient::new(Config {
+            logseq_api_token: "test-token".to_string(),
+            logseq_api_url: server.uri(),
+            ..Config::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_move_block_sends_child_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(header("authorization", "Bearer test-token"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.moveBlock",
+                "args": ["src-uuid", "target-uuid", {"before": false, "children": true}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = test_client(&server)
+            .move_block("src-uuid", "target-uuid", false)
+            .await
+            .unwrap();
+        assert!(result.is_null());
+    }
+
+    #[tokio::test]
+    async fn test_move_block_sibling_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.moveBlock",
+                "args": ["src-uuid", "target-uuid", {"before": false, "children": false}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        test_client(&server)
+            .move_block("src-uuid", "target-uuid", true)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_move_block_surfaces_api_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"error": "Target block is in another graph"})),
+            )
+            .mount(&server)
+            .await;
+
+        let err = test_client(&server)
+            .move_block("src-uuid", "other-graph-uuid", false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("another graph"));
+    }
+
+    #[tokio::test]
+    async fn test_rename_page_surfaces_existing_name_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.renamePage",
+                "args": ["Old", "Existing"]
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"error": "page already exists"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let err = test_client(&server)
+            .rename_page("Old", "Existing")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[tokio::test]
+    async fn test_get_linked_references_normalizes_null() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.getPageLinkedReferences",
+                "args": ["Lonely Page"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .mount(&server)
+            .await;
+
+        let refs = test_client(&server)
+            .get_linked_references("Lonely Page")
+            .await
+            .unwrap();
+        assert_eq!(refs, json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_connection_reports_unreachable_server() {
+        let client = LogseqClient::new(Config {
+            logseq_api_token: "test-token".to_string(),
+            // Port 9 (discard) is not expected to have an HTTP server
+            logseq_api_url: "http://127.0.0.1:9".to_string(),
+            max_retries: 0,
+            ..Config::default()
+        })
+        .unwrap();
+        let err = client.test_connection().await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Cannot connect to Logseq at http://127.0.0.1:9")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_succeeds_against_live_api() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(
+                json!({"method": "logseq.App.getCurrentGraph", "args": []}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "notes"})))
+            .mount(&server)
+            .await;
+        let graph = test_client(&server).test_connection().await.unwrap();
+        assert_eq!(graph["name"], "notes");
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_exponentially() {
+        for attempt in 0..4 {
+            let base = 100 * (1 << attempt);
+            let delay = backoff_delay(100, attempt).as_millis() as u64;
+            assert!(
+                delay >= base && delay <= base + base / 2,
+                "attempt {attempt}: {delay}ms"
+            );
+        }
+        assert_eq!(backoff_delay(0, 3), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_call_api_retries_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "notes"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            initial_retry_delay_ms: 1,
+            ..Config::default()
+        })
+        .unwrap();
+        let graph = client.get_current_graph().await.unwrap();
+        assert_eq!(graph["name"], "notes");
+    }
+
+    #[tokio::test]
+    async fn test_call_api_does_not_retry_client_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad token"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            initial_retry_delay_ms: 1,
+            ..Config::default()
+        })
+        .unwrap();
+        let err = client.get_current_graph().await.unwrap_err();
+        assert!(err.to_string().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_timeout_reports_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([]))
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            max_retries: 0,
+            ..Config::default()
+        })
+        .unwrap();
+        let err = client
+            .search_with_timeout("slow", Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::Timeout(_)));
+    }
+
+    #[tokio::test]
+    async fn test_call_api_maps_error_variants() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"error": "nope"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        match client.get_page("x").await.unwrap_err() {
+            McpError::LogseqApi { method, message } => {
+                assert_eq!(method, "logseq.Editor.getPage");
+                assert!(message.contains("nope"));
+            }
+            other => panic!("expected LogseqApi, got {other:?}"),
+        }
+        assert!(matches!(
+            client.get_current_graph().await.unwrap_err(),
+            McpError::Json(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_refs_queries_incoming_refs() {
+        let uuid = "6650a1b2-0000-4000-8000-000000000001";
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.DB.datascriptQuery",
+                "args": [format!(
+                    "[:find (pull ?b [:block/uuid :block/content {{:block/page [:block/original-name]}}]) \
+                     :where [?t :block/uuid #uuid \"{uuid}\"] [?b :block/refs ?t]]"
+                )]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([[{"uuid": "r1"}]])))
+            .mount(&server)
+            .await;
+
+        let refs = test_client(&server).get_block_refs(uuid).await.unwrap();
+        assert_eq!(refs, json!([[{"uuid": "r1"}]]));
+    }
+
+    #[tokio::test]
+    async fn test_insert_block_with_opts_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.insertBlock",
+                "args": ["ref-uuid", "New", {"sibling": true, "before": true}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "new"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let block = test_client(&server)
+            .insert_block_with_opts("ref-uuid", "New", json!({"sibling": true, "before": true}))
+            .await
+            .unwrap();
+        assert_eq!(block["uuid"], "new");
+    }
+
+    #[tokio::test]
+    async fn test_measure_api_usage_counts_calls() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([]))
+                    .set_delay(Duration::from_millis(50)),
+            )
+            .mount(&server)
+            .await;
+        let client = test_client(&server);
+
+        let (result, usage) = measure_api_usage(async {
+            client.get_all_pages().await.unwrap();
+            client.get_all_pages().await
+        })
+        .await;
+        assert!(result.is_ok());
+        assert!(usage.time >= Duration::from_millis(100));
+        assert_eq!(usage.retries, 0);
+
+        // Outside a scope, calls still work and nothing is recorded
+        client.get_all_pages().await.unwrap();
+        let ((), none) = measure_api_usage(async {}).await;
+        assert_eq!(none, ApiUsage::default());
+    }
+
+    #[tokio::test]
+    async fn test_call_api_batch_keeps_input_order() {
+        let server = MockServer::start().await;
+        for (name, delay_ms) in [("slow", 150), ("fast", 0)] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(json!({"args": [name]})))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({"name": name}))
+                        .set_delay(Duration::from_millis(delay_ms)),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let calls = ["slow", "fast", "fast"]
+            .iter()
+            .map(|name| ("logseq.Editor.getPage".to_string(), vec![json!(name)]))
+            .collect();
+        let results = test_client(&server).call_api_batch(calls).await.unwrap();
+        let names: Vec<&str> = results
+            .iter()
+            .map(|r| r["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["slow", "fast", "fast"]);
+
+        let empty = test_client(&server)
+            .call_api_batch(Vec::new())
+            .await
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_call_api_batch_limits_concurrency() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!(null))
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            max_concurrent_requests: 2,
+            ..Config::default()
+        })
+        .unwrap();
+        let calls = vec![("logseq.Editor.getPage".to_string(), vec![json!("p")]); 4];
+        let started = Instant::now();
+        client.call_api_batch(calls).await.unwrap();
+        // Two rounds of two concurrent calls
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_call_api_batch_fails_on_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"args": ["bad"]})))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .mount(&server)
+            .await;
+
+        let calls = ["ok", "bad"]
+            .iter()
+            .map(|name| ("logseq.Editor.getPage".to_string(), vec![json!(name)]))
+            .collect();
+        let err = test_client(&server)
+            .call_api_batch(calls)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::Http { .. }));
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_gzip_response_is_decoded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(|request: &wiremock::Request| {
+                request.headers.get("accept-encoding").map(|v| v.as_bytes()) == Some(b"gzip, br")
+            })
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "gzip")
+                    .insert_header("content-type", "application/json")
+                    .set_body_bytes(gzip(br#"{"name": "compressed"}"#)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let page = test_client(&server).get_page("compressed").await.unwrap();
+        assert_eq!(page, json!({"name": "compressed"}));
+    }
+
+    #[tokio::test]
+    async fn test_accept_encoding_is_configurable() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("accept-encoding", "identity"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            accept_encoding: "identity".to_string(),
+            ..Config::default()
+        })
+        .unwrap();
+        client.get_page("p").await.unwrap();
+
+        let invalid = LogseqClient::new(Config {
+            accept_encoding: "gzip\n".to_string(),
+            ..Config::default()
+        });
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_compression_gzips_body() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("content-encoding", "gzip"))
+            .and(header("content-type", "application/json"))
+            .and(|request: &wiremock::Request| {
+                let mut body = String::new();
+                GzDecoder::new(request.body.as_slice())
+                    .read_to_string(&mut body)
+                    .is_ok_and(|_| {
+                        serde_json::from_str::<Value>(&body).ok()
+                            == Some(json!({"method": "logseq.Editor.getPage", "args": ["p"]}))
+                    })
+            })
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "p"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            request_compression: true,
+            ..Config::default()
+        })
+        .unwrap();
+        assert_eq!(client.get_page("p").await.unwrap(), json!({"name": "p"}));
+    }
+
+    #[tokio::test]
+    async fn test_call_api_does_not_retry_mutations_after_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            initial_retry_delay_ms: 1,
+            ..Config::default()
+        })
+        .unwrap();
+        let (result, usage) = measure_api_usage(client.append_block_in_page("p", "x")).await;
+        assert!(result.is_err());
+        assert_eq!(usage.retries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_measure_api_usage_counts_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .mount(&server)
+            .await;
+
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            initial_retry_delay_ms: 1,
+            ..Config::default()
+        })
+        .unwrap();
+        let (result, usage) = measure_api_usage(client.get_page("p")).await;
+        assert!(result.is_ok());
+        assert_eq!(usage.retries, 2);
+    }
+
+    #[test]
+    fn test_is_read_method() {
+        assert!(is_read_method("logseq.Editor.getPageBlocksTree"));
+        assert!(is_read_method("logseq.DB.datascriptQuery"));
+        assert!(is_read_method("logseq.App.existTemplate"));
+        assert!(!is_read_method("logseq.Editor.insertBlock"));
+        assert!(!is_read_method("logseq.Editor.createPage"));
+        assert!(!is_read_method("logseq.Editor.moveBlock"));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_refs_rejects_non_uuid() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let err = test_client(&server)
+            .get_block_refs(r#"x"] [?b :block/content ?c]] ["#)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::InvalidParams(_)));
+    }
+
+    #[tokio::test]
+    async fn test_client_error_includes_masked_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_string("MethodNotExist: logseq.Editor.nope (token test-token)\n"),
+            )
+            .mount(&server)
+            .await;
+
+        let err = test_client(&server)
+            .call_api("logseq.Editor.nope", vec![])
+            .await
+            .unwrap_err();
+        let McpError::Http { body, .. } = &err else {
+            panic!("expected an HTTP error, got {err:?}");
+        };
+        assert_eq!(
+            body.as_deref(),
+            Some("MethodNotExist: logseq.Editor.nope (token [REDACTED])")
+        );
+        assert!(err.to_string().contains("400 Bad Request"));
+        assert!(
+            err.to_string()
+                .ends_with(": MethodNotExist: logseq.Editor.nope (token [REDACTED])")
+        );
+    }
 }
@@ -9,7 +9,9 @@
 //!
 //! The server implements the MCP protocol over JSON-RPC via stdin/stdout,
 //! allowing it to be used by MCP-compatible clients like Claude Desktop,
-//! IDEs, and other development tools.
+//! IDEs, and other development tools. Setting `TRANSPORT=http` serves the
+//! same protocol over HTTP `POST /` instead; that transport has no
+//! authentication, so only expose it on trusted networks.
 //!
 //! ## Features
 //!
@@ -19,24 +21,38 @@
 //! - Error handling with graceful degradation
 //! - Configurable via environment variables
 
-mod config;
-mod logseq_client;
-mod models;
-mod protocol;
-mod tools;
-
 use anyhow::Result;
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
 use serde_json::{Value, json};
-use std::io::Write;
-use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, BufReader};
+use tokio::sync::oneshot;
+use tokio::task::{JoinError, JoinHandle, JoinSet};
 use tracing_subscriber::EnvFilter;
 
-use crate::{
-    config::Config,
-    logseq_client::LogseqClient,
-    protocol::{HandlerResponse, JsonRpcRequest, ResponseBuilder, error_codes, parse_request},
-    tools::{mutate, query},
+use mcp_logseq_rust::{
+    audit::AuditLogger,
+    config::{Config, Transport},
+    error::McpError,
+    logseq_client::{LogseqClient, measure_api_usage},
+    progress::{ProgressReporter, SharedWriter},
+    prompts,
+    protocol::{
+        HandlerResponse, JsonRpcRequest, LimitedReader, ReadLine, ResponseBuilder, error_codes,
+        generate_request_id, parse_request,
+    },
+    resources,
+    tools::registry::ToolRegistry,
+    utils::{self, Timer},
+    version::get_server_info,
 };
 
 /// Main entry point for the MCP Logseq server.
@@ -55,49 +71,199 @@ async fn main() -> Result<()> {
         .with_level(true)
         .with_writer(std::io::stderr)
         .init();
+    install_panic_hook();
 
     // Load configuration from environment variables
     let config = Config::from_env()?;
-    let client = Arc::new(LogseqClient::new(config)?);
+    let client = Arc::new(LogseqClient::new(config.clone())?);
 
     tracing::info!("MCP Logseq Server starting...");
+    if config.read_only {
+        tracing::info!("Read-only mode enabled; mutation tools are disabled");
+    }
+    if let Some(allowed) = &config.allowed_tools {
+        let mut names: Vec<_> = ToolRegistry::global()
+            .available_tools(&config)
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        names.sort();
+        tracing::info!(
+            "ALLOWED_TOOLS restricts tools to: [{}] ({} requested)",
+            names.join(", "),
+            allowed.len()
+        );
+    }
 
     // Verify Logseq is reachable before entering the server loop
-    if let Err(e) = client.get_current_graph().await {
-        eprintln!(
-            "[ERROR] Cannot connect to Logseq: {}. Is Logseq running with HTTP API enabled?",
-            e
+    if config.skip_startup_check {
+        tracing::info!("LOGSEQ_SKIP_STARTUP_CHECK set; not checking the Logseq connection");
+    } else if let Err(e) = verify_logseq_connection(&client).await {
+        tracing::error!(
+            "{}",
+            utils::mask_token(&e.to_string(), &config.logseq_api_token)
         );
         std::process::exit(1);
     }
-    tracing::info!("Connected to Logseq successfully");
 
-    // Start the MCP server loop
-    run_mcp_server(client).await?;
+    let audit = config
+        .audit_log_file
+        .as_deref()
+        .map(|path| AuditLogger::open(path, &config.logseq_api_token))
+        .transpose()?;
+    if let Some(path) = &config.audit_log_file {
+        tracing::info!("Recording mutation tool calls to {}", path.display());
+    }
+
+    // Start the MCP server loop on the configured transport
+    run_transport(&config, client, audit).await
+}
+
+/// How long the startup connection check waits for Logseq to answer.
+const STARTUP_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Checks that Logseq answers within [`STARTUP_CHECK_TIMEOUT`] and logs the
+/// current graph's name.
+///
+/// # Errors
+///
+/// Returns an error naming the configured URL if Logseq can't be reached,
+/// rejects the token, or doesn't answer in time.
+async fn verify_logseq_connection(client: &LogseqClient) -> Result<()> {
+    let url = &client.config().logseq_api_url;
+    let graph = tokio::time::timeout(STARTUP_CHECK_TIMEOUT, client.test_connection())
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Cannot connect to Logseq at {}. Ensure Logseq is running with HTTP API \
+                 enabled on that address. (no response within {:?})",
+                url,
+                STARTUP_CHECK_TIMEOUT
+            )
+        })??;
+    tracing::info!(
+        "Connected to Logseq at {} (graph: {})",
+        url,
+        graph["name"].as_str().unwrap_or("unknown")
+    );
     Ok(())
 }
 
+/// Logs panics through `tracing` before the default hook prints them, so
+/// they show up alongside the server's other log output.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!("Panic: {}", info);
+        default_hook(info);
+    }));
+}
+
+/// Runs the server on the transport selected by `config.transport`.
+async fn run_transport(
+    config: &Config,
+    client: Arc<LogseqClient>,
+    audit: Option<AuditLogger>,
+) -> Result<()> {
+    match config.transport {
+        Transport::Stdio => {
+            let stdin = LimitedReader::new(
+                BufReader::new(tokio::io::stdin()),
+                config.max_request_size_bytes,
+            );
+            let stdout: SharedWriter = Arc::new(Mutex::new(std::io::stdout()));
+            let drain_timeout = Duration::from_millis(config.shutdown_timeout_ms);
+            run_mcp_server(client, audit, stdin, stdout, drain_timeout).await
+        }
+        Transport::Http => {
+            run_http_server(client, audit, &config.http_host, config.http_port).await
+        }
+    }
+}
+
+/// In-flight requests that can be cancelled, keyed by the JSON text of their
+/// id (`serde_json::Value` isn't `Hash`, and `1` and `"1"` must stay distinct).
+type CancelRegistry = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+
 /// Main MCP server loop that handles JSON-RPC communication.
 ///
-/// Reads JSON-RPC requests from stdin line by line, processes each request
-/// through the request handler, and writes responses to stdout. This follows
-/// the MCP protocol specification for server communication.
+/// Reads JSON-RPC requests from stdin line by line and handles each one in
+/// its own task, so a slow tool call doesn't block later requests and can be
+/// cancelled. This follows the MCP protocol specification for server communication.
 ///
 /// ## Protocol Details
 ///
 /// - Each request is a single line of JSON
+/// - A line longer than `MAX_REQUEST_SIZE_BYTES` is discarded without being
+///   buffered and answered with a parse error (id null); reading continues
+///   with the next line
 /// - Empty lines are ignored
-/// - Responses are written immediately after processing
+/// - Responses are written as each request completes, possibly out of order
 /// - Notifications (requests without IDs) may not generate responses
+/// - `notifications/cancelled` aborts the in-flight request named by
+///   `params.requestId`, which then answers with error -32800
+/// - A request reusing the id of one still in flight is answered with error
+///   -32600 and not run
+/// - Progress notifications share stdout with responses through one mutex
+/// - A handler that panics answers with error -32603 instead of leaving the
+///   request unanswered
 /// - All errors are logged to stderr to avoid polluting the JSON-RPC stream
-async fn run_mcp_server(client: Arc<LogseqClient>) -> Result<()> {
-    let stdin = BufReader::new(tokio::io::stdin());
-    let mut stdout = std::io::stdout();
-    let mut lines = stdin.lines();
+///
+/// ## Shutdown
+///
+/// When stdin closes, in-flight requests are allowed to finish. On SIGTERM
+/// or SIGINT the server stops reading, waits up to `drain_timeout` for
+/// in-flight requests, then aborts whatever is still running and returns.
+///
+/// ## Thread Safety
+///
+/// Requests run concurrently on the tokio runtime, each handler in its own
+/// spawned task (see [`spawn_handler`]) so a panic is contained to that
+/// request. Every write to stdout goes through the `SharedWriter` mutex and
+/// writes a whole line at once, so responses and progress notifications
+/// from different tasks never interleave. The cancellation registry is
+/// likewise behind a mutex; a poisoned lock is recovered rather than
+/// propagated, since its contents stay consistent.
+async fn run_mcp_server<R: AsyncBufRead + Unpin>(
+    client: Arc<LogseqClient>,
+    audit: Option<AuditLogger>,
+    mut lines: LimitedReader<R>,
+    stdout: SharedWriter,
+    drain_timeout: Duration,
+) -> Result<()> {
+    let cancellations: CancelRegistry = Arc::default();
+    let mut in_flight = JoinSet::new();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
 
     // Process each line from stdin as a separate JSON-RPC request
-    while let Some(line) = lines.next_line().await? {
+    loop {
+        // `next_line` is cancel-safe, so losing the race loses no input
+        let line = tokio::select! {
+            line = lines.next_line() => line?,
+            () = &mut shutdown => {
+                tracing::info!(
+                    "Shutdown signal received; waiting up to {:?} for {} in-flight request(s)",
+                    drain_timeout,
+                    in_flight.len()
+                );
+                if tokio::time::timeout(drain_timeout, drain(&mut in_flight)).await.is_err() {
+                    tracing::warn!("Aborting {} request(s) still running", in_flight.len());
+                    in_flight.shutdown().await;
+                }
+                return Ok(());
+            }
+        };
+        let line = match line {
+            Some(ReadLine::Line(line)) => line,
+            Some(ReadLine::TooLong) => {
+                tracing::error!("Rejected a request over the {} byte limit", lines.limit());
+                let error_response = ResponseBuilder::request_too_large(lines.limit());
+                write_line(&stdout, &serde_json::to_string(&error_response)?)?;
+                continue;
+            }
+            None => break,
+        };
         if line.trim().is_empty() {
             continue;
         }
@@ -112,32 +278,314 @@ async fn run_mcp_server(client: Arc<LogseqClient>) -> Result<()> {
                 tracing::error!("Failed to parse JSON: {}", e);
                 let error_response = ResponseBuilder::parse_error();
                 let error_str = serde_json::to_string(&error_response)?;
-                writeln!(stdout, "{}", error_str)?;
-                stdout.flush()?;
+                write_line(&stdout, &error_str)?;
                 continue;
             }
         };
 
-        // Handle the request and generate a response
-        let response = handle_request(request, &client).await;
-
-        // Check if this is a notification (no response needed)
-        if response.is_notification_ack() {
-            tracing::debug!("Skipping response for notification");
+        if request.method == "notifications/cancelled" {
+            cancel_request(&cancellations, request.params.as_ref());
             continue;
         }
 
-        // Send response back to client via stdout
-        let response_str = response
-            .serialize()
-            .map_err(|e| anyhow::anyhow!("Failed to serialize response: {}", e))?;
-        writeln!(stdout, "{}", response_str)?;
-        stdout.flush()?;
+        let progress = ProgressReporter::new(
+            ProgressReporter::token_from_params(request.params.as_ref()),
+            stdout.clone(),
+        );
+        let cancel_key = request.id.as_ref().map(Value::to_string);
+        let cancelled = match &cancel_key {
+            Some(key) => match register_request(&cancellations, key) {
+                Some(rx) => Some(rx),
+                None => {
+                    tracing::error!("Rejected duplicate in-flight request id {}", key);
+                    let error_response = ResponseBuilder::error(
+                        request.id.unwrap_or(Value::Null),
+                        error_codes::INVALID_REQUEST,
+                        format!("Request id {} is already in flight", key),
+                    );
+                    write_line(&stdout, &serde_json::to_string(&error_response)?)?;
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let client = client.clone();
+        let audit = audit.clone();
+        let stdout = stdout.clone();
+        let cancellations = cancellations.clone();
+        in_flight.spawn(async move {
+            let id = request.id.clone();
+            let mut handler = spawn_handler(request, client, audit, progress);
+            let response = match cancelled {
+                Some(cancelled) => tokio::select! {
+                    joined = &mut handler => joined_response(joined, id),
+                    Ok(()) = cancelled => {
+                        handler.abort();
+                        // Wait for the task to be dropped, so its audit
+                        // record is written before the response goes out
+                        let _ = handler.await;
+                        HandlerResponse::error(
+                            id.unwrap_or_else(generate_request_id),
+                            error_codes::REQUEST_CANCELLED,
+                            "Request cancelled".to_string(),
+                        )
+                    }
+                },
+                None => joined_response(handler.await, id),
+            };
+            if let Some(key) = cancel_key {
+                unregister_request(&cancellations, key);
+            }
+            if let Err(e) = send_response(&stdout, response) {
+                tracing::error!("{}", e);
+            }
+        });
+
+        // Reap finished tasks so the set doesn't grow without bound
+        while in_flight.try_join_next().is_some() {}
+    }
+
+    // stdin closed: let in-flight requests finish and answer
+    drain(&mut in_flight).await;
+
+    Ok(())
+}
+
+/// Waits for every task in the set to finish.
+async fn drain(in_flight: &mut JoinSet<()>) {
+    while in_flight.join_next().await.is_some() {}
+}
+
+/// Resolves on SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+///
+/// If a handler can't be installed, that signal is logged and ignored
+/// rather than triggering a shutdown.
+async fn shutdown_signal() {
+    let interrupt = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = interrupt => {}
+        () = terminate => {}
     }
+}
+
+/// Serves JSON-RPC over HTTP on `host:port`.
+///
+/// Each `POST /` body is one JSON-RPC request, answered inline through the
+/// same `handle_request` used by the stdio transport. There is no
+/// authentication, so this must only be exposed on trusted networks.
+///
+/// ## Protocol Details
+///
+/// - Responses are returned as `application/json` with status 200
+/// - Notifications are acknowledged with an empty 202 Accepted
+/// - Progress notifications have no channel back to the client and are dropped
+/// - A handler that panics answers with error -32603, as on stdio
+/// - `notifications/cancelled` is accepted but has no effect, since each
+///   request is tied to its own HTTP connection
+async fn run_http_server(
+    client: Arc<LogseqClient>,
+    audit: Option<AuditLogger>,
+    host: &str,
+    port: u16,
+) -> Result<()> {
+    let app = axum::Router::new()
+        .route("/", axum::routing::post(handle_http_request))
+        .with_state((client, audit));
+
+    let listener = tokio::net::TcpListener::bind((host, port)).await?;
+    tracing::info!("Listening for HTTP JSON-RPC on {}", listener.local_addr()?);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            shutdown_signal().await;
+            tracing::info!("Shutdown signal received; finishing open HTTP requests");
+        })
+        .await?;
 
     Ok(())
 }
 
+/// Handles a single HTTP `POST /` carrying one JSON-RPC request.
+async fn handle_http_request(
+    State((client, audit)): State<(Arc<LogseqClient>, Option<AuditLogger>)>,
+    body: String,
+) -> axum::response::Response {
+    let request = match parse_request(&body) {
+        Ok(req) => {
+            tracing::debug!("Received request: method={}, id={:?}", req.method, req.id);
+            req
+        }
+        Err(e) => {
+            tracing::error!("Failed to parse JSON: {}", e);
+            return Json(ResponseBuilder::parse_error()).into_response();
+        }
+    };
+
+    if request.method == "notifications/cancelled" {
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    let id = request.id.clone();
+    let handler = spawn_handler(request, client, audit, ProgressReporter::disabled());
+    let response = joined_response(handler.await, id);
+    if response.is_notification_ack() {
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    match response.serialize() {
+        Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to serialize response: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Runs `handle_request` in its own task, so a panicking handler only fails
+/// its own request. Join the handle with [`joined_response`].
+fn spawn_handler(
+    request: JsonRpcRequest,
+    client: Arc<LogseqClient>,
+    audit: Option<AuditLogger>,
+    progress: ProgressReporter,
+) -> JoinHandle<HandlerResponse> {
+    tokio::spawn(async move { handle_request(request, &client, audit.as_ref(), &progress).await })
+}
+
+/// Turns a joined handler task into its response, answering a panicked or
+/// aborted task with an internal error (or nothing, for a notification).
+fn joined_response(
+    joined: std::result::Result<HandlerResponse, JoinError>,
+    id: Option<Value>,
+) -> HandlerResponse {
+    let err = match joined {
+        Ok(response) => return response,
+        Err(err) => err,
+    };
+    let reason = if err.is_panic() {
+        let payload = err.into_panic();
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    } else {
+        "task was aborted".to_string()
+    };
+    tracing::error!("Request handler for id {:?} failed: {}", id, reason);
+
+    match id {
+        Some(id) => HandlerResponse::error(
+            id,
+            error_codes::INTERNAL_ERROR,
+            "Internal error: the request handler crashed".to_string(),
+        ),
+        None => HandlerResponse::notification_ack(),
+    }
+}
+
+/// Signals cancellation for the request named by `params.requestId`.
+///
+/// Unknown or already-finished requests are ignored, as the MCP spec allows.
+fn cancel_request(cancellations: &CancelRegistry, params: Option<&Value>) {
+    let Some(request_id) = params.and_then(|p| p.get("requestId")) else {
+        tracing::debug!("Ignoring cancellation without requestId");
+        return;
+    };
+    match lock_registry(cancellations).remove(&request_id.to_string()) {
+        Some(tx) => {
+            tracing::debug!("Cancelling request {}", request_id);
+            let _ = tx.send(());
+        }
+        None => tracing::debug!("No in-flight request {} to cancel", request_id),
+    }
+}
+
+/// Registers an in-flight request under `key`, returning the receiver its
+/// task waits on for cancellation.
+///
+/// Returns `None` if another request with the same id is still running:
+/// JSON-RPC ids must be unique among in-flight requests, and taking over
+/// the key would leave the earlier request uncancellable.
+fn register_request(cancellations: &CancelRegistry, key: &str) -> Option<oneshot::Receiver<()>> {
+    let mut registry = lock_registry(cancellations);
+    if registry.get(key).is_some_and(|tx| !tx.is_closed()) {
+        return None;
+    }
+    let (tx, rx) = oneshot::channel();
+    registry.insert(key.to_string(), tx);
+    Some(rx)
+}
+
+/// Removes a finished request's entry from the registry.
+///
+/// The task's receiver is gone by now, so its sender reports closed. An open
+/// sender under the same key belongs to a later request that reused the id
+/// after this one was cancelled, and is left in place.
+fn unregister_request(cancellations: &CancelRegistry, key: String) {
+    if let Entry::Occupied(entry) = lock_registry(cancellations).entry(key)
+        && entry.get().is_closed()
+    {
+        entry.remove();
+    }
+}
+
+/// Locks the cancellation registry, recovering from a poisoned lock.
+fn lock_registry(
+    cancellations: &CancelRegistry,
+) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<()>>> {
+    cancellations
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Serializes and writes a handler response, skipping notification acks.
+fn send_response(out: &SharedWriter, response: HandlerResponse) -> Result<()> {
+    // Check if this is a notification (no response needed)
+    if response.is_notification_ack() {
+        tracing::debug!("Skipping response for notification");
+        return Ok(());
+    }
+
+    // Send response back to client via stdout
+    let response_str = response
+        .serialize()
+        .map_err(|e| anyhow::anyhow!("Failed to serialize response: {}", e))?;
+    write_line(out, &response_str)
+}
+
+/// Writes one JSON-RPC message line to the shared output and flushes it.
+fn write_line(out: &SharedWriter, line: &str) -> Result<()> {
+    let mut out = out
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Output stream lock poisoned"))?;
+    writeln!(out, "{}", line)?;
+    out.flush()?;
+    Ok(())
+}
+
 /// Central request handler that routes JSON-RPC requests to appropriate handlers.
 ///
 /// Extracts the method name from the request and dispatches to the corresponding
@@ -156,20 +604,58 @@ async fn run_mcp_server(client: Arc<LogseqClient>) -> Result<()> {
 /// ## Error Handling
 ///
 /// Unknown methods return a JSON-RPC error with code -32601 (Method not found).
-/// The ID is preserved from the request, or defaults to 0 for malformed requests.
-async fn handle_request(request: JsonRpcRequest, client: &Arc<LogseqClient>) -> HandlerResponse {
+/// The ID is preserved from the request.
+///
+/// ## Notifications
+///
+/// A request is treated according to its `id`:
+/// - `"id": null` — a notification: the method runs but nothing is sent back
+/// - no `id` member — also a notification, as the JSON-RPC spec defines it
+/// - any other `id` — a normal request, answered with that same id
+///
+/// Notifications still run (a `tools/call` without an id still executes);
+/// handlers get a [`generate_request_id`] id rather than a made-up `0` that
+/// could belong to a real request, and the response is dropped.
+async fn handle_request(
+    request: JsonRpcRequest,
+    client: &Arc<LogseqClient>,
+    audit: Option<&AuditLogger>,
+    progress: &ProgressReporter,
+) -> HandlerResponse {
+    let is_notification = request.is_notification();
     // Resolve id for handlers that always respond. Notification handlers receive
     // the raw Option<Value> so they can detect and correctly silence notifications.
-    let id = request.id.clone().unwrap_or(json!(0));
-    let method = &request.method;
+    let id = request.id.clone().unwrap_or_else(generate_request_id);
+    let method = request.method.clone();
 
-    match method.as_str() {
+    let response = dispatch_request(&method, id.clone(), request, client, audit, progress).await;
+    if is_notification && !response.is_notification_ack() {
+        tracing::debug!("Dropping response to notification '{}' ({})", method, id);
+        return HandlerResponse::notification_ack();
+    }
+    response
+}
+
+/// Routes a request to the handler for its method.
+async fn dispatch_request(
+    method: &str,
+    id: Value,
+    request: JsonRpcRequest,
+    client: &Arc<LogseqClient>,
+    audit: Option<&AuditLogger>,
+    progress: &ProgressReporter,
+) -> HandlerResponse {
+    match method {
         "initialize" => handle_initialize(id),
         "initialized" => handle_initialized(request.id.clone()),
         "notifications/initialized" => handle_notifications_initialized(request.id.clone()),
         "ping" => handle_ping(id),
-        "tools/list" => handle_tools_list(id),
-        "tools/call" => handle_tool_call(id, request, client).await,
+        "tools/list" => handle_tools_list(id, client),
+        "tools/call" => handle_tool_call(id, request, client, audit, progress).await,
+        "resources/list" => handle_resources_list(id, client).await,
+        "resources/read" => handle_resources_read(id, request, client).await,
+        "prompts/list" => handle_prompts_list(id, client).await,
+        "prompts/get" => handle_prompts_get(id, request, client).await,
         _ => {
             tracing::debug!("Unknown method: {}", method);
             HandlerResponse::error(
@@ -191,7 +677,7 @@ async fn handle_request(request: JsonRpcRequest, client: &Arc<LogseqClient>) ->
 ///
 /// Returns server info including:
 /// - Protocol version (2024-11-05)
-/// - Server capabilities (tools support)
+/// - Server capabilities (tools, resources and prompts support)
 /// - Server name and version
 ///
 /// Note: Tools are NOT included here per MCP spec - they're returned via tools/list
@@ -199,12 +685,11 @@ fn handle_initialize(id: Value) -> HandlerResponse {
     let result = json!({
         "protocolVersion": "2024-11-05",
         "capabilities": {
-            "tools": {}
+            "tools": {},
+            "resources": {},
+            "prompts": {}
         },
-        "serverInfo": {
-            "name": "mcp-logseq-rust",
-            "version": "1.0.0"
-        }
+        "serverInfo": get_server_info()
     });
 
     HandlerResponse::success(id, result)
@@ -253,7 +738,9 @@ fn handle_notifications_initialized(_id: Option<Value>) -> HandlerResponse {
 ///
 /// Returns a list of all available tools that clients can invoke.
 /// Each tool includes its name, description, and input schema definition
-/// which describes the expected parameters and their types.
+/// which describes the expected parameters and their types. Mutation tools
+/// are left out when the server runs in read-only mode, as is any tool not
+/// named in `ALLOWED_TOOLS` when that is set.
 ///
 /// ## Response Format
 ///
@@ -261,13 +748,16 @@ fn handle_notifications_initialized(_id: Option<Value>) -> HandlerResponse {
 /// - `name`: Unique identifier for the tool
 /// - `description`: Human-readable description of what the tool does
 /// - `inputSchema`: JSON Schema defining expected parameters
-fn handle_tools_list(id: Value) -> HandlerResponse {
-    let tools = tools::get_all_tools();
+///
+/// `_meta.serverInfo` repeats the server name and version from `initialize`.
+fn handle_tools_list(id: Value, client: &LogseqClient) -> HandlerResponse {
+    let tools = ToolRegistry::global().available_tools(client.config());
 
     tracing::debug!("Handling tools/list request");
 
     let result = json!({
-        "tools": tools.iter().map(|t| t.to_json()).collect::<Vec<_>>()
+        "tools": tools.iter().map(|t| t.to_json()).collect::<Vec<_>>(),
+        "_meta": { "serverInfo": get_server_info() }
     });
 
     HandlerResponse::success(id, result)
@@ -285,23 +775,38 @@ fn handle_tools_list(id: Value) -> HandlerResponse {
 /// - `params.name`: The name of the tool to execute
 /// - `params.arguments`: Object containing tool-specific parameters
 ///
+/// Arguments are checked against the tool's input schema (required fields,
+/// types) before the handler runs; violations are reported as -32602.
+///
 /// ## Response Format
 ///
 /// Success responses contain:
 /// - `result.content`: Array with tool output as formatted text
+/// - `result._timing`: Per-phase timings, only when `MCP_INCLUDE_TIMING` is set
+/// - `result._retries`: Logseq API retries made for the call, only when
+///   `DEBUG` logging is enabled
 ///
 /// Error responses contain:
-/// - `error.code`: -32603 (Internal error)
+/// - `error.code`: -32602 (Invalid params) for `McpError::InvalidParams` and
+///   unknown tools, -32001 for `McpError::Timeout`, otherwise -32603 (Internal error)
 /// - `error.message`: Description of what went wrong
+/// - `error.data`: For `McpError::LogseqApi`, the failing API method and Logseq's error
 ///
 /// ## Supported Tools
 ///
-/// Query tools: list_graphs, list_pages, get_page, get_block, search
-/// Mutation tools: create_page, update_block, insert_block, delete_block, append_to_page
+/// Every tool declared in `tools/mod.rs`, looked up through `ToolRegistry`.
+///
+/// ## Audit Log
+///
+/// With an `audit` logger, every mutation tool call that gets past the
+/// read-only and `ALLOWED_TOOLS` checks is recorded, whether it succeeds,
+/// fails, panics or is cancelled.
 async fn handle_tool_call(
     id: Value,
     request: JsonRpcRequest,
     client: &Arc<LogseqClient>,
+    audit: Option<&AuditLogger>,
+    progress: &ProgressReporter,
 ) -> HandlerResponse {
     // Extract tool name and parameters from the MCP request format
     let params = match request.params {
@@ -326,33 +831,46 @@ async fn handle_tool_call(
         }
     };
 
-    let default_params = json!({});
-    let tool_params = params.get("arguments").unwrap_or(&default_params);
-
-    // Dispatch to the appropriate tool handler based on tool name
-    let result = match tool_name {
-        // Query tools
-        "list_graphs" => query::list_graphs(client, tool_params.clone()).await,
-        "list_pages" => query::list_pages(client, tool_params.clone()).await,
-        "get_page" => query::get_page(client, tool_params.clone()).await,
-        "get_block" => query::get_block(client, tool_params.clone()).await,
-        "search" => query::search(client, tool_params.clone()).await,
-        "query" => query::query(client, tool_params.clone()).await,
-        "get_today_journal" => query::get_today_journal(client, tool_params.clone()).await,
-        "get_page_references" => query::get_page_references(client, tool_params.clone()).await,
-        "get_block_properties" => query::get_block_properties(client, tool_params.clone()).await,
-        // Mutation tools
-        "create_page" => mutate::create_page(client, tool_params.clone()).await,
-        "update_block" => mutate::update_block(client, tool_params.clone()).await,
-        "insert_block" => mutate::insert_block(client, tool_params.clone()).await,
-        "delete_block" => mutate::delete_block(client, tool_params.clone()).await,
-        "delete_page" => mutate::delete_page(client, tool_params.clone()).await,
-        "append_to_page" => mutate::append_to_page(client, tool_params.clone()).await,
-        "append_to_journal" => mutate::append_to_journal(client, tool_params.clone()).await,
-        "set_block_property" => mutate::set_block_property(client, tool_params.clone()).await,
-        "remove_block_property" => mutate::remove_block_property(client, tool_params.clone()).await,
-        _ => Err(anyhow::anyhow!("Unknown tool: {}", tool_name)),
-    };
+    let registry = ToolRegistry::global();
+    if client.config().read_only && registry.is_mutation(tool_name) {
+        return HandlerResponse::error(
+            id,
+            error_codes::INTERNAL_ERROR,
+            "Server is in read-only mode; mutation tools are disabled".to_string(),
+        );
+    }
+
+    if registry.contains(tool_name) && !registry.is_enabled(client.config(), tool_name) {
+        return HandlerResponse::error(
+            id,
+            error_codes::METHOD_NOT_FOUND,
+            format!("Tool '{}' is disabled by ALLOWED_TOOLS", tool_name),
+        );
+    }
+
+    let tool_params = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let pending_audit = audit
+        .filter(|_| registry.is_mutation(tool_name))
+        .map(|audit| audit.start(tool_name, &tool_params, &id));
+
+    // Validate the arguments against the tool's schema and run it
+    let mut timer = Timer::new();
+    let (result, api_usage) = measure_api_usage(registry.call_timed(
+        tool_name,
+        client,
+        tool_params,
+        progress,
+        &mut timer,
+    ))
+    .await;
+    timer.start_phase("formatting");
+
+    if let Some(pending) = pending_audit {
+        pending.finish(&result);
+    }
 
     // Format the response according to MCP protocol
     match result {
@@ -367,20 +885,545 @@ async fn handle_tool_call(
                     );
                 }
             };
-            HandlerResponse::success(
-                id,
-                json!({
-                    "content": [{
-                        "type": "text",
-                        "text": text
-                    }]
-                }),
-            )
+            let mut response = json!({
+                "content": [{
+                    "type": "text",
+                    "text": text
+                }]
+            });
+            if client.config().include_timing {
+                response["_timing"] = tool_timing(timer, api_usage.time);
+            }
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                response["_retries"] = json!(api_usage.retries);
+            }
+            HandlerResponse::success(id, response)
         }
-        Err(e) => HandlerResponse::error(
+        Err(e) => error_response(id, e),
+    }
+}
+
+/// Builds the `_timing` breakdown for a tool call.
+///
+/// `processing_ms` is the tool's execution time outside Logseq API calls
+/// plus formatting the response. Concurrent API calls are summed, so
+/// `api_calls_ms` can exceed the wall-clock time they took.
+fn tool_timing(timer: Timer, api_time: Duration) -> Value {
+    let phases = timer.finish();
+    let phase = |name: &str| phases.get(name).copied().unwrap_or(0);
+    let api_calls_ms = api_time.as_millis() as u64;
+    json!({
+        "validation_ms": phase("validation_ms"),
+        "api_calls_ms": api_calls_ms,
+        "processing_ms": (phase("execution_ms") + phase("formatting_ms")).saturating_sub(api_calls_ms),
+        "total_ms": phase("total_ms")
+    })
+}
+
+/// Maps an `McpError` to a JSON-RPC error response.
+///
+/// `InvalidParams` → -32602, `Timeout` → -32001, everything else → -32603;
+/// `LogseqApi` errors also carry the failing method in `error.data`.
+fn error_response(id: Value, e: McpError) -> HandlerResponse {
+    match e {
+        McpError::InvalidParams(msg) => {
+            HandlerResponse::error(id, error_codes::INVALID_PARAMS, msg)
+        }
+        McpError::Timeout(_) => {
+            HandlerResponse::error(id, error_codes::REQUEST_TIMEOUT, e.to_string())
+        }
+        McpError::LogseqApi { .. } => HandlerResponse::error_with_data(
             id,
             error_codes::INTERNAL_ERROR,
             format!("Tool execution failed: {}", e),
+            e.data(),
         ),
+        _ => HandlerResponse::error(
+            id,
+            error_codes::INTERNAL_ERROR,
+            format!("Tool execution failed: {}", e),
+        ),
+    }
+}
+
+/// Handles the MCP `resources/list` request.
+///
+/// Returns every page in the graph as a `logseq://page/<name>` resource.
+async fn handle_resources_list(id: Value, client: &Arc<LogseqClient>) -> HandlerResponse {
+    match resources::list_resources(client).await {
+        Ok(result) => HandlerResponse::success(id, result),
+        Err(e) => error_response(id, e),
+    }
+}
+
+/// Handles the MCP `resources/read` request.
+///
+/// Expects `params.uri` to be a `logseq://page/<name>` URI and returns the
+/// page rendered as markdown.
+async fn handle_resources_read(
+    id: Value,
+    request: JsonRpcRequest,
+    client: &Arc<LogseqClient>,
+) -> HandlerResponse {
+    let params = request.params.unwrap_or_else(|| json!({}));
+    match resources::read_resource(client, &params).await {
+        Ok(result) => HandlerResponse::success(id, result),
+        Err(e) => error_response(id, e),
+    }
+}
+
+/// Handles the MCP `prompts/list` request.
+///
+/// Returns every Logseq template page (`template::` property) as a prompt.
+async fn handle_prompts_list(id: Value, client: &Arc<LogseqClient>) -> HandlerResponse {
+    match prompts::list_prompts(client).await {
+        Ok(result) => HandlerResponse::success(id, result),
+        Err(e) => error_response(id, e),
+    }
+}
+
+/// Handles the MCP `prompts/get` request.
+///
+/// Expects `params.name` to be a template name from `prompts/list`.
+async fn handle_prompts_get(
+    id: Value,
+    request: JsonRpcRequest,
+    client: &Arc<LogseqClient>,
+) -> HandlerResponse {
+    let params = request.params.unwrap_or_else(|| json!({}));
+    match prompts::get_prompt(client, &params).await {
+        Ok(result) => HandlerResponse::success(id, result),
+        Err(e) => error_response(id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_request_signals_matching_id() {
+        let cancellations: CancelRegistry = Arc::default();
+        let (tx_num, mut rx_num) = oneshot::channel();
+        let (tx_str, mut rx_str) = oneshot::channel();
+        lock_registry(&cancellations).insert(json!(1).to_string(), tx_num);
+        lock_registry(&cancellations).insert(json!("1").to_string(), tx_str);
+
+        cancel_request(&cancellations, Some(&json!({"requestId": "1"})));
+        assert!(rx_str.try_recv().is_ok());
+        assert!(rx_num.try_recv().is_err());
+        assert_eq!(lock_registry(&cancellations).len(), 1);
+
+        // Unknown ids and missing params are ignored
+        cancel_request(&cancellations, Some(&json!({"requestId": 99})));
+        cancel_request(&cancellations, None);
+        assert_eq!(lock_registry(&cancellations).len(), 1);
+    }
+
+    #[test]
+    fn test_registry_keeps_ids_unique() {
+        let cancellations: CancelRegistry = Arc::default();
+        let first = register_request(&cancellations, "1").unwrap();
+        assert!(register_request(&cancellations, "1").is_none());
+
+        // Once the first request is cancelled its id may be reused, and the
+        // first task's cleanup must not remove the new entry
+        cancel_request(&cancellations, Some(&json!({"requestId": 1})));
+        let second = register_request(&cancellations, "1").unwrap();
+        drop(first);
+        unregister_request(&cancellations, "1".to_string());
+        assert_eq!(lock_registry(&cancellations).len(), 1);
+
+        drop(second);
+        unregister_request(&cancellations, "1".to_string());
+        assert!(lock_registry(&cancellations).is_empty());
+    }
+
+    async fn http_call(body: &str) -> (StatusCode, String) {
+        let client = Arc::new(LogseqClient::new(Config::default()).unwrap());
+        let response = handle_http_request(State((client, None)), body.to_string()).await;
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_http_request_answers_inline() {
+        let (status, body) = http_call(r#"{"jsonrpc":"2.0","id":7,"method":"ping"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["result"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_http_notification_is_accepted_without_body() {
+        let (status, body) =
+            http_call(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_http_invalid_json_is_parse_error() {
+        let (status, body) = http_call("{not json").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["error"]["code"], error_codes::PARSE_ERROR);
+        assert!(body["id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mode_refuses_mutation_tools() {
+        let config = Config {
+            read_only: true,
+            ..Config::default()
+        };
+        let client = Arc::new(LogseqClient::new(config).unwrap());
+        let request = parse_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"delete_page","arguments":{"page_name":"x"}}}"#,
+        )
+        .unwrap();
+
+        let response = handle_request(request, &client, None, &ProgressReporter::disabled()).await;
+        let body: Value = serde_json::from_str(&response.serialize().unwrap()).unwrap();
+        assert_eq!(body["error"]["code"], error_codes::INTERNAL_ERROR);
+        assert_eq!(
+            body["error"]["message"],
+            "Server is in read-only mode; mutation tools are disabled"
+        );
+
+        let request = parse_request(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#).unwrap();
+        let response = handle_request(request, &client, None, &ProgressReporter::disabled()).await;
+        let body: Value = serde_json::from_str(&response.serialize().unwrap()).unwrap();
+        let tools = body["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().all(|t| t["name"] != "delete_page"));
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_tool_call_is_refused() {
+        let config = Config {
+            allowed_tools: Some(["search".to_string()].into()),
+            ..Config::default()
+        };
+        let client = Arc::new(LogseqClient::new(config).unwrap());
+        let request = parse_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_page","arguments":{"page_name":"x"}}}"#,
+        )
+        .unwrap();
+
+        let response = handle_request(request, &client, None, &ProgressReporter::disabled()).await;
+        let body: Value = serde_json::from_str(&response.serialize().unwrap()).unwrap();
+        assert_eq!(body["error"]["code"], error_codes::METHOD_NOT_FOUND);
+        assert_eq!(
+            body["error"]["message"],
+            "Tool 'get_page' is disabled by ALLOWED_TOOLS"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_rejects_wrong_param_type() {
+        let client = Arc::new(LogseqClient::new(Config::default()).unwrap());
+        let request = parse_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_page","arguments":{"page_name":3}}}"#,
+        )
+        .unwrap();
+
+        let response = handle_request(request, &client, None, &ProgressReporter::disabled()).await;
+        let body: Value = serde_json::from_str(&response.serialize().unwrap()).unwrap();
+        assert_eq!(body["error"]["code"], error_codes::INVALID_PARAMS);
+        assert_eq!(
+            body["error"]["message"],
+            "Parameter 'page_name' must be of type string, got integer"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handler_answers_with_internal_error() {
+        let task: JoinHandle<HandlerResponse> = tokio::spawn(async { panic!("boom") });
+        let response = joined_response(task.await, Some(json!(7)));
+        let value: Value = serde_json::from_str(&response.serialize().unwrap()).unwrap();
+        assert_eq!(value["id"], 7);
+        assert_eq!(value["error"]["code"], error_codes::INTERNAL_ERROR);
+
+        let task: JoinHandle<HandlerResponse> = tokio::spawn(async { panic!("boom") });
+        assert!(joined_response(task.await, None).is_notification_ack());
+    }
+
+    #[tokio::test]
+    async fn test_verify_logseq_connection_reports_url() {
+        let client = LogseqClient::new(Config {
+            logseq_api_token: "test-token".to_string(),
+            logseq_api_url: "http://127.0.0.1:9".to_string(),
+            max_retries: 0,
+            ..Config::default()
+        })
+        .unwrap();
+        let err = verify_logseq_connection(&client).await.unwrap_err();
+        assert!(err.to_string().starts_with(
+            "Cannot connect to Logseq at http://127.0.0.1:9. Ensure Logseq is running"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_id_get_no_response() {
+        // No id member: a notification
+        let (status, body) = http_call(r#"{"jsonrpc":"2.0","method":"ping"}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(body.is_empty());
+
+        // Explicit null id: also a notification
+        let (status, body) = http_call(r#"{"jsonrpc":"2.0","id":null,"method":"ping"}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(body.is_empty());
+
+        // Unknown methods are silent too, since nobody is waiting for the error
+        let (status, _) = http_call(r#"{"jsonrpc":"2.0","method":"nope"}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn test_request_with_id_zero_is_answered() {
+        let (status, body) = http_call(r#"{"jsonrpc":"2.0","id":0,"method":"ping"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["id"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_stdio_recovers_after_oversized_request() {
+        let big = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"ping","params":{{"pad":"{}"}}}}"#,
+            "x".repeat(1000)
+        );
+        let input = format!(
+            "{}\n{}\n",
+            big, r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#
+        );
+        let output = Arc::new(Mutex::new(Vec::<u8>::new()));
+
+        let client = Arc::new(LogseqClient::new(Config::default()).unwrap());
+        let reader = LimitedReader::new(BufReader::new(input.as_bytes()), 200);
+        run_mcp_server(client, None, reader, output.clone(), Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let responses: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[0]["error"]["code"], error_codes::PARSE_ERROR);
+        assert_eq!(
+            responses[0]["error"]["message"],
+            "Parse error: request exceeds the 200 byte limit"
+        );
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[1]["result"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_tool_timing_only_when_enabled() {
+        let call = |include_timing: bool| async move {
+            let client = Arc::new(
+                LogseqClient::new(Config {
+                    include_timing,
+                    ..Config::default()
+                })
+                .unwrap(),
+            );
+            let request = parse_request(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call",
+                    "params":{"name":"validate_uuid","arguments":{"uuid":"x"}}}"#,
+            )
+            .unwrap();
+            let response = handle_tool_call(
+                json!(1),
+                request,
+                &client,
+                None,
+                &ProgressReporter::disabled(),
+            )
+            .await
+            .serialize()
+            .unwrap();
+            serde_json::from_str::<Value>(&response).unwrap()["result"].clone()
+        };
+
+        let plain = call(false).await;
+        assert!(plain.get("_timing").is_none());
+        assert!(plain["content"][0]["text"].is_string());
+
+        let timed = call(true).await;
+        let timing = timed["_timing"].as_object().unwrap();
+        let keys: Vec<&str> = timing.keys().map(String::as_str).collect();
+        assert_eq!(keys.len(), 4);
+        for key in ["validation_ms", "api_calls_ms", "processing_ms", "total_ms"] {
+            assert!(timing[key].is_u64(), "{key} missing");
+        }
+        assert_eq!(timing["api_calls_ms"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_audits_mutations_only() {
+        let path =
+            std::env::temp_dir().join(format!("mcp-logseq-main-audit-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let audit = AuditLogger::open(&path, "").unwrap();
+        let client = Arc::new(LogseqClient::new(Config::default()).unwrap());
+
+        for (id, body) in [
+            (1, r#"{"name":"validate_uuid","arguments":{"uuid":"x"}}"#),
+            (
+                2,
+                r#"{"name":"delete_block","arguments":{"uuid":"not-a-uuid"}}"#,
+            ),
+        ] {
+            let request = parse_request(&format!(
+                r#"{{"jsonrpc":"2.0","id":{id},"method":"tools/call","params":{body}}}"#
+            ))
+            .unwrap();
+            handle_tool_call(
+                json!(id),
+                request,
+                &client,
+                Some(&audit),
+                &ProgressReporter::disabled(),
+            )
+            .await;
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries: Vec<Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["tool"], "delete_block");
+        assert_eq!(entries[0]["request_id"], 2);
+        assert_eq!(entries[0]["params"], json!({"uuid": "not-a-uuid"}));
+        assert_eq!(entries[0]["result"]["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_tool_retries_reported_at_debug_level() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        let client = Arc::new(
+            LogseqClient::new(Config {
+                logseq_api_url: server.uri(),
+                initial_retry_delay_ms: 1,
+                ..Config::default()
+            })
+            .unwrap(),
+        );
+        let call = || async {
+            let request = parse_request(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call",
+                    "params":{"name":"list_pages","arguments":{}}}"#,
+            )
+            .unwrap();
+            let response = handle_tool_call(
+                json!(1),
+                request,
+                &client,
+                None,
+                &ProgressReporter::disabled(),
+            )
+            .await
+            .serialize()
+            .unwrap();
+            serde_json::from_str::<Value>(&response).unwrap()["result"].clone()
+        };
+
+        assert!(call().await.get("_retries").is_none());
+
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(std::io::sink)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        server.reset().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        assert_eq!(call().await["_retries"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_stdio_cancels_in_flight_request() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([]))
+                    .set_delay(Duration::from_secs(30)),
+            )
+            .mount(&server)
+            .await;
+        let client = Arc::new(
+            LogseqClient::new(Config {
+                logseq_api_url: server.uri(),
+                ..Config::default()
+            })
+            .unwrap(),
+        );
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call",
+            "params":{"name":"list_pages","arguments":{}}}"#
+            .replace('\n', "");
+        let cancel =
+            r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":1}}"#;
+        let input = format!("{call}\n{call}\n{cancel}\n");
+        let output = Arc::new(Mutex::new(Vec::<u8>::new()));
+
+        let reader = LimitedReader::new(BufReader::new(input.as_bytes()), 4096);
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            run_mcp_server(client, None, reader, output.clone(), Duration::from_secs(1)),
+        )
+        .await
+        .expect("cancelled request should not wait for the API")
+        .unwrap();
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let responses: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        // The duplicate is turned away without disturbing the original...
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["error"]["code"], error_codes::INVALID_REQUEST);
+        // ...which the cancellation then reaches
+        assert_eq!(responses[1]["id"], 1);
+        assert_eq!(
+            responses[1]["error"]["code"],
+            error_codes::REQUEST_CANCELLED
+        );
     }
 }
@@ -0,0 +1,258 @@
+//! # Org-mode Serializer
+//!
+//! Renders Logseq block trees as Org-mode text: each block becomes a
+//! heading (`*`, `**`, ...), `key:: value` properties become a
+//! `:PROPERTIES:` drawer, fenced code becomes `#+BEGIN_SRC`/`#+END_SRC`,
+//! and inline markdown is rewritten to Org markup (`**bold**` → `*bold*`,
+//! `_italic_` → `/italic/`, `` `code` `` → `~code~`,
+//! `[text](url)` → `[[url][text]]`). `[[Page]]` links and task markers
+//! such as `TODO`/`DONE` are already valid Org and are kept as they are.
+
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// Renders a block tree as Org-mode, with block properties as drawers.
+///
+/// `depth` is the nesting level of `blocks`; top-level blocks at depth 0
+/// become `*` headings.
+pub fn block_tree_to_org(blocks: &Value, depth: usize) -> String {
+    let mut out = String::new();
+    render_tree(blocks, depth, true, &mut out);
+    out
+}
+
+/// Like [`block_tree_to_org`], but drops `key:: value` properties.
+pub fn block_tree_to_org_without_properties(blocks: &Value, depth: usize) -> String {
+    let mut out = String::new();
+    render_tree(blocks, depth, false, &mut out);
+    out
+}
+
+fn render_tree(blocks: &Value, depth: usize, include_properties: bool, out: &mut String) {
+    for block in blocks.as_array().into_iter().flatten() {
+        let content = block["content"].as_str().unwrap_or("");
+        let (properties, text) = split_properties(content);
+
+        // The page-level properties block has no heading of its own
+        if block["preBlock?"].as_bool() == Some(true) {
+            if include_properties {
+                push_drawer(&properties, out);
+            }
+            continue;
+        }
+
+        let mut lines = text.iter();
+        let title = lines.next().map(|l| strip_heading_marker(l)).unwrap_or("");
+        out.push_str(&"*".repeat(depth + 1));
+        if !title.is_empty() {
+            out.push(' ');
+            out.push_str(&convert_inline(title));
+        }
+        out.push('\n');
+        if include_properties {
+            push_drawer(&properties, out);
+        }
+        push_body(lines.copied(), out);
+
+        render_tree(&block["children"], depth + 1, include_properties, out);
+    }
+}
+
+/// Splits block content into its `key:: value` properties and other lines.
+fn split_properties(content: &str) -> (Vec<(&str, &str)>, Vec<&str>) {
+    let mut properties = Vec::new();
+    let mut text = Vec::new();
+    let mut in_code = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        match line.split_once(":: ") {
+            Some((key, value)) if !in_code && is_property_key(key) => {
+                properties.push((key.trim(), value.trim()));
+            }
+            _ => text.push(line),
+        }
+    }
+    (properties, text)
+}
+
+fn is_property_key(key: &str) -> bool {
+    let key = key.trim();
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))
+}
+
+fn push_drawer(properties: &[(&str, &str)], out: &mut String) {
+    if properties.is_empty() {
+        return;
+    }
+    out.push_str(":PROPERTIES:\n");
+    for (key, value) in properties {
+        out.push_str(&format!(":{}: {}\n", key, value));
+    }
+    out.push_str(":END:\n");
+}
+
+/// Writes the lines after a block's title, turning fenced code into source
+/// blocks and converting inline markup everywhere else.
+fn push_body<'a>(lines: impl Iterator<Item = &'a str>, out: &mut String) {
+    let mut in_code = false;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            if in_code {
+                out.push_str("#+END_SRC\n");
+            } else {
+                out.push_str("#+BEGIN_SRC");
+                if !lang.trim().is_empty() {
+                    out.push(' ');
+                    out.push_str(lang.trim());
+                }
+                out.push('\n');
+            }
+            in_code = !in_code;
+        } else if in_code {
+            out.push_str(line);
+            out.push('\n');
+        } else {
+            out.push_str(&convert_inline(line));
+            out.push('\n');
+        }
+    }
+    if in_code {
+        out.push_str("#+END_SRC\n");
+    }
+}
+
+/// Drops a markdown heading prefix (`## Title`), since the block is
+/// already an Org heading.
+fn strip_heading_marker(line: &str) -> &str {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    match line[hashes..].strip_prefix(' ') {
+        Some(rest) if hashes > 0 => rest,
+        _ => line,
+    }
+}
+
+/// Matches the inline markdown constructs that differ in Org.
+static INLINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"`(?P<code>[^`]+)`",
+        r"|\*\*(?P<bold>[^*]+)\*\*",
+        r"|\*(?P<star>[^*\s][^*]*)\*",
+        r"|\b_(?P<under>[^_\s][^_]*)_\b",
+        r"|\[(?P<label>[^\[\]]+)\]\((?P<url>[^()\s]+)\)",
+    ))
+    .expect("valid inline markup regex")
+});
+
+/// Rewrites inline markdown in one line to Org markup.
+fn convert_inline(line: &str) -> String {
+    INLINE_RE
+        .replace_all(line, |caps: &Captures| {
+            if let Some(code) = caps.name("code") {
+                format!("~{}~", code.as_str())
+            } else if let Some(bold) = caps.name("bold") {
+                format!("*{}*", bold.as_str())
+            } else if let Some(italic) = caps.name("star").or(caps.name("under")) {
+                format!("/{}/", italic.as_str())
+            } else {
+                format!("[[{}][{}]]", &caps["url"], &caps["label"])
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nesting_becomes_heading_levels() {
+        let blocks = json!([
+            {"content": "Parent", "children": [
+                {"content": "Child", "children": [{"content": "Grandchild"}]}
+            ]},
+            {"content": "Sibling"}
+        ]);
+        assert_eq!(
+            block_tree_to_org(&blocks, 0),
+            "* Parent\n** Child\n*** Grandchild\n* Sibling\n"
+        );
+        assert_eq!(block_tree_to_org(&json!([{"content": "x"}]), 2), "*** x\n");
+    }
+
+    #[test]
+    fn test_links_and_task_markers_kept() {
+        let blocks = json!([
+            {"content": "TODO Read [[Rust Book]]"},
+            {"content": "DONE [#A] Ship [[Release/1.0]]"}
+        ]);
+        assert_eq!(
+            block_tree_to_org(&blocks, 0),
+            "* TODO Read [[Rust Book]]\n* DONE [#A] Ship [[Release/1.0]]\n"
+        );
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        assert_eq!(
+            convert_inline("**bold**, _italic_, *also italic* and `code`"),
+            "*bold*, /italic/, /also italic/ and ~code~"
+        );
+        assert_eq!(
+            convert_inline("see [docs](https://example.com)"),
+            "see [[https://example.com][docs]]"
+        );
+        // Underscores inside words aren't emphasis
+        assert_eq!(convert_inline("snake_case_name"), "snake_case_name");
+        // Markup inside inline code is left alone
+        assert_eq!(convert_inline("`**not bold**`"), "~**not bold**~");
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let blocks = json!([
+            {"content": "Example\n```rust\nlet x = **y**;\nkey:: not a property\n```"}
+        ]);
+        assert_eq!(
+            block_tree_to_org(&blocks, 0),
+            "* Example\n#+BEGIN_SRC rust\nlet x = **y**;\nkey:: not a property\n#+END_SRC\n"
+        );
+
+        let unclosed = json!([{"content": "Snippet\n```\necho hi"}]);
+        assert_eq!(
+            block_tree_to_org(&unclosed, 0),
+            "* Snippet\n#+BEGIN_SRC\necho hi\n#+END_SRC\n"
+        );
+    }
+
+    #[test]
+    fn test_properties_become_drawers() {
+        let blocks = json!([
+            {"content": "tags:: rust\nalias:: r", "preBlock?": true},
+            {"content": "## Task\nstatus:: open\nDetails here"}
+        ]);
+        assert_eq!(
+            block_tree_to_org(&blocks, 0),
+            ":PROPERTIES:\n:tags: rust\n:alias: r\n:END:\n\
+             * Task\n:PROPERTIES:\n:status: open\n:END:\nDetails here\n"
+        );
+        assert_eq!(
+            block_tree_to_org_without_properties(&blocks, 0),
+            "* Task\nDetails here\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(block_tree_to_org(&json!([]), 0), "");
+        assert_eq!(block_tree_to_org(&Value::Null, 0), "");
+        assert_eq!(block_tree_to_org(&json!([{"content": ""}]), 0), "*\n");
+    }
+}
@@ -0,0 +1,116 @@
+//! # Progress Notifications
+//!
+//! Long-running tools can report progress with MCP `notifications/progress`
+//! messages. A client opts in by sending `_meta.progressToken` in the
+//! request params; without a token, reporting is a no-op.
+//!
+//! Notifications share the server's output stream with responses, so every
+//! write goes through the same mutex to keep lines from interleaving.
+
+use serde_json::{Value, json};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Output stream shared by responses and notifications.
+pub type SharedWriter = Arc<Mutex<dyn Write + Send>>;
+
+/// Sends progress notifications for a single request.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    /// The client's `_meta.progressToken`, if it asked for progress
+    token: Option<Value>,
+    /// Where notifications are written (stdout in production)
+    out: SharedWriter,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter that writes to `out` when `token` is set.
+    pub fn new(token: Option<Value>, out: SharedWriter) -> Self {
+        Self { token, out }
+    }
+
+    /// Creates a reporter that never sends anything.
+    pub fn disabled() -> Self {
+        Self::new(None, Arc::new(Mutex::new(std::io::sink())))
+    }
+
+    /// Extracts `params._meta.progressToken` from a request's params.
+    pub fn token_from_params(params: Option<&Value>) -> Option<Value> {
+        params
+            .and_then(|p| p.get("_meta"))
+            .and_then(|meta| meta.get("progressToken"))
+            .filter(|token| token.is_string() || token.is_number())
+            .cloned()
+    }
+
+    /// Writes a `notifications/progress` message, if the client asked for one.
+    ///
+    /// Write failures are logged rather than returned: a lost progress update
+    /// shouldn't fail the tool call that produced it.
+    pub fn report(&self, progress: f64, total: f64, message: &str) {
+        let Some(token) = &self.token else {
+            return;
+        };
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": {
+                "progressToken": token,
+                "progress": progress,
+                "total": total,
+                "message": message
+            }
+        });
+
+        let mut out = match self.out.lock() {
+            Ok(out) => out,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(e) = writeln!(out, "{}", notification).and_then(|_| out.flush()) {
+            tracing::warn!("Failed to write progress notification: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_writes_notification_line() {
+        let buffer = Arc::new(Mutex::new(Vec::<u8>::new()));
+        let reporter = ProgressReporter::new(Some(json!("tok-1")), buffer.clone());
+
+        reporter.report(1.0, 3.0, "Inserted block 1 of 3");
+        reporter.report(2.0, 3.0, "Inserted block 2 of 3");
+
+        let output = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["method"], "notifications/progress");
+        assert_eq!(lines[0]["params"]["progressToken"], "tok-1");
+        assert_eq!(lines[1]["params"]["progress"], 2.0);
+        assert_eq!(lines[1]["params"]["total"], 3.0);
+    }
+
+    #[test]
+    fn test_report_without_token_is_silent() {
+        let buffer = Arc::new(Mutex::new(Vec::<u8>::new()));
+        ProgressReporter::new(None, buffer.clone()).report(1.0, 1.0, "done");
+        assert!(buffer.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_token_from_params() {
+        let params = json!({"name": "x", "_meta": {"progressToken": 7}});
+        assert_eq!(
+            ProgressReporter::token_from_params(Some(&params)),
+            Some(json!(7))
+        );
+        assert_eq!(ProgressReporter::token_from_params(Some(&json!({}))), None);
+        assert_eq!(ProgressReporter::token_from_params(None), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! # MCP Prompts
+//!
+//! Exposes Logseq templates through the MCP prompts capability. Any page
+//! with a `template::` property becomes a prompt named after the template;
+//! getting the prompt returns the page's blocks as user messages.
+
+use crate::{
+    error::{McpError, McpResult},
+    logseq_client::LogseqClient,
+    utils::format_block_tree_as_markdown,
+};
+use serde_json::{Value, json};
+
+/// Lists all template pages as MCP prompts.
+///
+/// # Returns
+///
+/// `{ "prompts": [{ "name", "description"? }] }`, sorted by name.
+pub async fn list_prompts(client: &LogseqClient) -> McpResult<Value> {
+    let pages = client.get_all_pages().await?;
+    let prompts: Vec<Value> = template_pages(&pages)
+        .into_iter()
+        .map(|t| {
+            let mut prompt = json!({ "name": t.name });
+            if let Some(description) = t.description {
+                prompt["description"] = description.into();
+            }
+            prompt
+        })
+        .collect();
+
+    Ok(json!({ "prompts": prompts }))
+}
+
+/// Gets a template as a sequence of prompt messages.
+///
+/// # Parameters
+///
+/// - `name` (required): The template name, as returned by `prompts/list`
+///
+/// # Returns
+///
+/// `{ "description"?, "messages": [{ "role": "user", "content": { "type": "text", "text" } }] }`
+/// with one message per top-level block (rendered with its children).
+///
+/// # Errors
+///
+/// Returns `McpError::InvalidParams` if the name is missing or no template
+/// page has that name.
+pub async fn get_prompt(client: &LogseqClient, params: &Value) -> McpResult<Value> {
+    let name = params["name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("name parameter is required".to_string()))?;
+
+    let pages = client.get_all_pages().await?;
+    let template = template_pages(&pages)
+        .into_iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| McpError::InvalidParams(format!("Unknown prompt '{}'", name)))?;
+
+    let blocks = client.get_page_blocks_tree(&template.page).await?;
+    let mut result = json!({ "messages": template_messages(&blocks) });
+    if let Some(description) = template.description {
+        result["description"] = description.into();
+    }
+    Ok(result)
+}
+
+/// A page marked with `template:: <name>`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct TemplatePage {
+    /// Template name (the `template` property value)
+    pub(crate) name: String,
+    /// Page name to fetch blocks from
+    pub(crate) page: String,
+    /// The page's UUID
+    pub(crate) uuid: Option<String>,
+    /// The page's `description` property, if set
+    pub(crate) description: Option<String>,
+}
+
+/// Picks template pages out of `getAllPages` output, sorted by template name.
+///
+/// `getAllPages` already includes each page's properties, so no per-page
+/// lookups are needed.
+pub(crate) fn template_pages(pages: &Value) -> Vec<TemplatePage> {
+    let mut templates: Vec<TemplatePage> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|page| {
+            let props = &page["properties"];
+            let name = match &props["template"] {
+                Value::String(s) => s.trim().to_string(),
+                Value::Array(items) => items.first()?.as_str()?.trim().to_string(),
+                _ => return None,
+            };
+            let page_name = page["originalName"].as_str().or(page["name"].as_str())?;
+            Some(TemplatePage {
+                name: if name.is_empty() {
+                    page_name.to_string()
+                } else {
+                    name
+                },
+                page: page_name.to_string(),
+                uuid: page["uuid"].as_str().map(str::to_string),
+                description: props["description"].as_str().map(str::to_string),
+            })
+        })
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+/// Turns a template page's block tree into user messages, one per top-level
+/// block. The properties block (`preBlock?`) and empty blocks are skipped.
+fn template_messages(blocks: &Value) -> Vec<Value> {
+    blocks
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|block| block["preBlock?"].as_bool() != Some(true))
+        .map(|block| format_block_tree_as_markdown(&json!([block]), 0))
+        .filter(|text| text.trim() != "-")
+        .map(|text| {
+            json!({
+                "role": "user",
+                "content": { "type": "text", "text": text }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_pages_maps_properties() {
+        let pages = json!([
+            {"name": "meeting notes", "originalName": "Meeting Notes", "uuid": "p1",
+             "properties": {"template": "meeting", "description": "Weekly sync"}},
+            {"name": "rust", "properties": {"tags": ["lang"]}},
+            {"name": "daily", "properties": {"template": ["daily-review"]}}
+        ]);
+
+        assert_eq!(
+            template_pages(&pages),
+            vec![
+                TemplatePage {
+                    name: "daily-review".to_string(),
+                    page: "daily".to_string(),
+                    uuid: None,
+                    description: None,
+                },
+                TemplatePage {
+                    name: "meeting".to_string(),
+                    page: "Meeting Notes".to_string(),
+                    uuid: Some("p1".to_string()),
+                    description: Some("Weekly sync".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template_messages_skips_properties_block() {
+        let blocks = json!([
+            {"content": "template:: meeting", "preBlock?": true},
+            {"content": "Attendees", "children": [{"content": "Alice"}]},
+            {"content": ""},
+            {"content": "Action items"}
+        ]);
+
+        let messages = template_messages(&blocks);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["role"], "user");
+        assert_eq!(messages[0]["content"]["type"], "text");
+        assert_eq!(messages[0]["content"]["text"], "- Attendees\n  - Alice\n");
+        assert_eq!(messages[1]["content"]["text"], "- Action items\n");
+    }
+}
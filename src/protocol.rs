@@ -4,23 +4,47 @@
 //! It provides abstractions for request/response handling, error codes, and
 //! protocol-specific logic, separating these concerns from business logic.
 
+mod limited_reader;
 mod response;
+pub use limited_reader::{LimitedReader, ReadLine};
 pub use response::HandlerResponse;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// JSON-RPC 2.0 Request structure
 #[derive(Debug, Deserialize, Clone)]
 pub struct JsonRpcRequest {
-    #[allow(dead_code)]
     pub jsonrpc: String,
     pub method: String,
     pub params: Option<Value>,
+    /// `None` both when the `id` member is absent and when it is `null`;
+    /// either way the request is a notification and gets no response.
     pub id: Option<Value>,
 }
 
+impl JsonRpcRequest {
+    /// Whether this request is a notification, i.e. has no usable id.
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// Returns a server-generated id for a request that arrived without one.
+///
+/// Handlers always work with a concrete id, so notifications get one of
+/// these for logging. They are strings with a prefix no client uses, so they
+/// can't collide with a real request id such as `0`.
+pub fn generate_request_id() -> Value {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    json!(format!(
+        "mcp-logseq-internal-{}",
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
 /// JSON-RPC 2.0 Response structure
 #[derive(Debug, Serialize)]
 pub struct JsonRpcResponse {
@@ -44,11 +68,14 @@ pub struct JsonRpcError {
 /// Standard JSON-RPC error codes
 pub mod error_codes {
     pub const PARSE_ERROR: i32 = -32700;
-    #[allow(dead_code)]
     pub const INVALID_REQUEST: i32 = -32600;
     pub const METHOD_NOT_FOUND: i32 = -32601;
     pub const INVALID_PARAMS: i32 = -32602;
     pub const INTERNAL_ERROR: i32 = -32603;
+    /// Server-defined: a Logseq API request timed out
+    pub const REQUEST_TIMEOUT: i32 = -32001;
+    /// The request was cancelled via `notifications/cancelled` (draft MCP spec)
+    pub const REQUEST_CANCELLED: i32 = -32800;
 }
 
 /// Response builder for creating JSON-RPC responses
@@ -67,23 +94,47 @@ impl ResponseBuilder {
 
     /// Creates an error response
     pub fn error(id: Value, code: i32, message: String) -> JsonRpcResponse {
+        Self::error_with_data(id, code, message, None)
+    }
+
+    /// Creates an error response with optional structured `data`
+    pub fn error_with_data(
+        id: Value,
+        code: i32,
+        message: String,
+        data: Option<Value>,
+    ) -> JsonRpcResponse {
         JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             result: None,
             error: Some(JsonRpcError {
                 code,
                 message,
-                data: None,
+                data,
             }),
             id,
         }
     }
 
-    /// Creates a parse error response (id unknown, use 0 per spec)
+    /// Creates the parse error sent for a request longer than `limit` bytes.
+    ///
+    /// The request was never read, so its id is unknown and reported as null.
+    pub fn request_too_large(limit: usize) -> JsonRpcResponse {
+        Self::error(
+            Value::Null,
+            error_codes::PARSE_ERROR,
+            format!("Parse error: request exceeds the {} byte limit", limit),
+        )
+    }
+
+    /// Creates a parse error response.
+    ///
+    /// The request couldn't be parsed, so its id is unknown and, per
+    /// JSON-RPC 2.0, reported as null. A real request can't have a null id,
+    /// so this never collides with one.
     pub fn parse_error() -> JsonRpcResponse {
-        use serde_json::json;
         Self::error(
-            json!(0),
+            Value::Null,
             error_codes::PARSE_ERROR,
             "Parse error".to_string(),
         )
@@ -99,6 +150,7 @@ pub fn parse_request(input: &str) -> Result<JsonRpcRequest> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use serde_json::json;
 
     // --- parse_request tests ---
@@ -146,9 +198,9 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_error_uses_id_zero() {
+    fn test_parse_error_uses_null_id() {
         let resp = ResponseBuilder::parse_error();
-        assert_eq!(resp.id, json!(0));
+        assert_eq!(resp.id, Value::Null);
         assert_eq!(resp.error.unwrap().code, error_codes::PARSE_ERROR);
     }
 
@@ -166,8 +218,137 @@ mod tests {
         assert!(!resp.is_notification_ack());
     }
 
+    // --- property tests ---
+
+    /// Arbitrary JSON scalars, used for ids and param values.
+    fn json_scalar() -> impl Strategy<Value = Value> {
+        prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
+            // Quarters round-trip exactly through serde_json's float parser
+            any::<i32>().prop_map(|n| Value::from(f64::from(n) / 4.0)),
+            ".*".prop_map(Value::from),
+        ]
+    }
+
+    /// Arbitrary JSON values up to a few levels deep.
+    fn json_value() -> impl Strategy<Value = Value> {
+        json_scalar().prop_recursive(3, 32, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(Value::from),
+                prop::collection::hash_map("[a-z_]{1,8}", inner, 0..4)
+                    .prop_map(|m| Value::Object(m.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// Valid JSON-RPC ids (numbers or strings).
+    fn request_id() -> impl Strategy<Value = Value> {
+        prop_oneof![
+            any::<i64>().prop_map(Value::from),
+            "[a-zA-Z0-9-]{0,16}".prop_map(Value::from),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_well_formed_requests_parse(
+            method in "[a-z/_]{1,24}",
+            id in prop::option::of(request_id()),
+            params in prop::option::of(json_value()),
+        ) {
+            let mut request = json!({"jsonrpc": "2.0", "method": method});
+            if let Some(id) = &id {
+                request["id"] = id.clone();
+            }
+            if let Some(params) = &params {
+                request["params"] = params.clone();
+            }
+
+            let parsed = parse_request(&request.to_string()).unwrap();
+            prop_assert_eq!(parsed.jsonrpc, "2.0");
+            prop_assert_eq!(parsed.method, method);
+            prop_assert_eq!(parsed.id, id);
+            // An explicit `"params": null` is indistinguishable from absent
+            prop_assert_eq!(parsed.params, params.filter(|p| !p.is_null()));
+        }
+
+        #[test]
+        fn prop_random_input_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+            let _ = parse_request(&String::from_utf8_lossy(&bytes));
+        }
+
+        #[test]
+        fn prop_random_strings_never_panic(input in ".*") {
+            let _ = parse_request(&input);
+        }
+
+        #[test]
+        fn prop_null_id_is_none(method in "[a-z/_]{1,24}") {
+            let input = json!({"jsonrpc": "2.0", "method": method, "id": null});
+            prop_assert!(parse_request(&input.to_string()).unwrap().id.is_none());
+        }
+
+        #[test]
+        fn prop_unknown_fields_are_ignored(
+            id in request_id(),
+            extra in prop::collection::hash_map("x_[a-z]{1,8}", json_value(), 1..4),
+        ) {
+            let mut request = json!({"jsonrpc": "2.0", "method": "tools/list", "id": id});
+            for (key, value) in extra {
+                request[key] = value;
+            }
+            let parsed = parse_request(&request.to_string()).unwrap();
+            prop_assert_eq!(parsed.method, "tools/list");
+            prop_assert_eq!(parsed.id, Some(id));
+        }
+
+        #[test]
+        fn prop_success_serializes_version_and_id(id in json_scalar(), result in json_value()) {
+            let out = serde_json::to_value(ResponseBuilder::success(id.clone(), result.clone())).unwrap();
+            prop_assert_eq!(&out["jsonrpc"], "2.0");
+            prop_assert_eq!(&out["id"], &id);
+            prop_assert_eq!(&out["result"], &result);
+            prop_assert!(out.get("error").is_none());
+        }
+
+        #[test]
+        fn prop_error_serializes_version_and_id(
+            id in json_scalar(),
+            code in any::<i32>(),
+            message in ".*",
+        ) {
+            let out = serde_json::to_value(ResponseBuilder::error(id.clone(), code, message.clone())).unwrap();
+            prop_assert_eq!(&out["jsonrpc"], "2.0");
+            prop_assert_eq!(&out["id"], &id);
+            prop_assert_eq!(&out["error"]["code"], code);
+            prop_assert_eq!(&out["error"]["message"], message.as_str());
+            prop_assert!(out.get("result").is_none());
+        }
+    }
+
     // TODO(human): Add 2-4 more test cases covering edge cases you think are important.
     // Consider: string ids ("abc"), explicit null id, requests with params object,
     // malformed-but-valid JSON (missing method field), or additional error codes.
     // Pattern: #[test] fn test_your_case() { ... }
+
+    #[test]
+    fn test_missing_and_null_ids_are_notifications() {
+        let missing = parse_request(r#"{"jsonrpc":"2.0","method":"ping"}"#).unwrap();
+        let null = parse_request(r#"{"jsonrpc":"2.0","method":"ping","id":null}"#).unwrap();
+        let zero = parse_request(r#"{"jsonrpc":"2.0","method":"ping","id":0}"#).unwrap();
+        assert!(missing.is_notification());
+        assert!(null.is_notification());
+        assert!(!zero.is_notification());
+        assert_eq!(zero.id, Some(json!(0)));
+    }
+
+    #[test]
+    fn test_generate_request_id_is_unique() {
+        let a = generate_request_id();
+        let b = generate_request_id();
+        assert_ne!(a, b);
+        assert!(a.as_str().unwrap().starts_with("mcp-logseq-internal-"));
+    }
 }
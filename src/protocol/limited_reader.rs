@@ -0,0 +1,142 @@
+//! Line reader with a per-line size cap, so one huge request can't make the
+//! server buffer unbounded input.
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// One line read by [`LimitedReader`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReadLine {
+    /// A complete line, without its trailing `\n` or `\r\n`
+    Line(String),
+    /// A line longer than the limit; its bytes were discarded unread
+    TooLong,
+}
+
+/// Reads newline-delimited input, dropping any line longer than `limit` bytes.
+///
+/// Bytes of an oversized line are skipped as they arrive rather than
+/// collected, so memory use stays bounded by `limit` plus the underlying
+/// buffer. Reading then resumes at the next line.
+///
+/// [`next_line`](Self::next_line) is cancel-safe: partial lines are kept
+/// in the reader, so losing a `tokio::select!` race loses no input.
+pub struct LimitedReader<R> {
+    inner: R,
+    limit: usize,
+    line: Vec<u8>,
+    oversized: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> LimitedReader<R> {
+    /// Wraps `inner`, allowing lines of at most `limit` bytes (excluding the newline).
+    pub fn new(inner: R, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            line: Vec::new(),
+            oversized: false,
+        }
+    }
+
+    /// The longest line accepted, in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Reads the next line, or returns `None` at end of input.
+    ///
+    /// Invalid UTF-8 is replaced rather than treated as an I/O error, so it
+    /// surfaces as a JSON parse error for that line alone.
+    pub async fn next_line(&mut self) -> std::io::Result<Option<ReadLine>> {
+        loop {
+            let available = self.inner.fill_buf().await?;
+            if available.is_empty() {
+                // A final line without a trailing newline still counts
+                if self.line.is_empty() && !self.oversized {
+                    return Ok(None);
+                }
+                return Ok(Some(self.take_line()));
+            }
+
+            let newline = available.iter().position(|&b| b == b'\n');
+            let content = &available[..newline.unwrap_or(available.len())];
+            if !self.oversized {
+                if self.line.len() + content.len() > self.limit {
+                    self.oversized = true;
+                    self.line = Vec::new();
+                } else {
+                    self.line.extend_from_slice(content);
+                }
+            }
+
+            let used = newline.map_or(available.len(), |i| i + 1);
+            self.inner.consume(used);
+            if newline.is_some() {
+                return Ok(Some(self.take_line()));
+            }
+        }
+    }
+
+    fn take_line(&mut self) -> ReadLine {
+        if std::mem::take(&mut self.oversized) {
+            return ReadLine::TooLong;
+        }
+        let mut line = std::mem::take(&mut self.line);
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        ReadLine::Line(String::from_utf8_lossy(&line).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    async fn read_all(input: &[u8], limit: usize, buffer: usize) -> Vec<ReadLine> {
+        let mut reader = LimitedReader::new(BufReader::with_capacity(buffer, input), limit);
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line().await.unwrap() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn line(s: &str) -> ReadLine {
+        ReadLine::Line(s.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_reads_lines() {
+        let lines = read_all(b"one\r\ntwo\n\nlast", 10, 8192).await;
+        assert_eq!(
+            lines,
+            vec![line("one"), line("two"), line(""), line("last")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skips_oversized_line_and_recovers() {
+        // A tiny buffer makes the long line arrive across several reads
+        let input = b"abcde\nabcdefghijk\nok\nabcdefghijk";
+        for buffer in [2, 4, 8192] {
+            let lines = read_all(input, 5, buffer).await;
+            assert_eq!(
+                lines,
+                vec![
+                    line("abcde"),
+                    ReadLine::TooLong,
+                    line("ok"),
+                    ReadLine::TooLong
+                ]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_utf8_is_replaced() {
+        let lines = read_all(b"\xff{}\n", 10, 8192).await;
+        assert_eq!(lines, vec![line("\u{fffd}{}")]);
+    }
+}
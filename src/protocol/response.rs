@@ -25,6 +25,11 @@ impl HandlerResponse {
         HandlerResponse::Response(ResponseBuilder::error(id, code, message))
     }
 
+    /// Creates an error response carrying structured `error.data`
+    pub fn error_with_data(id: Value, code: i32, message: String, data: Option<Value>) -> Self {
+        HandlerResponse::Response(ResponseBuilder::error_with_data(id, code, message, data))
+    }
+
     /// Creates a notification acknowledgment
     pub fn notification_ack() -> Self {
         HandlerResponse::NotificationAck
@@ -0,0 +1,135 @@
+//! # MCP Resources
+//!
+//! Exposes Logseq pages through the MCP resources capability. Every page is
+//! a resource with a `logseq://page/<page name>` URI; reading it returns the
+//! page's block tree rendered as a markdown outline.
+
+use crate::{
+    error::{McpError, McpResult},
+    logseq_client::LogseqClient,
+    tools::query,
+    utils::format_block_tree_as_markdown,
+};
+use serde_json::{Value, json};
+
+/// URI prefix for page resources.
+const PAGE_URI_PREFIX: &str = "logseq://page/";
+
+/// Lists every page in the current graph as a markdown resource.
+///
+/// # Returns
+///
+/// `{ "resources": [{ "uri", "name", "mimeType" }] }` in `getAllPages` order.
+pub async fn list_resources(client: &LogseqClient) -> McpResult<Value> {
+    let pages = client.get_all_pages().await?;
+    let resources: Vec<Value> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|page| page["originalName"].as_str().or(page["name"].as_str()))
+        .map(|name| {
+            json!({
+                "uri": page_uri(name),
+                "name": name,
+                "mimeType": "text/markdown"
+            })
+        })
+        .collect();
+
+    Ok(json!({ "resources": resources }))
+}
+
+/// Reads a page resource as markdown.
+///
+/// # Parameters
+///
+/// - `uri` (required): A `logseq://page/<page name>` URI
+///
+/// # Returns
+///
+/// `{ "contents": [{ "uri", "mimeType": "text/markdown", "text" }] }`
+///
+/// # Errors
+///
+/// Returns `McpError::InvalidParams` if the uri is missing or not a page URI,
+/// and an error if the page doesn't exist.
+pub async fn read_resource(client: &LogseqClient, params: &Value) -> McpResult<Value> {
+    let uri = params["uri"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uri parameter is required".to_string()))?;
+    let page_name = parse_page_uri(uri)
+        .ok_or_else(|| McpError::InvalidParams(format!("Unsupported resource URI '{}'", uri)))?;
+
+    let page = query::get_page(client, json!({ "page_name": page_name })).await?;
+    if page["page"].is_null() {
+        return Err(McpError::Internal(format!(
+            "Page '{}' not found",
+            page_name
+        )));
+    }
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "text/markdown",
+            "text": format_block_tree_as_markdown(&page["blocks"], 0)
+        }]
+    }))
+}
+
+/// Builds the resource URI for a page, percent-encoding characters that
+/// aren't URI-safe. `/` is kept so namespaces read naturally.
+fn page_uri(page_name: &str) -> String {
+    let mut uri = String::from(PAGE_URI_PREFIX);
+    for byte in page_name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Extracts and percent-decodes the page name from a page URI.
+fn parse_page_uri(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix(PAGE_URI_PREFIX)?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = encoded.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    let name = String::from_utf8(decoded).ok()?;
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_uri_round_trip() {
+        let uri = page_uri("Projects/Q1 Plan & Notes");
+        assert_eq!(uri, "logseq://page/Projects/Q1%20Plan%20%26%20Notes");
+        assert_eq!(parse_page_uri(&uri).unwrap(), "Projects/Q1 Plan & Notes");
+
+        let unicode = page_uri("café");
+        assert_eq!(parse_page_uri(&unicode).unwrap(), "café");
+    }
+
+    #[test]
+    fn test_parse_page_uri_rejects_other_schemes() {
+        assert_eq!(parse_page_uri("logseq://block/abc"), None);
+        assert_eq!(parse_page_uri("logseq://page/"), None);
+        assert_eq!(parse_page_uri("logseq://page/bad%2"), None);
+    }
+}
@@ -13,6 +13,8 @@ pub struct ToolBuilder {
     description: Option<String>,
     properties: HashMap<String, Value>,
     required: Vec<String>,
+    /// Most recently added parameter, which constraint methods apply to
+    last_param: Option<String>,
 }
 
 impl ToolBuilder {
@@ -23,6 +25,7 @@ impl ToolBuilder {
             description: None,
             properties: HashMap::new(),
             required: Vec::new(),
+            last_param: None,
         }
     }
 
@@ -40,6 +43,7 @@ impl ToolBuilder {
         required: bool,
     ) -> Self {
         let param_name = name.into();
+        self.last_param = Some(param_name.clone());
         self.properties.insert(
             param_name.clone(),
             json!({
@@ -62,6 +66,7 @@ impl ToolBuilder {
         required: bool,
     ) -> Self {
         let param_name = name.into();
+        self.last_param = Some(param_name.clone());
         let mut param_def = json!({
             "type": "boolean",
             "description": description.into()
@@ -79,7 +84,6 @@ impl ToolBuilder {
     }
 
     /// Adds an integer parameter to the tool
-    #[allow(dead_code)]
     pub fn int_param(
         mut self,
         name: impl Into<String>,
@@ -87,6 +91,7 @@ impl ToolBuilder {
         required: bool,
     ) -> Self {
         let param_name = name.into();
+        self.last_param = Some(param_name.clone());
         self.properties.insert(
             param_name.clone(),
             json!({
@@ -100,6 +105,121 @@ impl ToolBuilder {
         self
     }
 
+    /// Adds a floating-point number parameter to the tool
+    pub fn number_param(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        required: bool,
+    ) -> Self {
+        let param_name = name.into();
+        self.last_param = Some(param_name.clone());
+        self.properties.insert(
+            param_name.clone(),
+            json!({
+                "type": "number",
+                "description": description.into()
+            }),
+        );
+        if required {
+            self.required.push(param_name);
+        }
+        self
+    }
+
+    /// Adds an array parameter whose items are of the given JSON Schema type
+    pub fn array_param(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        item_type: &str,
+        required: bool,
+    ) -> Self {
+        let param_name = name.into();
+        self.last_param = Some(param_name.clone());
+        self.properties.insert(
+            param_name.clone(),
+            json!({
+                "type": "array",
+                "description": description.into(),
+                "items": { "type": item_type }
+            }),
+        );
+        if required {
+            self.required.push(param_name);
+        }
+        self
+    }
+
+    /// Adds a string parameter restricted to a fixed set of values.
+    ///
+    /// The values are published as a JSON Schema `enum`, so clients can offer
+    /// them as choices, and `tools/call` arguments outside the set are rejected.
+    pub fn enum_param<V: Into<String>>(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        values: impl IntoIterator<Item = V>,
+        required: bool,
+    ) -> Self {
+        let param_name = name.into();
+        self.last_param = Some(param_name.clone());
+        let values: Vec<String> = values.into_iter().map(Into::into).collect();
+        self.properties.insert(
+            param_name.clone(),
+            json!({
+                "type": "string",
+                "description": description.into(),
+                "enum": values
+            }),
+        );
+        if required {
+            self.required.push(param_name);
+        }
+        self
+    }
+
+    /// Sets the smallest value the most recently added integer or number parameter accepts
+    pub fn min(self, min: f64) -> Self {
+        self.constrain_last(&["integer", "number"], "minimum", json!(min))
+    }
+
+    /// Sets the largest value the most recently added integer or number parameter accepts
+    pub fn max(self, max: f64) -> Self {
+        self.constrain_last(&["integer", "number"], "maximum", json!(max))
+    }
+
+    /// Requires the most recently added array parameter to have at least `n` items
+    pub fn min_items(self, n: usize) -> Self {
+        self.constrain_last(&["array"], "minItems", json!(n))
+    }
+
+    /// Limits the most recently added array parameter to at most `n` items
+    pub fn max_items(self, n: usize) -> Self {
+        self.constrain_last(&["array"], "maxItems", json!(n))
+    }
+
+    /// Adds a schema keyword to the most recently added parameter, which must
+    /// have one of `types`. Misuse is a bug in a tool definition, so it is
+    /// caught by a debug assertion rather than reported at runtime.
+    fn constrain_last(mut self, types: &[&str], key: &str, value: Value) -> Self {
+        let property = self
+            .last_param
+            .as_ref()
+            .and_then(|name| self.properties.get_mut(name))
+            .filter(|p| p["type"].as_str().is_some_and(|t| types.contains(&t)));
+        debug_assert!(
+            property.is_some(),
+            "{} must follow a {} parameter",
+            key,
+            types.join("/")
+        );
+        if let Some(property) = property {
+            property[key] = value;
+        }
+        self
+    }
+
     /// Builds the final Tool instance
     pub fn build(self) -> Tool {
         Tool {
@@ -139,3 +259,174 @@ pub fn single_string_param_tool(
         .string_param(param_name, param_desc, true)
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enum_param_schema() {
+        let tool = ToolBuilder::new("t")
+            .enum_param("mode", "Mode", ["fast", "slow"], true)
+            .enum_param("kind", "Kind", vec!["a".to_string()], false)
+            .build();
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(
+            props["mode"],
+            json!({"type": "string", "description": "Mode", "enum": ["fast", "slow"]})
+        );
+        assert_eq!(props["kind"]["enum"], json!(["a"]));
+        assert_eq!(tool.input_schema.required, Some(vec!["mode".to_string()]));
+    }
+
+    #[test]
+    fn test_array_param_item_bounds() {
+        let tool = ToolBuilder::new("t")
+            .array_param("ids", "Ids", "string", true)
+            .min_items(1)
+            .max_items(10)
+            .string_param("other", "Other", false)
+            .build();
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(
+            props["ids"],
+            json!({
+                "type": "array",
+                "description": "Ids",
+                "items": {"type": "string"},
+                "minItems": 1,
+                "maxItems": 10
+            })
+        );
+        assert!(props["other"].get("minItems").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "minItems must follow a array parameter")]
+    fn test_min_items_requires_array_param() {
+        ToolBuilder::new("t")
+            .string_param("name", "Name", true)
+            .min_items(1);
+    }
+
+    #[test]
+    fn test_numeric_range() {
+        let tool = ToolBuilder::new("t")
+            .int_param("limit", "Limit", false)
+            .min(1.0)
+            .max(100.0)
+            .number_param("threshold", "Threshold", true)
+            .min(0.0)
+            .build();
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(props["limit"]["minimum"], 1.0);
+        assert_eq!(props["limit"]["maximum"], 100.0);
+        assert_eq!(props["threshold"]["type"], "number");
+        assert_eq!(props["threshold"]["minimum"], 0.0);
+        assert!(props["threshold"].get("maximum").is_none());
+    }
+
+    #[test]
+    fn test_simple_tool_has_no_params() {
+        let tool = simple_tool("list_graphs", "List graphs");
+        assert_eq!(tool.name, "list_graphs");
+        assert_eq!(tool.description.as_deref(), Some("List graphs"));
+        assert_eq!(tool.input_schema.r#type, "object");
+        assert!(tool.input_schema.properties.is_none());
+        assert!(tool.input_schema.required.is_none());
+    }
+
+    #[test]
+    fn test_single_string_param_tool() {
+        let tool = single_string_param_tool("get_page", "Get a page", "page_name", "Page name");
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(props.len(), 1);
+        assert_eq!(
+            props["page_name"],
+            json!({"type": "string", "description": "Page name"})
+        );
+        assert_eq!(
+            tool.input_schema.required,
+            Some(vec!["page_name".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_bool_param_default() {
+        let tool = ToolBuilder::new("t")
+            .bool_param("flag", "Flag", Some(false), false)
+            .bool_param("other", "Other", None, false)
+            .build();
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(
+            props["flag"],
+            json!({"type": "boolean", "description": "Flag", "default": false})
+        );
+        assert!(props["other"].get("default").is_none());
+    }
+
+    #[test]
+    fn test_two_string_params() {
+        let tool = ToolBuilder::new("t")
+            .string_param("a", "First", true)
+            .string_param("b", "Second", true)
+            .build();
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(props.len(), 2);
+        assert_eq!(
+            props["a"],
+            json!({"type": "string", "description": "First"})
+        );
+        assert_eq!(
+            props["b"],
+            json!({"type": "string", "description": "Second"})
+        );
+        assert_eq!(
+            tool.input_schema.required,
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_optional_params_not_required() {
+        let tool = ToolBuilder::new("t")
+            .string_param("name", "Name", true)
+            .string_param("note", "Note", false)
+            .int_param("limit", "Limit", false)
+            .build();
+        assert_eq!(tool.input_schema.properties.unwrap().len(), 3);
+        assert_eq!(tool.input_schema.required, Some(vec!["name".to_string()]));
+
+        let tool = ToolBuilder::new("t")
+            .string_param("note", "Note", false)
+            .build();
+        assert!(tool.input_schema.required.is_none());
+    }
+
+    #[test]
+    fn test_build_sets_object_type() {
+        for tool in [
+            ToolBuilder::new("empty").build(),
+            ToolBuilder::new("t").string_param("a", "A", true).build(),
+            ToolBuilder::new("t")
+                .bool_param("b", "B", None, false)
+                .build(),
+        ] {
+            assert_eq!(tool.input_schema.r#type, "object");
+        }
+        assert!(ToolBuilder::new("empty").build().description.is_none());
+    }
+
+    #[test]
+    fn test_int_param_schema() {
+        let tool = ToolBuilder::new("t")
+            .int_param("limit", "Max results", true)
+            .build();
+        let props = tool.input_schema.properties.unwrap();
+        assert_eq!(
+            props["limit"],
+            json!({"type": "integer", "description": "Max results"})
+        );
+        assert_eq!(tool.input_schema.required, Some(vec!["limit".to_string()]));
+    }
+}
@@ -18,14 +18,19 @@
 //!
 //! ## Usage
 //!
-//! Tools are registered in `get_all_tools()` and their implementations
-//! are in the respective `query` and `mutate` modules.
+//! Tools are declared once with `tool_handlers!` below, which pairs each
+//! definition with its implementation in the `query` or `mutate` module.
+//! `ToolRegistry` (see `registry.rs`) serves both `tools/list` and
+//! `tools/call` from those declarations.
 
 pub mod builder;
 pub mod mutate;
 pub mod query;
+pub mod registry;
 
+use crate::utils::{INCOMPLETE_TASK_MARKERS, TASK_MARKERS};
 use builder::{ToolBuilder, simple_tool, single_string_param_tool};
+use registry::tool_handlers;
 use serde_json::{Value, json};
 use std::collections::HashMap;
 
@@ -72,133 +77,690 @@ pub struct ToolInputSchema {
     pub required: Option<Vec<String>>,
 }
 
-/// Returns a complete list of all tools available through this MCP server.
-///
-/// This function registers and configures all tools that clients can invoke.
-/// Each tool is defined with its name, description, and input schema.
-/// The tools are organized into two categories: query tools (read-only)
-/// and mutation tools (write operations).
-///
-/// ## Tool Registration
-///
-/// Tools must be added here to be discoverable by MCP clients. Each tool
-/// definition includes:
-/// - A unique name used in tool calls
-/// - A description explaining what the tool does
-/// - A JSON Schema defining expected parameters
-///
-/// ## Schema Guidelines
-///
-/// - Use "object" type for tools with parameters
-/// - Define all parameters in the properties map
-/// - List required parameters in the required array
-/// - Include descriptions for each parameter
-pub fn get_all_tools() -> Vec<Tool> {
-    vec![
-        // ==========================================================================
-        // Query Tools - Read-only operations
-        // ==========================================================================
-        simple_tool("list_graphs", "List available Logseq graphs"),
+// Every tool available through this MCP server.
+//
+// Each entry declares a zero-sized handler type: its kind (`query` or
+// `mutation`, optionally `_with_progress`), the function that runs it, and
+// its definition (name, description and input schema). The `ToolRegistry`
+// built from these handlers is the single source for both `tools/list`
+// and `tools/call`, so adding a tool only takes a new entry here.
+//
+// ## Schema Guidelines
+//
+// - Use "object" type for tools with parameters
+// - Define all parameters in the properties map
+// - List required parameters in the required array
+// - Include descriptions for each parameter
+tool_handlers! {
+    // ==========================================================================
+    // Query Tools - Read-only operations
+    // ==========================================================================
+    query ListGraphs = query::list_graphs,
+        simple_tool("list_graphs", "List available Logseq graphs");
+    query ListPages = query::list_pages,
         ToolBuilder::new("list_pages")
-            .description("List pages in the current graph, with optional name filter and limit")
+            .description("List pages in the current graph, with optional filters and pagination")
             .string_param(
                 "name_contains",
                 "Filter pages whose name contains this substring (case-insensitive)",
                 false,
             )
             .string_param(
-                "limit",
-                "Maximum number of pages to return (default: 100)",
+                "namespace",
+                "Only pages under this namespace, e.g. \"Projects\" matches \"Projects/2025\"",
                 false,
             )
-            .build(),
-        single_string_param_tool(
-            "get_page",
-            "Get content of a specific page by name",
-            "page_name",
-            "Name of the page to retrieve",
-        ),
-        single_string_param_tool(
-            "get_block",
-            "Get a specific block by its UUID",
-            "uuid",
-            "UUID of the block to retrieve",
-        ),
-        single_string_param_tool(
-            "search",
-            "Full-text search across all pages and blocks in the graph",
-            "query",
-            "Search query string",
-        ),
+            .bool_param(
+                "include_journals",
+                "Include journal pages (default: true)",
+                Some(true),
+                false,
+            )
+            .int_param("limit", "Maximum number of pages to return (default: 100)", false)
+            .min(1.0)
+            .max(10000.0)
+            .int_param("offset", "Number of matching pages to skip (default: 0)", false)
+            .int_param(
+                "timeout_ms",
+                "Timeout for fetching the page list in milliseconds (overrides the server default)",
+                false,
+            )
+            .min(1.0)
+            .build();
+    query GetPage = query::get_page,
+        ToolBuilder::new("get_page")
+            .description("Get content of a specific page by name")
+            .string_param("page_name", "Name of the page to retrieve", true)
+            .bool_param(
+                "include_metadata",
+                "Include page metadata (default: true)",
+                Some(true),
+                false,
+            )
+            .bool_param(
+                "include_blocks",
+                "Include the page's block tree (default: true)",
+                Some(true),
+                false,
+            )
+            .bool_param(
+                "include_properties",
+                "Include page-level properties (default: false)",
+                Some(false),
+                false,
+            )
+            .build();
+    query GetBlock = query::get_block,
+        ToolBuilder::new("get_block")
+            .description("Get a specific block by its UUID, optionally with its children")
+            .string_param("uuid", "UUID of the block to retrieve", true)
+            .bool_param(
+                "include_children",
+                "Include nested child blocks (default: false)",
+                Some(false),
+                false,
+            )
+            .int_param(
+                "max_depth",
+                "Levels of children to include when include_children is true (default: 3)",
+                false,
+            )
+            .min(0.0)
+            .build();
+    query Search = query::search,
+        ToolBuilder::new("search")
+            .description("Full-text search across all pages and blocks in the graph")
+            .string_param("query", "Search query string", true)
+            .int_param(
+                "timeout_ms",
+                "Timeout for this search in milliseconds (overrides the server default)",
+                false,
+            )
+            .min(1.0)
+            .enum_param(
+                "type",
+                "Restrict results to blocks or pages (default: all)",
+                ["all", "block", "page"],
+                false,
+            )
+            .bool_param(
+                "page_name_only",
+                "Only return page name matches (default: false)",
+                Some(false),
+                false,
+            )
+            .int_param("limit", "Maximum number of results to return", false)
+            .build();
+    query Query = query::query,
         single_string_param_tool(
             "query",
             "Run a Datascript/Datalog query against the graph database for precise structured queries",
             "query",
             "Datalog query string, e.g. [:find ?n :where [?b :block/name ?n]]",
-        ),
+        );
+    query GetTodayJournal = query::get_today_journal,
         simple_tool(
             "get_today_journal",
             "Get the content of today's journal page",
-        ),
+        );
+    query GetPageReferences = query::get_page_references,
         single_string_param_tool(
             "get_page_references",
             "Get all blocks that link to a given page (backlinks)",
             "page_name",
             "Name of the page to find references for",
-        ),
+        );
+    query GetBlockProperties = query::get_block_properties,
         single_string_param_tool(
             "get_block_properties",
             "Get all properties (tags, type, priority, etc.) on a specific block",
             "uuid",
             "UUID of the block",
-        ),
-        // ==========================================================================
-        // Mutation Tools - Write operations that modify Logseq content
-        // ==========================================================================
+        );
+    query GetAllScheduledItemsForWeek = query::get_all_scheduled_items_for_week,
+        ToolBuilder::new("get_all_scheduled_items_for_week")
+            .description("Get all scheduled and deadline items for a Monday-Sunday week")
+            .int_param(
+                "week_offset",
+                "Week relative to the current one (0 = this week, 1 = next, -1 = last)",
+                false,
+            )
+            .min(-520.0)
+            .max(520.0)
+            .build();
+    query GetPageAsAnkiCards = query::get_page_as_anki_cards,
+        ToolBuilder::new("get_page_as_anki_cards")
+            .description("Export a page's flashcard blocks as Anki-importable cards")
+            .string_param("page_name", "Name of the page holding the deck", true)
+            .string_param(
+                "card_property",
+                "Property marking flashcard blocks (default: \"card\")",
+                false,
+            )
+            .build();
+    query GetPageContentHash = query::get_page_content_hash,
+        single_string_param_tool(
+            "get_page_content_hash",
+            "Get a SHA-256 hash of a page's content for cheap change detection",
+            "page_name",
+            "Name of the page to hash",
+        );
+    query GetMultiplePageHashes = query::get_multiple_page_hashes,
+        ToolBuilder::new("get_multiple_page_hashes")
+            .description("Get content hashes for several pages in one call")
+            .array_param("page_names", "Names of the pages to hash", "string", true)
+            .build();
+    query GetAllPagesWithTasks = query::get_all_pages_with_tasks,
+        ToolBuilder::new("get_all_pages_with_tasks")
+            .description(
+                "List pages containing tasks, ordered by task count, for a global task overview",
+            )
+            .array_param(
+                "states",
+                "Task states to include, e.g. [\"TODO\", \"DOING\"] (default: all)",
+                "string",
+                false,
+            )
+            .int_param(
+                "limit",
+                "Maximum number of pages to return (default: 20)",
+                false,
+            )
+            .build();
+    query GetBlockByContent = query::get_block_by_content,
+        ToolBuilder::new("get_block_by_content")
+            .description("Find blocks by exact or substring content match when the UUID is unknown")
+            .string_param("content", "Block content to look for", true)
+            .bool_param(
+                "exact",
+                "Match the whole content exactly (true, default) or as a substring (false)",
+                Some(true),
+                false,
+            )
+            .string_param(
+                "page_name",
+                "Restrict the lookup to this page (optional)",
+                false,
+            )
+            .build();
+    query SearchByProperty = query::search_by_property,
+        ToolBuilder::new("search_by_property")
+            .description("Find blocks and pages by property (e.g. status:: open), optionally filtered by value")
+            .string_param("property_name", "Property key, e.g. status or type", true)
+            .string_param(
+                "property_value",
+                "Only return blocks whose value matches, ignoring case (optional)",
+                false,
+            )
+            .bool_param(
+                "exact_match",
+                "Match the whole value (true, default) or as a substring (false)",
+                Some(true),
+                false,
+            )
+            .build();
+    query GetPageCreationContext = query::get_page_creation_context,
+        single_string_param_tool(
+            "get_page_creation_context",
+            "Find journal blocks from around a page's creation date that reference it",
+            "page_name",
+            "Name of the page to investigate",
+        );
+    query GetGraphMostActiveDays = query::get_graph_most_active_days,
+        ToolBuilder::new("get_graph_most_active_days")
+            .description("Get the days with the most page or block activity in the graph")
+            .int_param("limit", "Number of days to return (default: 10)", false)
+            .enum_param(
+                "metric",
+                "Activity to count (default: blocks_modified)",
+                ["pages_created", "blocks_created", "blocks_modified"],
+                false,
+            )
+            .build();
+    query GetPageIcon = query::get_page_icon,
+        single_string_param_tool(
+            "get_page_icon",
+            "Get the icon (emoji or icon code) of a page",
+            "page_name",
+            "Name of the page",
+        );
+    query GetPagesUpdatedSince = query::get_pages_updated_since,
+        ToolBuilder::new("get_pages_updated_since")
+            .description(
+                "List pages updated after a timestamp, with cursor pagination for incremental sync",
+            )
+            .int_param(
+                "timestamp",
+                "Unix time in milliseconds; only later updates are returned",
+                true,
+            )
+            .int_param(
+                "limit",
+                "Maximum number of pages to return (default: 50)",
+                false,
+            )
+            .string_param(
+                "cursor",
+                "The next_cursor value from a previous call",
+                false,
+            )
+            .build();
+    query GetPageComplexityScore = query::get_page_complexity_score,
+        single_string_param_tool(
+            "get_page_complexity_score",
+            "Score a page's structural complexity (nesting, size, links, tasks, rich content)",
+            "page_name",
+            "Name of the page to analyze",
+        );
+    query GetBlockStatistics = query::get_block_statistics,
+        single_string_param_tool(
+            "get_block_statistics",
+            "Get content metrics for a page's blocks: counts, lengths, nesting depth and tasks by marker",
+            "page_name",
+            "Name of the page to analyze",
+        );
+    query GetPagePropertyHistory = query::get_page_property_history,
+        ToolBuilder::new("get_page_property_history")
+            .description("Show how a page property changed over time using the graph's git history")
+            .string_param("page_name", "Name of the page", true)
+            .string_param("property_key", "Property whose history to extract", true)
+            .build();
+    query GetBlockContentStatistics = query::get_block_content_statistics,
+        single_string_param_tool(
+            "get_block_content_statistics",
+            "Compute writing-style statistics (sentence length, common words) for a page",
+            "page_name",
+            "Name of the page to analyze",
+        );
+    query GetAllBlockUuidsOnPage = query::get_all_block_uuids_on_page,
+        ToolBuilder::new("get_all_block_uuids_on_page")
+            .description("List the UUIDs of all blocks on a page in document order")
+            .string_param("page_name", "Name of the page", true)
+            .int_param(
+                "depth_limit",
+                "Maximum nesting depth to include (1 = top-level only, default: unlimited)",
+                false,
+            )
+            .build();
+    query GetTaskCompletionTrend = query::get_task_completion_trend,
+        ToolBuilder::new("get_task_completion_trend")
+            .description("Show tasks completed vs created per day or week over a recent window")
+            .int_param("days", "Number of days to look back (default: 30)", false)
+            .min(1.0)
+            .max(3650.0)
+            .enum_param(
+                "granularity",
+                "Bucket size (default: day)",
+                ["day", "week"],
+                false,
+            )
+            .build();
+    query GetPageAsCsv = query::get_page_as_csv,
+        ToolBuilder::new("get_page_as_csv")
+            .description("Export the markdown tables on a page as CSV")
+            .string_param("page_name", "Name of the page to export", true)
+            .bool_param(
+                "table_only",
+                "Only export markdown tables (true, default); false also exports block properties as a table",
+                Some(true),
+                false,
+            )
+            .build();
+    query GetPageKanbanView = query::get_page_kanban_view,
+        single_string_param_tool(
+            "get_page_kanban_view",
+            "View a page as a Kanban board with heading blocks as columns of tasks",
+            "page_name",
+            "Name of the page",
+        );
+    query GetBlockMarkdownRendered = query::get_block_markdown_rendered,
+        ToolBuilder::new("get_block_markdown_rendered")
+            .description("Render a block tree as markdown with ((references)) and embeds resolved")
+            .string_param("uuid", "UUID of the block to render", true)
+            .int_param(
+                "resolve_depth",
+                "How many levels of references to follow (default: 1, max: 3)",
+                false,
+            )
+            .build();
+    query GetPagesWithDeadlineSoon = query::get_pages_with_deadline_soon,
+        ToolBuilder::new("get_pages_with_deadline_soon")
+            .description("List pages with DEADLINE items due within the next few days")
+            .int_param("days_ahead", "Number of days ahead to look (default: 7)", false)
+            .min(0.0)
+            .max(3650.0)
+            .build();
+    query GetBlockChildrenRecursive = query::get_block_children_recursive,
+        ToolBuilder::new("get_block_children_recursive")
+            .description("Fetch the nested subtree of children below a block, up to 20 levels deep; truncated is true when deeper blocks were left out")
+            .string_param("uuid", "UUID of the root block", true)
+            .int_param(
+                "max_depth",
+                "Deepest level to fetch (1 = immediate children, default: 5, max: 20)",
+                false,
+            )
+            .min(1.0)
+            .max(20.0)
+            .bool_param(
+                "include_content",
+                "Include each block's content (default: true)",
+                Some(true),
+                false,
+            )
+            .build();
+    query GetGraphLanguageStats = query::get_graph_language_stats,
+        simple_tool(
+            "get_graph_language_stats",
+            "Estimate the share of content in each writing system (Latin, CJK, Cyrillic, ...)",
+        );
+    query GetAllCodeBlocks = query::get_all_code_blocks,
+        ToolBuilder::new("get_all_code_blocks")
+            .description("Collect fenced code snippets across the graph, optionally by language or page")
+            .string_param("language", "Only return snippets in this language, e.g. \"rust\"", false)
+            .string_param("page_name", "Restrict the search to this page (optional)", false)
+            .build();
+    query GetPageVersionDiff = query::get_page_version_diff,
+        ToolBuilder::new("get_page_version_diff")
+            .description("Diff a page's file at a past git commit against the current committed version")
+            .string_param("page_name", "Name of the page", true)
+            .string_param("commit_hash", "Git commit to compare against", true)
+            .build();
+    query GetGraphLinkDensity = query::get_graph_link_density,
+        simple_tool(
+            "get_graph_link_density",
+            "Measure how well-connected the graph is: average, median and distribution of links per page",
+        );
+    query DatalogQuery = query::datalog_query,
+        ToolBuilder::new("datalog_query")
+            .description("Run a parameterised Datalog query; bindings fill the query's :in clause")
+            .string_param(
+                "query",
+                "Datalog query, e.g. [:find ?c :in $ ?name :where [?p :block/name ?name] [?b :block/page ?p] [?b :block/content ?c]]",
+                true,
+            )
+            .array_param(
+                "bindings",
+                "Inputs for the :in clause after $, as EDN strings (e.g. \"\\\"my page\\\"\")",
+                "string",
+                false,
+            )
+            .build();
+    query GetLinkedReferences = query::get_linked_references,
+        single_string_param_tool(
+            "get_linked_references",
+            "Get the blocks linking to a page, returned with the page name; empty if there are none",
+            "page_name",
+            "Name of the page to find linked references for",
+        );
+    query GetPageProperties = query::get_page_properties,
+        single_string_param_tool(
+            "get_page_properties",
+            "Get a page's page-level properties (tags, alias, custom fields), with tags as an array",
+            "page_name",
+            "Name of the page to read properties from",
+        );
+    query GetJournalPages = query::get_journal_pages,
+        ToolBuilder::new("get_journal_pages")
+            .description("List journal (daily note) pages, most recent first")
+            .int_param("limit", "Maximum number of journals to return", false)
+            .string_param(
+                "since",
+                "Only include journals on or after this date (YYYY-MM-DD)",
+                false,
+            )
+            .build();
+    query GetBlockParent = query::get_block_parent,
+        single_string_param_tool(
+            "get_block_parent",
+            "Get a block together with its parent block (null if the block is top-level on its page)",
+            "uuid",
+            "UUID of the block whose parent to fetch",
+        );
+    query GetBlockPath = query::get_block_path,
+        single_string_param_tool(
+            "get_block_path",
+            "Get a block with its breadcrumb of ancestor blocks from the top of its page",
+            "uuid",
+            "UUID of the block to locate",
+        );
+    query GetAllTags = query::get_all_tags,
+        simple_tool(
+            "get_all_tags",
+            "List all tags used in the graph (page tags and inline #tags) with how many pages use each",
+        );
+    query_with_progress FindOrphanedPages = query::find_orphaned_pages,
+        ToolBuilder::new("find_orphaned_pages")
+            .description("Find pages that no block links to (no linked references)")
+            .int_param("limit", "Maximum number of orphaned pages to return (default: 50)", false)
+            .bool_param(
+                "include_journals",
+                "Also consider journal pages (default: false)",
+                Some(false),
+                false,
+            )
+            .build();
+    query GetPageContentAsMarkdown = query::get_page_content_as_markdown,
+        single_string_param_tool(
+            "get_page_content_as_markdown",
+            "Get a page's content as a markdown outline (nested bullets) instead of raw JSON",
+            "page_name",
+            "Name of the page to render",
+        );
+    query GetPageWordCount = query::get_page_word_count,
+        single_string_param_tool(
+            "get_page_word_count",
+            "Count the words, blocks and characters on a page",
+            "page_name",
+            "Name of the page to analyze",
+        );
+    query GetGraphWordCount = query::get_graph_word_count,
+        simple_tool(
+            "get_graph_word_count",
+            "Count words across all pages, with a per-page breakdown sorted by word count",
+        );
+    query GetRecentlyModifiedPages = query::get_recently_modified_pages,
+        ToolBuilder::new("get_recently_modified_pages")
+            .description("List the most recently modified pages within a time window")
+            .int_param("since_hours", "How many hours back to look (default: 24)", false)
+            .min(1.0)
+            .max(87600.0)
+            .int_param("limit", "Maximum number of pages to return (default: 10)", false)
+            .min(1.0)
+            .max(10000.0)
+            .build();
+    query FindIncompleteTasks = query::find_incomplete_tasks,
+        ToolBuilder::new("find_incomplete_tasks")
+            .description("Find incomplete tasks across the graph by task marker")
+            .enum_param(
+                "marker",
+                "Task marker to look for (default: TODO)",
+                INCOMPLETE_TASK_MARKERS.iter().copied(),
+                false,
+            )
+            .build();
+    query GetNamespacePages = query::get_namespace_pages,
+        ToolBuilder::new("get_namespace_pages")
+            .description("List the pages under a namespace (e.g. Projects/2025/Q1 under Projects)")
+            .string_param("namespace", "Namespace prefix, e.g. \"Projects\" (case-insensitive)", true)
+            .bool_param(
+                "recursive",
+                "Include nested sub-namespaces (default: true)",
+                Some(true),
+                false,
+            )
+            .build();
+    query GetPageBacklinks = query::get_page_backlinks,
+        ToolBuilder::new("get_page_backlinks")
+            .description("Get the blocks that link to a page, with their source page and a short excerpt")
+            .string_param("page_name", "Name of the page to find backlinks for", true)
+            .int_param("limit", "Maximum number of backlinks to return", false)
+            .min(1.0)
+            .bool_param(
+                "include_journal",
+                "Include backlinks from journal pages (default: true)",
+                Some(true),
+                false,
+            )
+            .build();
+    query GetGraphConfig = query::get_graph_config,
+        simple_tool(
+            "get_graph_config",
+            "Get the graph's Logseq configuration (preferred format, journal settings, ...)",
+        );
+    query GetLogseqVersion = query::get_logseq_version,
+        simple_tool(
+            "get_logseq_version",
+            "Get the running Logseq app version and this server's version (useful for bug reports)",
+        );
+    query GetBlockRefs = query::get_block_refs,
+        single_string_param_tool(
+            "get_block_refs",
+            "Find the blocks that reference a block via ((uuid)), e.g. before deleting it",
+            "uuid",
+            "UUID of the referenced block",
+        );
+    query GetPageLinkCount = query::get_page_link_count,
+        ToolBuilder::new("get_page_link_count")
+            .description("Count a page's outgoing [[links]] and incoming references")
+            .string_param("page_name", "Name of the page to analyze", true)
+            .enum_param(
+                "direction",
+                "Which links to count (default: both)",
+                ["outgoing", "incoming", "both"],
+                false,
+            )
+            .build();
+    query GetTemplates = query::get_templates,
+        simple_tool(
+            "get_templates",
+            "List the graph's templates (pages with a template:: property) for execute_template",
+        );
+    query GetGraphStatistics = query::get_graph_statistics,
+        simple_tool(
+            "get_graph_statistics",
+            "Get an overview of the graph: page, journal and namespace counts and recent activity",
+        );
+    query ValidateUuid = query::validate_uuid,
+        single_string_param_tool(
+            "validate_uuid",
+            "Check that a block UUID is well-formed and exists before using it",
+            "uuid",
+            "The UUID to check",
+        );
+    query GetPageAsOrg = query::get_page_as_org,
+        ToolBuilder::new("get_page_as_org")
+            .description("Export a page's content in Org-mode format")
+            .string_param("page_name", "Name of the page to export", true)
+            .bool_param(
+                "include_properties",
+                "Render block properties as :PROPERTIES: drawers (default: true)",
+                Some(true),
+                false,
+            )
+            .build();
+    query GenerateTableOfContents = query::generate_table_of_contents,
+        ToolBuilder::new("generate_table_of_contents")
+            .description("Build a table of contents from a page's markdown headings and its top two outline levels")
+            .string_param("page_name", "Name of the page to index", true)
+            .int_param("max_depth", "Deepest heading level to include (default: 3)", false)
+            .min(1.0)
+            .max(6.0)
+            .build();
+    query FindBrokenLinks = query::find_broken_links,
+        ToolBuilder::new("find_broken_links")
+            .description("Find [[links]] to pages that don't exist")
+            .int_param("limit", "Maximum number of broken links to return (default: 100)", false)
+            .min(1.0)
+            .build();
+    query GetFavorites = query::get_favorites,
+        ToolBuilder::new("get_favorites")
+            .description("List the graph's favorite pages in the user's order")
+            .bool_param(
+                "include_page_details",
+                "Include each favorite's full page metadata (default: false)",
+                Some(false),
+                false,
+            )
+            .build();
+    // ==========================================================================
+    // Mutation Tools - Write operations that modify Logseq content
+    // ==========================================================================
+    mutation CreatePage = mutate::create_page,
         ToolBuilder::new("create_page")
             .description("Create a new page with optional initial content")
             .string_param("page_name", "Name of the page to create", true)
             .string_param("content", "Initial content for the page (optional)", false)
-            .build(),
+            .build();
+    mutation UpdateBlock = mutate::update_block,
         ToolBuilder::new("update_block")
             .description("Replace the content of an existing block")
             .string_param("uuid", "UUID of the block to update", true)
             .string_param("content", "New content for the block", true)
-            .build(),
+            .build();
+    mutation UpdateBlockTracked = mutate::update_block_with_diff,
+        ToolBuilder::new("update_block_tracked")
+            .description("Replace the content of an existing block and return a diff of what changed")
+            .string_param("uuid", "UUID of the block to update", true)
+            .string_param("content", "New content for the block", true)
+            .build();
+    mutation InsertBlock = mutate::insert_block,
         ToolBuilder::new("insert_block")
             .description("Insert a new block as a child or sibling of an existing block")
             .string_param("parent_uuid", "UUID of the parent block or page", true)
             .string_param("content", "Content for the new block", true)
             .bool_param(
                 "sibling",
-                "Insert as sibling (true) or child (false, default)",
+                "Deprecated: use insert_block_after instead. Insert after parent_uuid at the same level (true) or as its child (false, default)",
                 Some(false),
                 false,
             )
-            .build(),
+            .build();
+    mutation InsertBlockBefore = mutate::insert_block_before,
+        ToolBuilder::new("insert_block_before")
+            .description("Insert a new block directly before an existing block, at the same level")
+            .string_param("reference_uuid", "UUID of the block to insert before", true)
+            .string_param("content", "Content for the new block", true)
+            .build();
+    mutation InsertBlockAfter = mutate::insert_block_after,
+        ToolBuilder::new("insert_block_after")
+            .description("Insert a new block directly after an existing block, at the same level")
+            .string_param("reference_uuid", "UUID of the block to insert after", true)
+            .string_param("content", "Content for the new block", true)
+            .build();
+    mutation DeleteBlock = mutate::delete_block,
         single_string_param_tool(
             "delete_block",
-            "Permanently delete a block and all its children by UUID",
+            "WARNING: irreversible. Permanently delete a block and all its children by UUID",
             "uuid",
             "UUID of the block to delete",
-        ),
+        );
+    mutation DeletePage = mutate::delete_page,
         single_string_param_tool(
             "delete_page",
-            "Permanently delete a page and all its blocks by name",
+            "WARNING: irreversible. Permanently delete a page and all its blocks by name",
             "page_name",
             "Name of the page to delete",
-        ),
+        );
+    mutation AppendToPage = mutate::append_to_page,
         ToolBuilder::new("append_to_page")
             .description("Append a new block to the end of a page")
             .string_param("page_name", "Name of the page to append to", true)
             .string_param("content", "Content to append", true)
-            .build(),
+            .build();
+    mutation PrependToPage = mutate::prepend_to_page,
+        ToolBuilder::new("prepend_to_page")
+            .description("Insert a new block at the top of a page, after any page properties")
+            .string_param("page_name", "Name of the page to prepend to", true)
+            .string_param("content", "Content to prepend", true)
+            .build();
+    mutation AppendToJournal = mutate::append_to_journal,
         single_string_param_tool(
             "append_to_journal",
             "Append a block to today's journal page",
             "content",
             "Content to append to today's journal",
-        ),
+        );
+    mutation SetBlockProperty = mutate::set_block_property,
         ToolBuilder::new("set_block_property")
             .description("Set a property (key-value pair) on a block, e.g. type, priority, tags")
             .string_param("uuid", "UUID of the block", true)
@@ -208,11 +770,235 @@ pub fn get_all_tools() -> Vec<Tool> {
                 true,
             )
             .string_param("value", "Property value", true)
-            .build(),
+            .build();
+    mutation RemoveBlockProperty = mutate::remove_block_property,
         ToolBuilder::new("remove_block_property")
             .description("Remove a property from a block")
             .string_param("uuid", "UUID of the block", true)
             .string_param("key", "Property name to remove", true)
-            .build(),
-    ]
+            .build();
+    mutation SetPageIcon = mutate::set_page_icon,
+        ToolBuilder::new("set_page_icon")
+            .description("Set the icon of a page to a single emoji or icon code")
+            .string_param("page_name", "Name of the page", true)
+            .string_param(
+                "icon",
+                "A single emoji (e.g. \"📚\") or icon code (e.g. \"ti-book\")",
+                true,
+            )
+            .build();
+    mutation MoveBlock = mutate::move_block,
+        ToolBuilder::new("move_block")
+            .description("Move a block (with its children) under a new parent or after a new sibling, keeping its UUID")
+            .string_param("uuid", "UUID of the block to move", true)
+            .string_param("target_uuid", "UUID of the block to move relative to", true)
+            .bool_param(
+                "sibling",
+                "Place after the target as a sibling (true) or as its child (false, default)",
+                Some(false),
+                false,
+            )
+            .build();
+    mutation SortPageBlocks = mutate::sort_page_blocks,
+        ToolBuilder::new("sort_page_blocks")
+            .description("Reorder a page's top-level blocks by an explicit UUID list or a sort key; give exactly one of order or sort_by")
+            .string_param("page_name", "Name of the page to reorder", true)
+            .array_param(
+                "order",
+                "Top-level block UUIDs in the wanted order; unlisted blocks follow in their current order",
+                "string",
+                false,
+            )
+            .enum_param(
+                "sort_by",
+                "Sort key to compute the order from",
+                ["content_asc", "content_desc", "created_asc", "created_desc"],
+                false,
+            )
+            .build();
+    mutation RenamePage = mutate::rename_page,
+        ToolBuilder::new("rename_page")
+            .description("Rename a page; links to it are updated to the new name")
+            .string_param("old_page_name", "Current name of the page", true)
+            .string_param("new_page_name", "New name for the page", true)
+            .build();
+    mutation SetPageProperty = mutate::set_page_property,
+        ToolBuilder::new("set_page_property")
+            .description("Set a page-level property (key:: value) on a page, replacing any existing value")
+            .string_param("page_name", "Name of the page to update", true)
+            .string_param("property_key", "Property name (no ':' or line breaks)", true)
+            .string_param("property_value", "Property value", true)
+            .build();
+    mutation CreateJournalEntry = mutate::create_journal_entry,
+        ToolBuilder::new("create_journal_entry")
+            .description("Create the journal page for a date, named using the graph's date format")
+            .string_param("date", "Journal date in ISO 8601 form (YYYY-MM-DD)", true)
+            .string_param("content", "Optional text for a first block on the page", false)
+            .build();
+    mutation_with_progress BatchInsertBlocks = mutate::batch_insert_blocks,
+        ToolBuilder::new("batch_insert_blocks")
+            .description("Insert several blocks relative to one block in a single call; reports per-block failures")
+            .string_param("parent_uuid", "UUID of the block to insert relative to", true)
+            .array_param(
+                "blocks",
+                "Blocks to insert in order, each {\"content\": string, \"sibling\": bool (optional)}",
+                "object",
+                true,
+            )
+            .min_items(1)
+            .build();
+    mutation_with_progress AppendMultipleBlocksToPage = mutate::append_multiple_blocks_to_page,
+        ToolBuilder::new("append_multiple_blocks_to_page")
+            .description("Append several blocks to the end of a page in one call; reports per-block failures")
+            .string_param("page_name", "Name of the page to append to", true)
+            .array_param(
+                "contents",
+                "Block contents to append, in order (must not be empty)",
+                "string",
+                true,
+            )
+            .min_items(1)
+            .build();
+    mutation CopyBlock = mutate::copy_block,
+        ToolBuilder::new("copy_block")
+            .description("Copy a block to a new location, optionally including all of its children")
+            .string_param("source_uuid", "UUID of the block to copy", true)
+            .string_param("target_parent_uuid", "UUID of the block to place the copy under (or after)", true)
+            .bool_param(
+                "sibling",
+                "Place the copy after the target (true) or as its child (false, default)",
+                Some(false),
+                false,
+            )
+            .bool_param(
+                "recursive",
+                "Also copy all child blocks, preserving the subtree (default: false)",
+                Some(false),
+                false,
+            )
+            .build();
+    mutation ReplaceInPage = mutate::replace_in_page,
+        ToolBuilder::new("replace_in_page")
+            .description("Find and replace text across all blocks of a page; changes nothing if there are more matches than max_replacements")
+            .string_param("page_name", "Name of the page to edit", true)
+            .string_param("find", "Text to find, or a regex pattern when use_regex is true", true)
+            .string_param("replace", "Replacement text; with use_regex, $1 etc. insert capture groups", true)
+            .bool_param(
+                "use_regex",
+                "Treat find as a regular expression (default: false)",
+                Some(false),
+                false,
+            )
+            .int_param(
+                "max_replacements",
+                "Maximum number of matches to replace (default: 50)",
+                false,
+            )
+            .min(1.0)
+            .build();
+    mutation ToggleTaskMarker = mutate::toggle_task_marker,
+        ToolBuilder::new("toggle_task_marker")
+            .description("Set a block's task marker (e.g. TODO -> DONE) without rewriting its content")
+            .string_param("uuid", "UUID of the task block", true)
+            .enum_param(
+                "new_marker",
+                "Marker to set",
+                TASK_MARKERS,
+                true,
+            )
+            .bool_param(
+                "add_if_missing",
+                "Prepend the marker if the block has none (default: true)",
+                Some(true),
+                false,
+            )
+            .build();
+    mutation ExecuteTemplate = mutate::execute_template,
+        ToolBuilder::new("execute_template")
+            .description("Insert a Logseq template after a block, or at the end of a page")
+            .string_param("template_name", "Name of the template to insert", true)
+            .string_param("target_uuid", "UUID of the block to insert the template after", false)
+            .string_param(
+                "page_name",
+                "Insert after this page's last block instead of target_uuid",
+                false,
+            )
+            .build();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use registry::{ToolHandler, ToolRegistry};
+
+    fn config_allowing(tools: Option<&[&str]>) -> Config {
+        Config {
+            allowed_tools: tools.map(|t| t.iter().map(|name| name.to_string()).collect()),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_registry_has_every_handler() {
+        let registry = ToolRegistry::default();
+        assert_eq!(registry.definitions().len(), all_handlers().len());
+        assert!(registry.contains("search"));
+        assert!(registry.is_mutation("delete_page"));
+        assert!(!registry.is_mutation("get_page"));
+    }
+
+    #[test]
+    fn test_read_only_hides_mutation_tools() {
+        let registry = ToolRegistry::default();
+        let all = registry.available_tools(&Config::default());
+        let read_only = registry.available_tools(&Config {
+            read_only: true,
+            ..Config::default()
+        });
+        let mutations = all.iter().filter(|t| registry.is_mutation(&t.name)).count();
+        assert!(mutations > 0);
+        assert_eq!(read_only.len(), all.len() - mutations);
+        assert!(read_only.iter().all(|t| !registry.is_mutation(&t.name)));
+        assert!(read_only.iter().any(|t| t.name == "search"));
+    }
+
+    #[test]
+    fn test_allowed_tools_unset_allows_everything() {
+        let registry = ToolRegistry::default();
+        let config = config_allowing(None);
+        assert_eq!(
+            registry.available_tools(&config).len(),
+            registry.definitions().len()
+        );
+        assert!(registry.is_enabled(&config, "delete_page"));
+    }
+
+    #[test]
+    fn test_allowed_tools_empty_disables_everything() {
+        let registry = ToolRegistry::default();
+        let config = config_allowing(Some(&[]));
+        assert!(registry.available_tools(&config).is_empty());
+        assert!(!registry.is_enabled(&config, "search"));
+    }
+
+    #[test]
+    fn test_allowed_tools_single_tool() {
+        let registry = ToolRegistry::default();
+        let config = config_allowing(Some(&["search"]));
+        let tools = registry.available_tools(&config);
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "search");
+        assert!(!registry.is_enabled(&config, "get_page"));
+    }
+
+    #[test]
+    fn test_tool_json_exposes_enum_values() {
+        let tool = ToggleTaskMarker.definition();
+        let schema = &tool.to_json()["inputSchema"];
+        assert_eq!(
+            schema["properties"]["new_marker"]["enum"],
+            json!(TASK_MARKERS)
+        );
+    }
 }
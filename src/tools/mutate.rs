@@ -17,9 +17,20 @@
 //! Logseq API, and returns success confirmation with relevant data. All functions
 //! follow consistent error handling and response formatting patterns.
 
-use crate::logseq_client::LogseqClient;
-use anyhow::Result;
+use super::query::block_page_name;
+use crate::{
+    error::{McpError, McpResult},
+    logseq_client::LogseqClient,
+    progress::ProgressReporter,
+    utils::{TASK_MARKERS, flatten_blocks, format_journal_date},
+    validation::require_block_uuid,
+};
+use chrono::{Local, NaiveDate};
+use regex::Regex;
 use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Creates a new page in the graph with optional initial content.
 ///
@@ -44,10 +55,10 @@ use serde_json::Value;
 /// - The page_name parameter is missing
 /// - A page with that name already exists (behavior depends on Logseq settings)
 /// - The API request fails due to network or permission issues
-pub async fn create_page(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn create_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let page_name = params["page_name"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("page_name parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
 
     let content = params["content"].as_str();
 
@@ -86,16 +97,18 @@ pub async fn create_page(client: &LogseqClient, params: Value) -> Result<Value>
 ///
 /// Returns an error if:
 /// - Either uuid or content parameters are missing
+/// - The uuid isn't a well-formed UUID (checked before calling Logseq)
 /// - The specified block UUID doesn't exist
 /// - The API request fails due to network or permission issues
-pub async fn update_block(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn update_block(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let uuid = params["uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
 
     let content = params["content"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("content parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
 
     let result = client.update_block(uuid, content).await?;
     Ok(serde_json::json!({
@@ -104,6 +117,87 @@ pub async fn update_block(client: &LogseqClient, params: Value) -> Result<Value>
     }))
 }
 
+/// Updates a block's content and reports what changed.
+///
+/// Works like [`update_block`], but reads the block's current content first
+/// so the response can describe the edit. Useful for callers that want to
+/// verify or log their changes.
+///
+/// # Parameters
+///
+/// - `uuid` (required): UUID of the block to update
+/// - `content` (required): New content for the block
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `block`: The block as it is after the update
+/// - `diff`: `{added, removed, unchanged, patch}`, where the counts are
+///   characters from a character-level diff and `patch` is a line-level
+///   unified diff (empty when nothing changed)
+///
+/// # Errors
+///
+/// Returns an error if a parameter is missing, the block doesn't exist, or
+/// the update fails.
+pub async fn update_block_with_diff(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
+
+    let content = params["content"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
+
+    let before = client.get_block(uuid).await?;
+    if before.is_null() {
+        return Err(McpError::Internal(format!("Block not found: {}", uuid)));
+    }
+    let page_name = block_page_name(client, &before, uuid).await?;
+    let diff = content_diff(
+        before["content"].as_str().unwrap_or(""),
+        content,
+        &format!("{}/{}", page_name, uuid),
+    );
+
+    client.update_block(uuid, content).await?;
+    let block = client.get_block(uuid).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "block": block,
+        "diff": diff
+    }))
+}
+
+/// Summarizes the change from `old` to `new` as `{added, removed, unchanged, patch}`.
+///
+/// The counts come from a character-level diff. The patch is line-level,
+/// since a unified diff with one character per line is unreadable; its
+/// header names `label` as `before/<label>` and `after/<label>`.
+fn content_diff(old: &str, new: &str, label: &str) -> Value {
+    let (mut added, mut removed, mut unchanged) = (0, 0, 0);
+    for change in TextDiff::from_chars(old, new).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => unchanged += 1,
+        }
+    }
+    let patch = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("before/{}", label), &format!("after/{}", label))
+        .to_string();
+
+    serde_json::json!({
+        "added": added,
+        "removed": removed,
+        "unchanged": unchanged,
+        "patch": patch
+    })
+}
+
 /// Inserts a new block with precise positioning control.
 ///
 /// Creates a new block and positions it either as a child of the specified
@@ -114,10 +208,13 @@ pub async fn update_block(client: &LogseqClient, params: Value) -> Result<Value>
 ///
 /// - `parent_uuid` (required): UUID of the parent block or page
 /// - `content` (required): Text content for the new block
-/// - `sibling` (optional): Positioning mode, defaults to false
+/// - `sibling` (optional, deprecated): Positioning mode, defaults to false
 ///   - `true`: Insert as a sibling (same level as parent)
 ///   - `false`: Insert as a child (nested under parent)
 ///
+///   Prefer [`insert_block_after`] / [`insert_block_before`] for sibling
+///   insertion; `sibling` is kept for existing callers.
+///
 /// # Returns
 ///
 /// JSON object containing:
@@ -134,16 +231,18 @@ pub async fn update_block(client: &LogseqClient, params: Value) -> Result<Value>
 ///
 /// Returns an error if:
 /// - parent_uuid or content parameters are missing
+/// - parent_uuid isn't a well-formed UUID (checked before calling Logseq)
 /// - The specified parent UUID doesn't exist
 /// - The API request fails due to network or permission issues
-pub async fn insert_block(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn insert_block(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let parent_uuid = params["parent_uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("parent_uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("parent_uuid parameter is required".to_string()))?;
+    require_block_uuid("parent_uuid", parent_uuid)?;
 
     let content = params["content"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("content parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
 
     // Default to child insertion if sibling parameter not specified
     let sibling = params["sibling"].as_bool().unwrap_or(false);
@@ -155,6 +254,79 @@ pub async fn insert_block(client: &LogseqClient, params: Value) -> Result<Value>
     }))
 }
 
+/// Inserts a new block directly before an existing block, at the same level.
+///
+/// # Parameters
+///
+/// - `reference_uuid` (required): UUID of the block to insert before
+/// - `content` (required): Text content for the new block
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `block`: The newly created block with UUID and metadata
+///
+/// # Errors
+///
+/// Returns an error if a parameter is missing, `reference_uuid` isn't a
+/// well-formed UUID, or no block has that UUID.
+pub async fn insert_block_before(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    insert_relative(client, params, true).await
+}
+
+/// Inserts a new block directly after an existing block, at the same level.
+///
+/// # Parameters
+///
+/// - `reference_uuid` (required): UUID of the block to insert after
+/// - `content` (required): Text content for the new block
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `block`: The newly created block with UUID and metadata
+///
+/// # Errors
+///
+/// Returns an error if a parameter is missing, `reference_uuid` isn't a
+/// well-formed UUID, or no block has that UUID.
+pub async fn insert_block_after(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    insert_relative(client, params, false).await
+}
+
+async fn insert_relative(client: &LogseqClient, params: Value, before: bool) -> McpResult<Value> {
+    let reference_uuid = params["reference_uuid"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("reference_uuid parameter is required".to_string())
+    })?;
+    require_block_uuid("reference_uuid", reference_uuid)?;
+
+    let content = params["content"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
+
+    // Check first so a stale UUID gets a clear error rather than a null block
+    if client.get_block(reference_uuid).await?.is_null() {
+        return Err(McpError::Internal(format!(
+            "Block not found: {}",
+            reference_uuid
+        )));
+    }
+
+    let result = client
+        .insert_block_with_opts(
+            reference_uuid,
+            content,
+            serde_json::json!({ "sibling": true, "before": before }),
+        )
+        .await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "block": result
+    }))
+}
+
 /// Permanently deletes a block from the graph.
 ///
 /// Removes the specified block and all its child blocks from the graph.
@@ -185,13 +357,15 @@ pub async fn insert_block(client: &LogseqClient, params: Value) -> Result<Value>
 ///
 /// Returns an error if:
 /// - The uuid parameter is missing
+/// - The uuid isn't a well-formed UUID (checked before calling Logseq)
 /// - The specified block doesn't exist
 /// - The block cannot be deleted (e.g., due to permissions)
 /// - The API request fails due to network issues
-pub async fn delete_block(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn delete_block(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let uuid = params["uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
 
     let result = client.delete_block(uuid).await?;
     Ok(serde_json::json!({
@@ -230,13 +404,18 @@ pub async fn delete_block(client: &LogseqClient, params: Value) -> Result<Value>
 ///
 /// Returns an error if:
 /// - The page_name parameter is missing
-/// - The specified page doesn't exist
+/// - The specified page doesn't exist (checked up front, since
+///   `deletePage` returns null for unknown pages rather than failing)
 /// - The page cannot be deleted (e.g., due to permissions)
 /// - The API request fails due to network issues
-pub async fn delete_page(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn delete_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let page_name = params["page_name"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("page_name parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    if client.get_page(page_name).await?.is_null() {
+        return Err(McpError::Internal(format!("Page not found: {}", page_name)));
+    }
 
     let result = client.delete_page(page_name).await?;
     Ok(serde_json::json!({
@@ -277,14 +456,14 @@ pub async fn delete_page(client: &LogseqClient, params: Value) -> Result<Value>
 /// - page_name or content parameters are missing
 /// - The specified page doesn't exist
 /// - The API request fails due to network or permission issues
-pub async fn append_to_page(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn append_to_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let page_name = params["page_name"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("page_name parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
 
     let content = params["content"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("content parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
 
     let result = client.append_block_in_page(page_name, content).await?;
     Ok(serde_json::json!({
@@ -293,6 +472,64 @@ pub async fn append_to_page(client: &LogseqClient, params: Value) -> Result<Valu
     }))
 }
 
+/// Inserts a new block at the top of a page.
+///
+/// The counterpart to [`append_to_page`]. The block goes before the page's
+/// first top-level block; if the page starts with a page-properties block,
+/// it goes directly after that instead so the properties stay first.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The name of the page to prepend to
+/// - `content` (required): The text content for the new block
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `block`: The newly created block with UUID and metadata
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - page_name or content parameters are missing
+/// - The specified page doesn't exist (checked up front, so no block is
+///   inserted into a page Logseq would otherwise create)
+/// - The API request fails due to network or permission issues
+pub async fn prepend_to_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let content = params["content"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
+
+    if client.get_page(page_name).await?.is_null() {
+        return Err(McpError::Internal(format!("Page not found: {}", page_name)));
+    }
+
+    let tree = client.get_page_blocks_tree(page_name).await?;
+    let first = tree.as_array().and_then(|blocks| blocks.first());
+    let result = match first.and_then(|block| block["uuid"].as_str()) {
+        Some(uuid) => {
+            let is_properties = first.is_some_and(|b| b["preBlock?"].as_bool() == Some(true));
+            client
+                .insert_block_with_opts(
+                    uuid,
+                    content,
+                    serde_json::json!({ "sibling": true, "before": !is_properties }),
+                )
+                .await?
+        }
+        None => client.append_block_in_page(page_name, content).await?,
+    };
+    Ok(serde_json::json!({
+        "success": true,
+        "block": result
+    }))
+}
+
 /// Appends a block to today's journal page.
 ///
 /// A convenience tool for the most common Logseq journaling workflow — adding
@@ -308,19 +545,17 @@ pub async fn append_to_page(client: &LogseqClient, params: Value) -> Result<Valu
 pub async fn append_to_journal(
     client: &crate::logseq_client::LogseqClient,
     params: Value,
-) -> Result<Value> {
-    use crate::tools::query::format_journal_date_pub;
-
+) -> McpResult<Value> {
     let content = params["content"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("content parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
 
     // Get the graph's date format and determine today's journal page name
     let formatter = client.get_date_formatter().await?;
     let fmt_str = formatter.as_str().unwrap_or("MMM do, yyyy");
 
     // Format the date and lowercase it (Logseq stores journal pages in lowercase)
-    let page_name = format_journal_date_pub(fmt_str).to_lowercase();
+    let page_name = format_journal_date(Local::now().date_naive(), fmt_str).to_lowercase();
 
     let result = client.append_block_in_page(&page_name, content).await?;
     Ok(serde_json::json!({
@@ -347,18 +582,19 @@ pub async fn append_to_journal(
 pub async fn set_block_property(
     client: &crate::logseq_client::LogseqClient,
     params: Value,
-) -> Result<Value> {
+) -> McpResult<Value> {
     let uuid = params["uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
 
     let key = params["key"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("key parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("key parameter is required".to_string()))?;
 
     let value = params["value"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("value parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("value parameter is required".to_string()))?;
 
     let result = client.upsert_block_property(uuid, key, value).await?;
     Ok(serde_json::json!({ "success": true, "result": result }))
@@ -380,68 +616,2010 @@ pub async fn set_block_property(
 pub async fn remove_block_property(
     client: &crate::logseq_client::LogseqClient,
     params: Value,
-) -> Result<Value> {
+) -> McpResult<Value> {
     let uuid = params["uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
 
     let key = params["key"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("key parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("key parameter is required".to_string()))?;
 
     let result = client.remove_block_property(uuid, key).await?;
     Ok(serde_json::json!({ "success": true, "result": result }))
 }
 
-#[cfg(test)]
-mod tests {
-    use serde_json::json;
+/// Sets the icon shown next to a page.
+///
+/// Logseq stores page icons in the page's `icon::` property. The icon must be
+/// a single emoji (including ZWJ and skin-tone sequences) or an icon code
+/// such as `ti-book`.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to decorate
+/// - `icon` (required): An emoji or icon identifier
+///
+/// # Returns
+///
+/// JSON object with `success`, `page_name` and the `icon` that was set.
+///
+/// # Errors
+///
+/// Returns an error if the icon is empty, contains whitespace, or mixes
+/// emoji with other text.
+pub async fn set_page_icon(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
 
-    /// Helper to call parameter-validation logic without a real Logseq client.
-    /// We test only the validation path (before the async client call).
-    fn missing_param_error(params: serde_json::Value, key: &str) -> bool {
-        params[key].as_str().is_none()
+    let icon = params["icon"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("icon parameter is required".to_string()))?;
+
+    if !is_valid_icon(icon) {
+        return Err(McpError::InvalidParams(format!(
+            "Invalid icon '{}': expected a single emoji or an icon code like 'ti-book'",
+            icon
+        )));
     }
 
-    #[test]
-    fn test_create_page_requires_page_name() {
-        assert!(missing_param_error(json!({}), "page_name"));
-        assert!(!missing_param_error(
-            json!({"page_name": "My Page"}),
-            "page_name"
-        ));
+    client.set_page_icon(page_name, icon).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "page_name": page_name,
+        "icon": icon
+    }))
+}
+
+/// Checks that an icon is either a single emoji sequence or an ASCII icon code.
+fn is_valid_icon(icon: &str) -> bool {
+    if icon.is_empty() {
+        return false;
     }
+    let is_icon_code = icon.len() <= 64
+        && icon
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    // Emoji sequences are short; the longest ZWJ family sequences are 11
+    // scalars, and a whole sequence is one grapheme cluster
+    let is_emoji = icon.chars().count() <= 11
+        && icon.chars().all(is_emoji_scalar)
+        && icon.graphemes(true).count() == 1;
+    is_icon_code || is_emoji
+}
 
-    #[test]
-    fn test_update_block_requires_both_uuid_and_content() {
-        assert!(missing_param_error(json!({"uuid": "abc"}), "content"));
-        assert!(missing_param_error(json!({"content": "text"}), "uuid"));
-        assert!(!missing_param_error(
-            json!({"uuid": "abc", "content": "text"}),
-            "uuid"
+/// Whether a scalar value belongs to an emoji block or an emoji sequence modifier.
+fn is_emoji_scalar(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // Pictographs, emoticons, flags, skin tones
+            | 0x2300..=0x23FF // Misc technical (⌚, ⏰)
+            | 0x2600..=0x27BF // Misc symbols and dingbats
+            | 0x2B00..=0x2BFF // Arrows and stars (⭐)
+            | 0x200D // Zero-width joiner
+            | 0xFE0F // Emoji variation selector
+            | 0x20E3 // Combining keycap
+            | 0xE0020..=0xE007F // Tag sequences (subdivision flags)
+    )
+}
+
+/// Moves an existing block under a new parent or next to a new sibling.
+///
+/// Unlike deleting and re-inserting, the block keeps its UUID, so
+/// `((references))` to it remain valid. Child blocks move with it.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The block to move
+/// - `target_uuid` (required): The block to move relative to
+/// - `sibling` (optional): Positioning mode, defaults to false
+///   - `true`: Place after `target_uuid` at the same level
+///   - `false`: Place as a child of `target_uuid`
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `block`: The moved block as it is after the move
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - uuid or target_uuid parameters are missing
+/// - The block would be moved relative to itself (`McpError::InvalidParams`)
+/// - The Logseq API rejects the move (e.g. the target is in another graph)
+pub async fn move_block(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+
+    let target_uuid = params["target_uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("target_uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
+    require_block_uuid("target_uuid", target_uuid)?;
+
+    let sibling = params["sibling"].as_bool().unwrap_or(false);
+
+    if uuid == target_uuid {
+        return Err(McpError::InvalidParams(
+            "Cannot move a block relative to itself".to_string(),
         ));
-        assert!(!missing_param_error(
-            json!({"uuid": "abc", "content": "text"}),
-            "content"
+    }
+
+    client.move_block(uuid, target_uuid, sibling).await?;
+    let block = client.get_block(uuid).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "block": block
+    }))
+}
+
+/// Reorders the top-level blocks of a page.
+///
+/// The order comes either from an explicit list of UUIDs or from a
+/// `sort_by` key. Blocks are moved one at a time with `moveBlock`, and
+/// blocks already in place aren't touched. Child blocks move with their
+/// parents. A page-properties block, if any, always stays first.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page whose blocks to reorder
+/// - `order` (one of `order`/`sort_by` required): Top-level block UUIDs in
+///   the wanted order. Listed blocks go to the top of the page; unlisted
+///   ones follow in their current order
+/// - `sort_by` (one of `order`/`sort_by` required): `content_asc`,
+///   `content_desc`, `created_asc` or `created_desc`. Content is compared
+///   case-insensitively; blocks without a creation time sort last
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `sorted_count`: Number of top-level blocks in the new order
+/// - `order`: UUIDs of the top-level blocks in their new order
+/// - `moves`: Number of `moveBlock` calls made
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Both or neither of `order` and `sort_by` are given, or `sort_by` is unknown
+/// - A UUID in `order` isn't a top-level block of the page, or appears twice
+/// - The page doesn't exist
+/// - A move fails; blocks moved before it stay where they were put
+pub async fn sort_page_blocks(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let order = params.get("order").filter(|v| !v.is_null());
+    let sort_by = params["sort_by"].as_str();
+    if order.is_some() == sort_by.is_some() {
+        return Err(McpError::InvalidParams(
+            "Provide exactly one of order or sort_by".to_string(),
         ));
     }
 
-    #[test]
-    fn test_insert_block_sibling_defaults_to_false() {
-        let params = json!({});
-        let sibling = params["sibling"].as_bool().unwrap_or(false);
-        assert!(!sibling, "sibling should default to false");
+    if client.get_page(page_name).await?.is_null() {
+        return Err(McpError::Internal(format!("Page not found: {}", page_name)));
     }
+    let tree = client.get_page_blocks_tree(page_name).await?;
+    let top_level: Vec<&Value> = tree.as_array().into_iter().flatten().collect();
+    let anchor = top_level
+        .first()
+        .filter(|b| b["preBlock?"].as_bool() == Some(true))
+        .and_then(|b| b["uuid"].as_str());
+    let blocks: Vec<&Value> = top_level
+        .iter()
+        .copied()
+        .filter(|b| b["preBlock?"].as_bool() != Some(true) && b["uuid"].is_string())
+        .collect();
+    let current: Vec<String> = blocks
+        .iter()
+        .filter_map(|b| b["uuid"].as_str().map(str::to_string))
+        .collect();
 
-    #[test]
-    fn test_set_block_property_requires_key_and_value() {
-        assert!(missing_param_error(
-            json!({"uuid": "abc", "value": "v"}),
-            "key"
-        ));
-        assert!(missing_param_error(
-            json!({"uuid": "abc", "key": "k"}),
-            "value"
+    let desired = match (order, sort_by) {
+        (Some(order), _) => explicit_block_order(order, &current, page_name)?,
+        (None, Some(key)) => sorted_block_order(&blocks, key)?,
+        (None, None) => unreachable!("checked above"),
+    };
+
+    let moves = plan_block_moves(&current, &desired, anchor);
+    for (uuid, target, before) in &moves {
+        client
+            .move_block_with_opts(
+                uuid,
+                target,
+                serde_json::json!({ "before": before, "children": false }),
+            )
+            .await?;
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "sorted_count": desired.len(),
+        "order": desired,
+        "moves": moves.len()
+    }))
+}
+
+/// Validates an explicit `order` and extends it with the unlisted blocks.
+fn explicit_block_order(
+    order: &Value,
+    current: &[String],
+    page_name: &str,
+) -> McpResult<Vec<String>> {
+    let items = order.as_array().ok_or_else(|| {
+        McpError::InvalidParams("order must be an array of block UUIDs".to_string())
+    })?;
+    let mut desired: Vec<String> = Vec::with_capacity(current.len());
+    for item in items {
+        let uuid = item.as_str().ok_or_else(|| {
+            McpError::InvalidParams("order must be an array of block UUIDs".to_string())
+        })?;
+        if !current.iter().any(|c| c == uuid) {
+            return Err(McpError::InvalidParams(format!(
+                "Block {} is not a top-level block of page '{}'",
+                uuid, page_name
+            )));
+        }
+        if desired.iter().any(|d| d == uuid) {
+            return Err(McpError::InvalidParams(format!(
+                "Block {} appears more than once in order",
+                uuid
+            )));
+        }
+        desired.push(uuid.to_string());
+    }
+    let rest: Vec<String> = current
+        .iter()
+        .filter(|c| !desired.contains(c))
+        .cloned()
+        .collect();
+    desired.extend(rest);
+    Ok(desired)
+}
+
+/// Orders blocks by a `sort_by` key; the sort is stable, so ties keep their order.
+fn sorted_block_order(blocks: &[&Value], sort_by: &str) -> McpResult<Vec<String>> {
+    let content = |b: &Value| b["content"].as_str().unwrap_or("").to_lowercase();
+    // Blocks without a creation time go last in both directions
+    let created =
+        |a: &Value, b: &Value, desc: bool| match (a["createdAt"].as_i64(), b["createdAt"].as_i64())
+        {
+            (Some(x), Some(y)) if desc => y.cmp(&x),
+            (Some(x), Some(y)) => x.cmp(&y),
+            (x, y) => x.is_none().cmp(&y.is_none()),
+        };
+
+    let mut sorted = blocks.to_vec();
+    match sort_by {
+        "content_asc" => sorted.sort_by_key(|b| content(b)),
+        "content_desc" => sorted.sort_by_key(|b| std::cmp::Reverse(content(b))),
+        "created_asc" => sorted.sort_by(|a, b| created(a, b, false)),
+        "created_desc" => sorted.sort_by(|a, b| created(a, b, true)),
+        other => {
+            return Err(McpError::InvalidParams(format!(
+                "Unknown sort_by '{}'; expected content_asc, content_desc, created_asc or created_desc",
+                other
+            )));
+        }
+    }
+    Ok(sorted
+        .iter()
+        .filter_map(|b| b["uuid"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// Plans the `moveBlock` calls that turn `current` into `desired`, as
+/// `(uuid, target, before)` triples. Each block is placed right after the
+/// previous one; the first goes after `anchor` (the page-properties block)
+/// or before whatever is currently first.
+fn plan_block_moves(
+    current: &[String],
+    desired: &[String],
+    anchor: Option<&str>,
+) -> Vec<(String, String, bool)> {
+    let mut current = current.to_vec();
+    let mut moves = Vec::new();
+    for (i, uuid) in desired.iter().enumerate() {
+        if current.get(i) == Some(uuid) {
+            continue;
+        }
+        let (target, before) = match (i, anchor) {
+            (0, Some(anchor)) => (anchor.to_string(), false),
+            (0, None) => (current[0].clone(), true),
+            _ => (desired[i - 1].clone(), false),
+        };
+        moves.push((uuid.clone(), target, before));
+        if let Some(from) = current.iter().position(|c| c == uuid) {
+            current.remove(from);
+        }
+        current.insert(i, uuid.clone());
+    }
+    moves
+}
+
+/// Renames an existing page.
+///
+/// Logseq rewrites references to the page so existing `[[links]]` keep
+/// pointing at it under the new name.
+///
+/// # Parameters
+///
+/// - `old_page_name` (required): The current name of the page
+/// - `new_page_name` (required): The new name for the page
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `old_name`: The previous page name
+/// - `new_name`: The new page name
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either parameter is missing or empty
+/// - The page doesn't exist
+/// - A page with the new name already exists (the Logseq error is surfaced)
+pub async fn rename_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let old_name = params["old_page_name"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("old_page_name parameter is required".to_string())
+    })?;
+
+    let new_name = params["new_page_name"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("new_page_name parameter is required".to_string())
+    })?;
+
+    if old_name.trim().is_empty() || new_name.trim().is_empty() {
+        return Err(McpError::InvalidParams(
+            "Page names cannot be empty".to_string(),
         ));
     }
+
+    client.rename_page(old_name, new_name).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "old_name": old_name,
+        "new_name": new_name
+    }))
+}
+
+/// Sets a page-level property, creating it or replacing its value.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to update
+/// - `property_key` (required): Property name, e.g. `status`
+/// - `property_value` (required): Property value, e.g. `active`
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `page`: The updated page name
+/// - `property`: The property key that was set
+/// - `value`: The value that was set
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Any parameter is missing
+/// - The key is empty or contains `:` or a line break (`McpError::InvalidParams`),
+///   which Logseq's `key:: value` property syntax can't represent
+/// - The page doesn't exist or the API request fails
+pub async fn set_page_property(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let key = params["property_key"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("property_key parameter is required".to_string()))?;
+
+    let value = params["property_value"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("property_value parameter is required".to_string())
+    })?;
+
+    if !is_valid_property_key(key) {
+        return Err(McpError::InvalidParams(format!(
+            "Invalid property key '{}': must be non-empty and contain no ':' or line breaks",
+            key
+        )));
+    }
+
+    let mut properties = serde_json::Map::new();
+    properties.insert(key.to_string(), Value::String(value.to_string()));
+    client
+        .upsert_page_properties(page_name, Value::Object(properties))
+        .await?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "page": page_name,
+        "property": key,
+        "value": value
+    }))
+}
+
+/// Checks that a key can be written as a Logseq `key:: value` property.
+fn is_valid_property_key(key: &str) -> bool {
+    !key.trim().is_empty() && !key.contains([':', '\n', '\r'])
+}
+
+/// Creates the journal page for a given date.
+///
+/// The date is converted to a page name using the graph's configured date
+/// format, so callers never need to know whether the graph uses
+/// `Jan 15th, 2025` or `2025-01-15` style journal names.
+///
+/// # Parameters
+///
+/// - `date` (required): ISO 8601 date, e.g. `2025-01-15`
+/// - `content` (optional): Text for a first block on the new journal page
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `page`: The created (or already existing) journal page object
+/// - `date`: The formatted journal page name
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The date parameter is missing or not a valid `YYYY-MM-DD` date (`McpError::InvalidParams`)
+/// - The page or block cannot be created
+pub async fn create_journal_entry(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let date_str = params["date"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("date parameter is required".to_string()))?;
+
+    let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|_| {
+        McpError::InvalidParams(format!("Invalid date '{}', expected YYYY-MM-DD", date_str))
+    })?;
+
+    let formatter = client.get_date_formatter().await?;
+    let fmt_str = formatter.as_str().unwrap_or("MMM do, yyyy");
+    let page_name = format_journal_date(date, fmt_str).to_lowercase();
+
+    let page = client.create_journal_page(&page_name).await?;
+    if let Some(content) = params["content"].as_str() {
+        client.append_block_in_page(&page_name, content).await?;
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "page": page,
+        "date": page_name
+    }))
+}
+
+/// Inserts several blocks under (or after) one block in a single tool call.
+///
+/// Blocks are inserted sequentially in array order. Each element is
+/// positioned relative to `parent_uuid` just like `insert_block`.
+///
+/// # Parameters
+///
+/// - `parent_uuid` (required): UUID of the block to insert relative to
+/// - `blocks` (required): Array of `{ "content": string, "sibling"?: bool }` objects
+///
+/// A `notifications/progress` update is sent after each block when the
+/// client supplied a progress token.
+///
+/// # Returns
+///
+/// When every insert succeeds:
+/// - `success`: true
+/// - `blocks`: The created block objects, in order
+/// - `count`: Number of blocks created
+///
+/// When any insert fails, the remaining blocks are still attempted and the
+/// response is a partial success:
+/// - `success`: false
+/// - `inserted`: The block objects that were created
+/// - `failed`: `[{ "index": N, "error": "..." }]` for each failed element
+///
+/// # Errors
+///
+/// Returns an error if `parent_uuid` or `blocks` is missing, or if any
+/// element lacks a `content` string (`McpError::InvalidParams`). These are
+/// checked before anything is inserted.
+pub async fn batch_insert_blocks(
+    client: &LogseqClient,
+    params: Value,
+    progress: &ProgressReporter,
+) -> McpResult<Value> {
+    let parent_uuid = params["parent_uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("parent_uuid parameter is required".to_string()))?;
+    require_block_uuid("parent_uuid", parent_uuid)?;
+
+    let blocks = params["blocks"]
+        .as_array()
+        .ok_or_else(|| McpError::InvalidParams("blocks parameter is required".to_string()))?;
+
+    let entries = blocks
+        .iter()
+        .enumerate()
+        .map(|(i, block)| {
+            let content = block["content"].as_str().ok_or_else(|| {
+                McpError::InvalidParams(format!("blocks[{}] is missing a content string", i))
+            })?;
+            Ok((content, block["sibling"].as_bool().unwrap_or(false)))
+        })
+        .collect::<McpResult<Vec<_>>>()?;
+
+    let total = entries.len();
+    let mut inserted = Vec::new();
+    let mut failed = Vec::new();
+    for (index, (content, sibling)) in entries.into_iter().enumerate() {
+        match client.insert_block(parent_uuid, content, sibling).await {
+            Ok(block) => inserted.push(block),
+            Err(e) => failed.push(serde_json::json!({
+                "index": index,
+                "error": e.to_string()
+            })),
+        }
+        progress.report(
+            (index + 1) as f64,
+            total as f64,
+            &format!("Inserted block {} of {}", index + 1, total),
+        );
+    }
+
+    Ok(batch_response(inserted, failed))
+}
+
+/// Appends several blocks to the end of a page, in order.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to append to
+/// - `contents` (required): Non-empty array of block contents
+///
+/// # Returns
+///
+/// Progress is reported after each block, as in `batch_insert_blocks`.
+///
+/// Same shape as `batch_insert_blocks`: `{ success: true, blocks, count }`
+/// when every append succeeds, otherwise `{ success: false, inserted, failed }`
+/// where `failed` holds `{ index, error }` for each failed element.
+///
+/// # Errors
+///
+/// Returns an error if `page_name` is missing, or `McpError::InvalidParams`
+/// if `contents` is missing, empty, or contains a non-string element.
+pub async fn append_multiple_blocks_to_page(
+    client: &LogseqClient,
+    params: Value,
+    progress: &ProgressReporter,
+) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let contents = params["contents"]
+        .as_array()
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| {
+            McpError::InvalidParams("contents must be a non-empty array of strings".to_string())
+        })?
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            c.as_str()
+                .ok_or_else(|| McpError::InvalidParams(format!("contents[{}] must be a string", i)))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let total = contents.len();
+    let mut inserted = Vec::new();
+    let mut failed = Vec::new();
+    for (index, content) in contents.into_iter().enumerate() {
+        match client.append_block_in_page(page_name, content).await {
+            Ok(block) => inserted.push(block),
+            Err(e) => failed.push(serde_json::json!({
+                "index": index,
+                "error": e.to_string()
+            })),
+        }
+        progress.report(
+            (index + 1) as f64,
+            total as f64,
+            &format!("Appended block {} of {}", index + 1, total),
+        );
+    }
+
+    Ok(batch_response(inserted, failed))
+}
+
+/// Builds the shared full/partial success response for multi-block inserts.
+fn batch_response(inserted: Vec<Value>, failed: Vec<Value>) -> Value {
+    if failed.is_empty() {
+        serde_json::json!({
+            "success": true,
+            "count": inserted.len(),
+            "blocks": inserted
+        })
+    } else {
+        serde_json::json!({
+            "success": false,
+            "inserted": inserted,
+            "failed": failed
+        })
+    }
+}
+
+/// Copies a block's content to a new location, optionally with its whole subtree.
+///
+/// The copy is a new block with its own UUID; `((references))` to the source
+/// keep pointing at the source.
+///
+/// # Parameters
+///
+/// - `source_uuid` (required): The block to copy
+/// - `target_parent_uuid` (required): Where to put the copy
+/// - `sibling` (optional): Place the copy after the target (true) or as its
+///   child (false, default)
+/// - `recursive` (optional): Also copy all descendants (default: false)
+///
+/// # Recursive Copies
+///
+/// Children are copied depth-first, one API call per block. The first child
+/// at each level is inserted as a child of the new parent, and each later
+/// child as a sibling of the previous copy, so the subtree keeps its shape
+/// and order. A failure part-way leaves the blocks copied so far in place.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `source_uuid`: The copied block
+/// - `new_block`: The newly inserted top-level copy
+/// - `blocks_copied`: Number of blocks created, including the top-level copy
+///
+/// # Errors
+///
+/// Returns an error if a required parameter is missing, the source block
+/// doesn't exist, or any insert fails.
+pub async fn copy_block(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let source_uuid = params["source_uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("source_uuid parameter is required".to_string()))?;
+
+    let target_parent_uuid = params["target_parent_uuid"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("target_parent_uuid parameter is required".to_string())
+    })?;
+    require_block_uuid("source_uuid", source_uuid)?;
+    require_block_uuid("target_parent_uuid", target_parent_uuid)?;
+
+    let sibling = params["sibling"].as_bool().unwrap_or(false);
+    let recursive = params["recursive"].as_bool().unwrap_or(false);
+
+    let source = client.get_block_with_tree(source_uuid, recursive).await?;
+    if source.is_null() {
+        return Err(McpError::Internal(format!(
+            "Block not found: {}",
+            source_uuid
+        )));
+    }
+
+    let content = source["content"].as_str().unwrap_or("");
+    let new_block = client
+        .insert_block(target_parent_uuid, content, sibling)
+        .await?;
+
+    let mut blocks_copied = 1;
+    if recursive {
+        let new_uuid = inserted_uuid(&new_block)?;
+        blocks_copied += copy_children(client, &source["children"], new_uuid).await?;
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "source_uuid": source_uuid,
+        "new_block": new_block,
+        "blocks_copied": blocks_copied
+    }))
+}
+
+/// Copies a tree of child blocks under `parent_uuid`, depth-first, and
+/// returns how many blocks were created.
+async fn copy_children(
+    client: &LogseqClient,
+    children: &Value,
+    parent_uuid: String,
+) -> McpResult<usize> {
+    let mut copied = 0;
+    let mut pending = vec![(children, parent_uuid)];
+    while let Some((children, parent_uuid)) = pending.pop() {
+        let mut previous: Option<String> = None;
+        for child in children.as_array().into_iter().flatten() {
+            let content = child["content"].as_str().unwrap_or("");
+            let block = match &previous {
+                Some(prev) => client.insert_block(prev, content, true).await?,
+                None => client.insert_block(&parent_uuid, content, false).await?,
+            };
+            let uuid = inserted_uuid(&block)?;
+            copied += 1;
+            pending.push((&child["children"], uuid.clone()));
+            previous = Some(uuid);
+        }
+    }
+    Ok(copied)
+}
+
+/// Reads the UUID of a block returned by `insertBlock`.
+fn inserted_uuid(block: &Value) -> McpResult<String> {
+    block["uuid"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| McpError::Internal("insertBlock returned no block uuid".to_string()))
+}
+
+/// Matches a task marker at the start of a block, capturing leading
+/// whitespace, the marker, and the rest of the content.
+static TASK_MARKER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"(?s)^(\s*)({})(\s.*)?$", TASK_MARKERS.join("|")))
+        .expect("valid task marker regex")
+});
+
+/// Sets a block's task marker (e.g. `TODO` -> `DONE`) without rewriting its text.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The task block
+/// - `new_marker` (required): One of `TODO`, `DONE`, `LATER`, `IN-PROGRESS`,
+///   `WAITING`, `DOING`, `NOW`, `CANCELLED`
+/// - `add_if_missing` (optional): Prepend the marker when the block has none
+///   (default: true)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `old_marker`: The previous marker, or null if there was none
+/// - `new_marker`: The marker now set
+/// - `block`: The block as it is after the update
+///
+/// # Errors
+///
+/// Returns an error if a parameter is missing or invalid, the block doesn't
+/// exist, or it has no marker and `add_if_missing` is false.
+pub async fn toggle_task_marker(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
+
+    let new_marker = params["new_marker"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("new_marker parameter is required".to_string()))?;
+    if !TASK_MARKERS.contains(&new_marker) {
+        return Err(McpError::InvalidParams(format!(
+            "new_marker must be one of {}",
+            TASK_MARKERS.join(", ")
+        )));
+    }
+
+    let add_if_missing = params["add_if_missing"].as_bool().unwrap_or(true);
+
+    let block = client.get_block(uuid).await?;
+    let content = block["content"]
+        .as_str()
+        .ok_or_else(|| McpError::Internal(format!("Block not found: {}", uuid)))?;
+
+    let (old_marker, new_content) = replace_task_marker(content, new_marker);
+    if old_marker.is_none() && !add_if_missing {
+        return Err(McpError::InvalidParams(format!(
+            "Block {} has no task marker and add_if_missing is false",
+            uuid
+        )));
+    }
+
+    client.update_block(uuid, &new_content).await?;
+    let block = client.get_block(uuid).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "old_marker": old_marker,
+        "new_marker": new_marker,
+        "block": block
+    }))
+}
+
+/// Swaps the leading task marker in `content` for `marker`, or prepends
+/// `marker` if there isn't one. Returns the old marker and the new content.
+fn replace_task_marker(content: &str, marker: &str) -> (Option<String>, String) {
+    match TASK_MARKER_RE.captures(content) {
+        Some(caps) => {
+            let indent = caps.get(1).map_or("", |m| m.as_str());
+            let rest = caps.get(3).map_or("", |m| m.as_str());
+            (
+                Some(caps[2].to_string()),
+                format!("{}{}{}", indent, marker, rest),
+            )
+        }
+        None if content.is_empty() => (None, marker.to_string()),
+        None => (None, format!("{} {}", marker, content)),
+    }
+}
+
+/// Inserts a Logseq template's blocks after a target block.
+///
+/// # Parameters
+///
+/// - `template_name` (required): Name of the template (its `template::` value)
+/// - `target_uuid` (optional): Block to insert the template after
+/// - `page_name` (optional): Insert after this page's last top-level block
+///   instead; one of `target_uuid` or `page_name` is required
+///
+/// # Returns
+///
+/// `{ "success": true, "inserted_blocks": <Logseq result> }`
+///
+/// # Errors
+///
+/// Returns `McpError::InvalidParams` if no target is given, `target_uuid`
+/// isn't a block UUID, the page has no blocks, or the template doesn't exist.
+pub async fn execute_template(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let template_name = params["template_name"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("template_name parameter is required".to_string())
+    })?;
+
+    let target_uuid = match (params["target_uuid"].as_str(), params["page_name"].as_str()) {
+        (Some(uuid), _) => {
+            require_block_uuid("target_uuid", uuid)?;
+            uuid.to_string()
+        }
+        (None, Some(page_name)) => last_block_uuid(client, page_name).await?,
+        (None, None) => {
+            return Err(McpError::InvalidParams(
+                "target_uuid or page_name parameter is required".to_string(),
+            ));
+        }
+    };
+
+    if !client.template_exists(template_name).await? {
+        return Err(McpError::InvalidParams(format!(
+            "Template '{}' not found",
+            template_name
+        )));
+    }
+
+    let inserted = client.insert_template(&target_uuid, template_name).await?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "inserted_blocks": inserted
+    }))
+}
+
+/// UUID of the last top-level block on a page.
+async fn last_block_uuid(client: &LogseqClient, page_name: &str) -> McpResult<String> {
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    blocks
+        .as_array()
+        .and_then(|blocks| blocks.last())
+        .and_then(|block| block["uuid"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            McpError::InvalidParams(format!(
+                "Page '{}' has no blocks to insert the template after",
+                page_name
+            ))
+        })
+}
+
+/// Finds and replaces text across every block of a page.
+///
+/// All matches are counted before anything is written: if the total would
+/// exceed `max_replacements`, nothing is changed and an error is returned,
+/// so a too-broad pattern can't half-rewrite a page.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to edit
+/// - `find` (required): Text to find (or a regex pattern with `use_regex`)
+/// - `replace` (required): Replacement text; with `use_regex` it may use
+///   capture groups such as `$1`
+/// - `use_regex` (optional): Treat `find` as a regular expression (default: false)
+/// - `max_replacements` (optional): Maximum matches to replace (default: 50)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `success`: Boolean indicating the operation succeeded
+/// - `replacements`: Number of matches replaced
+/// - `affected_blocks`: UUIDs of the updated blocks, in page order
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A required parameter is missing or `find` is empty
+/// - `find` is not a valid regex, or matches the empty string, with `use_regex`
+/// - The page doesn't exist
+/// - The page has more matches than `max_replacements`
+/// - A block update fails; blocks updated before it keep their new content
+pub async fn replace_in_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let find = params["find"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("find parameter is required".to_string()))?;
+    let replace = params["replace"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("replace parameter is required".to_string()))?;
+    let use_regex = params["use_regex"].as_bool().unwrap_or(false);
+    let max_replacements = params["max_replacements"].as_u64().unwrap_or(50) as usize;
+
+    if find.is_empty() {
+        return Err(McpError::InvalidParams(
+            "find must not be empty".to_string(),
+        ));
+    }
+    let pattern = if use_regex {
+        let re = Regex::new(find)
+            .map_err(|e| McpError::InvalidParams(format!("Invalid regex '{}': {}", find, e)))?;
+        if re.is_match("") {
+            return Err(McpError::InvalidParams(format!(
+                "Regex '{}' matches the empty string",
+                find
+            )));
+        }
+        re
+    } else {
+        Regex::new(&regex::escape(find)).expect("escaped pattern is a valid regex")
+    };
+    // Plain replacements are literal, so `$` in them must not expand groups
+    let replace = if use_regex {
+        replace.to_string()
+    } else {
+        replace.replace('$', "$$")
+    };
+
+    if client.get_page(page_name).await?.is_null() {
+        return Err(McpError::Internal(format!("Page not found: {}", page_name)));
+    }
+
+    let tree = client.get_page_blocks_tree(page_name).await?;
+    let mut blocks = Vec::new();
+    flatten_blocks(&tree, &mut blocks);
+
+    let (edits, replacements) = plan_replacements(&blocks, &pattern, &replace);
+    if replacements > max_replacements {
+        return Err(McpError::InvalidParams(format!(
+            "Found {} matches on '{}', more than max_replacements ({}); nothing was changed",
+            replacements, page_name, max_replacements
+        )));
+    }
+
+    let mut affected_blocks = Vec::with_capacity(edits.len());
+    for (uuid, content) in edits {
+        client.update_block(&uuid, &content).await?;
+        affected_blocks.push(uuid);
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "replacements": replacements,
+        "affected_blocks": affected_blocks
+    }))
+}
+
+/// Works out the new content of each block `pattern` matches, returning
+/// `(uuid, new_content)` pairs and the total number of matches.
+fn plan_replacements(
+    blocks: &[&Value],
+    pattern: &Regex,
+    replace: &str,
+) -> (Vec<(String, String)>, usize) {
+    let mut edits = Vec::new();
+    let mut total = 0;
+    for block in blocks {
+        let (Some(uuid), Some(content)) = (block["uuid"].as_str(), block["content"].as_str())
+        else {
+            continue;
+        };
+        let matches = pattern.find_iter(content).count();
+        if matches == 0 {
+            continue;
+        }
+        total += matches;
+        let updated = pattern.replace_all(content, replace);
+        if updated != content {
+            edits.push((uuid.to_string(), updated.into_owned()));
+        }
+    }
+    (edits, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{config::Config, logseq_client::LogseqClient};
+    use wiremock::matchers::{body_json, body_partial_json, method};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    /// A client pointed at `server`.
+    fn test_client(server: &MockServer) -> LogseqClient {
+        LogseqClient::new(Config {
+            logseq_api_token: "test-token".to_string(),
+            logseq_api_url: server.uri(),
+            ..Config::default()
+        })
+        .unwrap()
+    }
+
+    /// Helper to call parameter-validation logic without a real Logseq client.
+    /// We test only the validation path (before the async client call).
+    fn missing_param_error(params: serde_json::Value, key: &str) -> bool {
+        params[key].as_str().is_none()
+    }
+
+    #[test]
+    fn test_create_page_requires_page_name() {
+        assert!(missing_param_error(json!({}), "page_name"));
+        assert!(!missing_param_error(
+            json!({"page_name": "My Page"}),
+            "page_name"
+        ));
+    }
+
+    #[test]
+    fn test_update_block_requires_both_uuid_and_content() {
+        assert!(missing_param_error(json!({"uuid": "abc"}), "content"));
+        assert!(missing_param_error(json!({"content": "text"}), "uuid"));
+        assert!(!missing_param_error(
+            json!({"uuid": "abc", "content": "text"}),
+            "uuid"
+        ));
+        assert!(!missing_param_error(
+            json!({"uuid": "abc", "content": "text"}),
+            "content"
+        ));
+    }
+
+    #[test]
+    fn test_insert_block_sibling_defaults_to_false() {
+        let params = json!({});
+        let sibling = params["sibling"].as_bool().unwrap_or(false);
+        assert!(!sibling, "sibling should default to false");
+    }
+
+    #[test]
+    fn test_set_block_property_requires_key_and_value() {
+        assert!(missing_param_error(
+            json!({"uuid": "abc", "value": "v"}),
+            "key"
+        ));
+        assert!(missing_param_error(
+            json!({"uuid": "abc", "key": "k"}),
+            "value"
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_icon() {
+        use super::is_valid_icon;
+        assert!(is_valid_icon("📚"));
+        assert!(is_valid_icon("👩‍💻"));
+        assert!(is_valid_icon("⭐"));
+        assert!(is_valid_icon("ti-book"));
+        assert!(!is_valid_icon(""));
+        assert!(!is_valid_icon("📚 books"));
+        assert!(!is_valid_icon("two words"));
+        assert!(is_valid_icon("👍🏽"));
+        assert!(is_valid_icon("🇯🇵"));
+        assert!(!is_valid_icon("📚📚📚"));
+        assert!(!is_valid_icon("⭐⭐"));
+        assert!(!is_valid_icon("👩‍💻📚"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_page_errors_when_page_missing() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.deletePage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::delete_page(&client, json!({"page_name": "Ghost"}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Page not found: Ghost");
+    }
+
+    #[test]
+    fn test_is_valid_property_key() {
+        use super::is_valid_property_key;
+
+        assert!(is_valid_property_key("status"));
+        assert!(is_valid_property_key("due-date"));
+        assert!(!is_valid_property_key(""));
+        assert!(!is_valid_property_key("  "));
+        assert!(!is_valid_property_key("a:b"));
+        assert!(!is_valid_property_key("multi\nline"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_insert_blocks_reports_partial_failure() {
+        const PARENT: &str = "6650a1b2-0000-4000-8000-0000000000ff";
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"args": [PARENT, "bad"]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"error": "boom"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.insertBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "new"})))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result = super::batch_insert_blocks(
+            &client,
+            json!({
+                "parent_uuid": PARENT,
+                "blocks": [{"content": "one"}, {"content": "bad"}, {"content": "three", "sibling": true}]
+            }),
+            &crate::progress::ProgressReporter::disabled(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["success"], false);
+        assert_eq!(result["inserted"].as_array().unwrap().len(), 2);
+        assert_eq!(result["failed"][0]["index"], 1);
+    }
+
+    #[test]
+    fn test_batch_response_shapes() {
+        let ok = super::batch_response(vec![json!({"uuid": "a"})], vec![]);
+        assert_eq!(ok["success"], true);
+        assert_eq!(ok["count"], 1);
+
+        let partial = super::batch_response(vec![], vec![json!({"index": 0, "error": "x"})]);
+        assert_eq!(partial["success"], false);
+        assert_eq!(partial["failed"][0]["index"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_copy_block_recursive_preserves_structure() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "uuid": "6650a1b2-0000-4000-8000-00000000000a", "content": "Root", "children": [
+                    {"uuid": "c1", "content": "A", "children": [
+                        {"uuid": "c3", "content": "A1", "children": []}
+                    ]},
+                    {"uuid": "c2", "content": "B", "children": []}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        // Each copy gets the uuid "new-<content>" so the anchors can be checked
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.insertBlock"}),
+            ))
+            .respond_with(|req: &Request| {
+                let body: serde_json::Value = req.body_json().unwrap();
+                let content = body["args"][1].as_str().unwrap();
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "uuid": format!("new-{}", content),
+                    "content": content
+                }))
+            })
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result = super::copy_block(
+            &client,
+            json!({"source_uuid": "6650a1b2-0000-4000-8000-00000000000a", "target_parent_uuid": "6650a1b2-0000-4000-8000-00000000000b", "recursive": true}),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["new_block"]["uuid"], "new-Root");
+        assert_eq!(result["blocks_copied"], 4);
+
+        let mut inserts: Vec<serde_json::Value> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.body_json::<serde_json::Value>().unwrap())
+            .filter(|b| b["method"] == "logseq.Editor.insertBlock")
+            .map(|b| b["args"].clone())
+            .collect();
+        inserts.sort_by_key(|args| args[1].as_str().unwrap().to_string());
+        assert_eq!(
+            inserts,
+            vec![
+                json!(["new-Root", "A", {"sibling": false}]),
+                json!(["new-A", "A1", {"sibling": false}]),
+                json!(["new-A", "B", {"sibling": true}]),
+                json!(["6650a1b2-0000-4000-8000-00000000000b", "Root", {"sibling": false}]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_block_non_recursive_copies_one_block() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "method": "logseq.Editor.getBlock",
+                "args": ["6650a1b2-0000-4000-8000-00000000000a", {"includeChildren": false}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                json!({"uuid": "6650a1b2-0000-4000-8000-00000000000a", "content": "Root", "children": [["uuid", "c1"]]}),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "method": "logseq.Editor.insertBlock",
+                "args": ["6650a1b2-0000-4000-8000-00000000000b", "Root", {"sibling": true}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "copy"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result = super::copy_block(
+            &client,
+            json!({"source_uuid": "6650a1b2-0000-4000-8000-00000000000a", "target_parent_uuid": "6650a1b2-0000-4000-8000-00000000000b", "sibling": true}),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["success"], true);
+        assert_eq!(
+            result["source_uuid"],
+            "6650a1b2-0000-4000-8000-00000000000a"
+        );
+        assert_eq!(result["blocks_copied"], 1);
+    }
+
+    #[test]
+    fn test_replace_task_marker() {
+        use super::replace_task_marker;
+
+        assert_eq!(
+            replace_task_marker("TODO write docs", "DONE"),
+            (Some("TODO".to_string()), "DONE write docs".to_string())
+        );
+        assert_eq!(
+            replace_task_marker("IN-PROGRESS ship\nid:: 1234", "WAITING"),
+            (
+                Some("IN-PROGRESS".to_string()),
+                "WAITING ship\nid:: 1234".to_string()
+            )
+        );
+        assert_eq!(
+            replace_task_marker("DOING", "DONE"),
+            (Some("DOING".to_string()), "DONE".to_string())
+        );
+        // Not a marker: only a prefix of a longer word
+        assert_eq!(
+            replace_task_marker("TODOS for today", "TODO"),
+            (None, "TODO TODOS for today".to_string())
+        );
+        assert_eq!(replace_task_marker("", "NOW"), (None, "NOW".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_execute_template_uses_last_page_block() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPageBlocksTree"}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([{"uuid": "first"}, {"uuid": "last"}])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_json(
+                json!({"method": "logseq.App.existTemplate", "args": ["meeting"]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.App.insertTemplate",
+                "args": ["last", "meeting"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"uuid": "new"}])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result = super::execute_template(
+            &client,
+            json!({"template_name": "meeting", "page_name": "Notes"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result,
+            json!({"success": true, "inserted_blocks": [{"uuid": "new"}]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_template_rejects_unknown_template() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.App.existTemplate"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(false)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.App.insertTemplate"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::execute_template(
+            &client,
+            json!({
+                "template_name": "nope",
+                "target_uuid": "6650a1b2-0000-4000-8000-0000000000b1"
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid params: Template 'nope' not found");
+
+        let err = super::execute_template(&client, json!({"template_name": "nope"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("target_uuid or page_name"));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_uuid_rejected_before_api_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::delete_block(&client, json!({"uuid": "abc"}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid params: Parameter 'uuid' must be a block UUID, got 'abc'"
+        );
+        assert!(
+            super::insert_block(&client, json!({"parent_uuid": "p", "content": "x"}))
+                .await
+                .is_err()
+        );
+        let err = super::execute_template(
+            &client,
+            json!({"template_name": "meeting", "target_uuid": "b1"}),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid params: Parameter 'target_uuid' must be a block UUID, got 'b1'"
+        );
+
+        let block = "6650a1b2-0000-4000-8000-00000000000a";
+        let calls = [
+            super::move_block(&client, json!({"uuid": block, "target_uuid": "t"})).await,
+            super::toggle_task_marker(&client, json!({"uuid": "b", "new_marker": "DONE"})).await,
+            super::set_block_property(&client, json!({"uuid": "b", "key": "k", "value": "v"}))
+                .await,
+            super::remove_block_property(&client, json!({"uuid": "b", "key": "k"})).await,
+            super::batch_insert_blocks(
+                &client,
+                json!({"parent_uuid": "p", "blocks": [{"content": "x"}]}),
+                &crate::progress::ProgressReporter::disabled(),
+            )
+            .await,
+        ];
+        for result in calls {
+            let err = result.unwrap_err();
+            assert!(err.to_string().contains("must be a block UUID"), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_block_before_and_after_options() {
+        let reference = "6650a1b2-0000-4000-8000-00000000000a";
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": reference})))
+            .mount(&server)
+            .await;
+        for before in [true, false] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(json!({
+                    "method": "logseq.Editor.insertBlock",
+                    "args": [reference, "New", {"sibling": true, "before": before}]
+                })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "new"})))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = test_client(&server);
+        let params = json!({"reference_uuid": reference, "content": "New"});
+        let before = super::insert_block_before(&client, params.clone())
+            .await
+            .unwrap();
+        let after = super::insert_block_after(&client, params).await.unwrap();
+        assert_eq!(before["block"]["uuid"], "new");
+        assert_eq!(after["success"], true);
+    }
+
+    #[tokio::test]
+    async fn test_insert_block_after_missing_reference() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.insertBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::insert_block_after(
+            &client,
+            json!({"reference_uuid": "6650a1b2-0000-4000-8000-00000000000b", "content": "x"}),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Block not found: 6650a1b2-0000-4000-8000-00000000000b"
+        );
+    }
+
+    async fn prepend_server(tree: serde_json::Value) -> wiremock::MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "notes"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPageBlocksTree"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tree))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_prepend_to_page_inserts_before_first_block() {
+        let server = prepend_server(json!([
+            {"uuid": "first", "content": "First"},
+            {"uuid": "second", "content": "Second"}
+        ]))
+        .await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.insertBlock",
+                "args": ["first", "Top", {"sibling": true, "before": true}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "new"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result =
+            super::prepend_to_page(&client, json!({"page_name": "Notes", "content": "Top"}))
+                .await
+                .unwrap();
+        assert_eq!(result, json!({"success": true, "block": {"uuid": "new"}}));
+    }
+
+    #[tokio::test]
+    async fn test_prepend_to_page_keeps_page_properties_first() {
+        let server = prepend_server(json!([
+            {"uuid": "props", "content": "tags:: rust", "preBlock?": true},
+            {"uuid": "first", "content": "First"}
+        ]))
+        .await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.insertBlock",
+                "args": ["props", "Top", {"sibling": true, "before": false}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "new"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        super::prepend_to_page(&client, json!({"page_name": "Notes", "content": "Top"}))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prepend_to_empty_page_appends() {
+        let server = prepend_server(json!([])).await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.appendBlockInPage",
+                "args": ["Notes", "Top"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"uuid": "new"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result =
+            super::prepend_to_page(&client, json!({"page_name": "Notes", "content": "Top"}))
+                .await
+                .unwrap();
+        assert_eq!(result["block"]["uuid"], "new");
+    }
+
+    #[tokio::test]
+    async fn test_prepend_to_page_errors_when_page_missing() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::prepend_to_page(&client, json!({"page_name": "Ghost", "content": "x"}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Page not found: Ghost");
+    }
+
+    #[test]
+    fn test_plan_replacements() {
+        use super::plan_replacements;
+        use regex::Regex;
+
+        let tree = json!([
+            {"uuid": "a", "content": "colour and colour", "children": [
+                {"uuid": "b", "content": "no match here"},
+                {"uuid": "c", "content": "one colour"}
+            ]},
+            {"uuid": "d", "content": "colour"}
+        ]);
+        let mut blocks = Vec::new();
+        crate::utils::flatten_blocks(&tree, &mut blocks);
+
+        let (edits, total) = plan_replacements(&blocks, &Regex::new("colour").unwrap(), "color");
+        assert_eq!(total, 4);
+        assert_eq!(
+            edits,
+            vec![
+                ("a".to_string(), "color and color".to_string()),
+                ("c".to_string(), "one color".to_string()),
+                ("d".to_string(), "color".to_string()),
+            ]
+        );
+
+        let (edits, total) =
+            plan_replacements(&blocks, &Regex::new(r"(\w+) colour").unwrap(), "$1 hue");
+        assert_eq!(total, 2);
+        assert_eq!(edits[0].1, "colour and hue");
+        assert_eq!(edits[1].1, "one hue");
+    }
+
+    #[tokio::test]
+    async fn test_replace_in_page_updates_matching_blocks() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "notes"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPageBlocksTree"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"uuid": "a", "content": "Costs $5", "children": [
+                    {"uuid": "b", "content": "Untouched"}
+                ]}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.updateBlock",
+                "args": ["a", "Costs $10"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result = super::replace_in_page(
+            &client,
+            json!({"page_name": "Notes", "find": "$5", "replace": "$10"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result,
+            json!({"success": true, "replacements": 1, "affected_blocks": ["a"]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_in_page_over_limit_changes_nothing() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "notes"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPageBlocksTree"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"uuid": "a", "content": "x x"},
+                {"uuid": "b", "content": "x"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.updateBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::replace_in_page(
+            &client,
+            json!({"page_name": "Notes", "find": "x", "replace": "y", "max_replacements": 2}),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid params: Found 3 matches on 'Notes', more than max_replacements (2); nothing was changed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_in_page_rejects_bad_patterns() {
+        // Validation fails before any request, so no server is needed
+        let client = LogseqClient::new(Config::default()).unwrap();
+        let replace = |find: &str, use_regex: bool| {
+            super::replace_in_page(
+                &client,
+                json!({"page_name": "Notes", "find": find, "replace": "", "use_regex": use_regex}),
+            )
+        };
+
+        let err = replace("(unclosed", true).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Invalid params: Invalid regex '(unclosed'")
+        );
+        let err = replace("a*", true).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid params: Regex 'a*' matches the empty string"
+        );
+        let err = replace("", false).await.unwrap_err();
+        assert_eq!(err.to_string(), "Invalid params: find must not be empty");
+    }
+
+    #[test]
+    fn test_plan_block_moves() {
+        use super::plan_block_moves;
+
+        let ids = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        let current = ids("a b c d");
+
+        assert!(plan_block_moves(&current, &current, None).is_empty());
+        assert_eq!(
+            plan_block_moves(&current, &ids("d a b c"), None),
+            vec![("d".to_string(), "a".to_string(), true)]
+        );
+        assert_eq!(
+            plan_block_moves(&current, &ids("d a b c"), Some("props")),
+            vec![("d".to_string(), "props".to_string(), false)]
+        );
+        assert_eq!(
+            plan_block_moves(&current, &ids("a c b d"), None),
+            vec![("c".to_string(), "a".to_string(), false)]
+        );
+        // Reversal: every block but the last is moved into place
+        assert_eq!(plan_block_moves(&current, &ids("d c b a"), None).len(), 3);
+    }
+
+    #[test]
+    fn test_sorted_and_explicit_block_order() {
+        use super::{explicit_block_order, sorted_block_order};
+
+        let blocks = [
+            json!({"uuid": "a", "content": "banana", "createdAt": 30}),
+            json!({"uuid": "b", "content": "Apple", "createdAt": 10}),
+            json!({"uuid": "c", "content": "cherry"}),
+        ];
+        let refs: Vec<&serde_json::Value> = blocks.iter().collect();
+        assert_eq!(
+            sorted_block_order(&refs, "content_asc").unwrap(),
+            ["b", "a", "c"]
+        );
+        assert_eq!(
+            sorted_block_order(&refs, "content_desc").unwrap(),
+            ["c", "a", "b"]
+        );
+        assert_eq!(
+            sorted_block_order(&refs, "created_asc").unwrap(),
+            ["b", "a", "c"]
+        );
+        assert_eq!(
+            sorted_block_order(&refs, "created_desc").unwrap(),
+            ["a", "b", "c"]
+        );
+        assert!(sorted_block_order(&refs, "random").is_err());
+
+        let current = ["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(
+            explicit_block_order(&json!(["c"]), &current, "P").unwrap(),
+            ["c", "a", "b"]
+        );
+        assert_eq!(
+            explicit_block_order(&json!(["x"]), &current, "P")
+                .unwrap_err()
+                .to_string(),
+            "Invalid params: Block x is not a top-level block of page 'P'"
+        );
+        assert_eq!(
+            explicit_block_order(&json!(["a", "a"]), &current, "P")
+                .unwrap_err()
+                .to_string(),
+            "Invalid params: Block a appears more than once in order"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sort_page_blocks_moves_out_of_place_blocks() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"name": "list"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPageBlocksTree"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"uuid": "props", "content": "type:: list", "preBlock?": true},
+                {"uuid": "b", "content": "Beta", "children": [{"uuid": "b1", "content": "zzz"}]},
+                {"uuid": "a", "content": "alpha"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "method": "logseq.Editor.moveBlock",
+                "args": ["a", "props", {"before": false, "children": false}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result = super::sort_page_blocks(
+            &client,
+            json!({"page_name": "List", "sort_by": "content_asc"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result,
+            json!({"success": true, "sorted_count": 2, "order": ["a", "b"], "moves": 1})
+        );
+
+        let err = super::sort_page_blocks(
+            &client,
+            json!({"page_name": "List", "sort_by": "content_asc", "order": ["a"]}),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid params: Provide exactly one of order or sort_by"
+        );
+    }
+
+    #[test]
+    fn test_content_diff() {
+        let diff = super::content_diff(
+            "buy milk\nand eggs",
+            "buy oat milk\nand eggs",
+            "Shopping/u1",
+        );
+        assert_eq!(diff["added"], 4);
+        assert_eq!(diff["removed"], 0);
+        assert_eq!(diff["unchanged"], 17);
+        assert_eq!(
+            diff["patch"],
+            "--- before/Shopping/u1\n+++ after/Shopping/u1\n@@ -1,2 +1,2 @@\n-buy milk\n+buy oat milk\n and eggs\n\\ No newline at end of file\n"
+        );
+
+        let same = super::content_diff("same", "same", "p/u");
+        assert_eq!(same["added"], 0);
+        assert_eq!(same["removed"], 0);
+        assert_eq!(same["unchanged"], 4);
+        assert_eq!(same["patch"], "");
+    }
+
+    #[tokio::test]
+    async fn test_update_block_with_diff() {
+        let uuid = "00000000-0000-0000-0000-000000000001";
+        let server = MockServer::start().await;
+        let get_block = json!({"method": "logseq.Editor.getBlock", "args": [uuid]});
+        Mock::given(method("POST"))
+            .and(body_json(&get_block))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"uuid": uuid, "content": "old text", "page": {"id": 7}})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_json(&get_block))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"uuid": uuid, "content": "new text", "page": {"id": 7}})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getPage", "args": [7]}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"originalName": "Notes"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_json(
+                json!({"method": "logseq.Editor.updateBlock", "args": [uuid, "new text"]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let result =
+            super::update_block_with_diff(&client, json!({"uuid": uuid, "content": "new text"}))
+                .await
+                .unwrap();
+
+        assert_eq!(result["success"], true);
+        assert_eq!(result["block"]["content"], "new text");
+        assert_eq!(result["diff"]["added"], 3);
+        assert_eq!(result["diff"]["removed"], 3);
+        assert_eq!(result["diff"]["unchanged"], 5);
+        let patch = result["diff"]["patch"].as_str().unwrap();
+        assert!(patch.starts_with(&format!(
+            "--- before/Notes/{uuid}\n+++ after/Notes/{uuid}\n"
+        )));
+        assert!(patch.contains("-old text\n") && patch.contains("+new text\n"));
+    }
+
+    #[tokio::test]
+    async fn test_update_block_with_diff_missing_block() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.getBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "logseq.Editor.updateBlock"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let err = super::update_block_with_diff(
+            &client,
+            json!({"uuid": "00000000-0000-0000-0000-000000000001", "content": "x"}),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Block not found: 00000000-0000-0000-0000-000000000001"
+        );
+    }
 }
@@ -18,10 +18,28 @@
 //! Parameter validation errors are returned immediately with descriptive messages.
 //! API errors from Logseq are propagated up to the MCP layer for consistent handling.
 
-use crate::logseq_client::LogseqClient;
-use anyhow::Result;
-use chrono::{Datelike, Local};
+use crate::{
+    error::{McpError, McpResult},
+    logseq_client::LogseqClient,
+    org_serializer::{block_tree_to_org, block_tree_to_org_without_properties},
+    progress::ProgressReporter,
+    prompts::template_pages,
+    utils::{
+        BlockStats, INCOMPLETE_TASK_MARKERS, analyze_block_tree, child_block_uuids, flatten_blocks,
+        format_block_tree_as_markdown, format_journal_date, prune_tree,
+    },
+    validation::{is_block_uuid, require_block_uuid},
+    version::SERVER_VERSION,
+};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use futures::future::{BoxFuture, join_all};
+use regex::Regex;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Lists available Logseq graphs.
 ///
@@ -36,91 +54,166 @@ use serde_json::Value;
 /// # Returns
 ///
 /// JSON object containing an array of graph information with name, path, and metadata.
-pub async fn list_graphs(client: &LogseqClient, _params: Value) -> Result<Value> {
+pub async fn list_graphs(client: &LogseqClient, _params: Value) -> McpResult<Value> {
     let graph = client.get_current_graph().await?;
     Ok(serde_json::json!({
         "graphs": [graph]
     }))
 }
 
-/// Retrieves a list of all pages in the current graph.
+/// Retrieves a page of results from the list of all pages in the current graph.
 ///
-/// Returns comprehensive information about every page in the graph, including
-/// page names, UUIDs, creation dates, and other metadata. Useful for getting
-/// an overview of the graph's structure and content.
+/// Returns information about pages in the graph, including page names, UUIDs,
+/// creation dates, and other metadata. Filtering and pagination happen on the
+/// server side so large graphs don't flood the client.
 ///
 /// # Parameters
 ///
-/// No parameters required.
+/// - `name_contains` (optional): Substring filter on page name (case-insensitive)
+/// - `namespace` (optional): Only pages in this namespace, e.g. `Projects` matches
+///   `Projects/2025` and `Projects/Archive/Old` (case-insensitive)
+/// - `include_journals` (optional): Include journal pages (default: true)
+/// - `limit` (optional): Max pages to return (default: 100)
+/// - `offset` (optional): Number of matching pages to skip (default: 0)
+/// - `timeout_ms` (optional): Request timeout for fetching the pages, overriding
+///   `LOGSEQ_REQUEST_TIMEOUT_MS`
 ///
 /// # Returns
 ///
-/// JSON object containing an array of page objects with metadata for each page
-/// in the graph.
-/// Optional params:
-/// - `name_contains`: substring filter on page name (case-insensitive)
-/// - `limit`: max pages to return (default: 100)
-pub async fn list_pages(client: &LogseqClient, params: Value) -> Result<Value> {
-    let pages = client.get_all_pages().await?;
+/// JSON object containing:
+/// - `pages`: The requested slice of matching page objects
+/// - `total`: Number of pages matching the filters, before pagination
+/// - `limit`, `offset`: The pagination values that were applied
+pub async fn list_pages(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let pages = match params["timeout_ms"].as_u64() {
+        Some(ms) => {
+            client
+                .get_all_pages_with_timeout(std::time::Duration::from_millis(ms))
+                .await?
+        }
+        None => client.get_all_pages().await?,
+    };
 
-    let name_filter = params["name_contains"].as_str().map(|s| s.to_lowercase());
+    let filter = PageFilter {
+        name_contains: params["name_contains"].as_str().map(|s| s.to_lowercase()),
+        namespace: params["namespace"]
+            .as_str()
+            .map(|s| s.trim_end_matches('/').to_lowercase()),
+        include_journals: params["include_journals"].as_bool().unwrap_or(true),
+    };
     let limit = params["limit"].as_u64().unwrap_or(100) as usize;
+    let offset = params["offset"].as_u64().unwrap_or(0) as usize;
 
-    let filtered: Vec<&Value> = pages
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .filter(|page| {
-                    name_filter.as_ref().is_none_or(|f| {
-                        page["name"]
-                            .as_str()
-                            .map(|n| n.to_lowercase().contains(f.as_str()))
-                            .unwrap_or(false)
-                    })
-                })
-                .take(limit)
-                .collect()
-        })
-        .unwrap_or_default();
+    let matching = filter.apply(&pages);
+    let total = matching.len();
+    let page = paginate(matching, offset, limit);
 
     Ok(serde_json::json!({
-        "pages": filtered,
-        "total": filtered.len()
+        "pages": page,
+        "total": total,
+        "limit": limit,
+        "offset": offset
     }))
 }
 
+/// Returns the `limit` items starting at `offset`; empty when `offset` is past the end.
+fn paginate<T>(items: Vec<T>, offset: usize, limit: usize) -> Vec<T> {
+    items.into_iter().skip(offset).take(limit).collect()
+}
+
+/// Page filters for `list_pages`; string fields are pre-lowercased.
+struct PageFilter {
+    name_contains: Option<String>,
+    namespace: Option<String>,
+    include_journals: bool,
+}
+
+impl PageFilter {
+    fn apply<'a>(&self, pages: &'a Value) -> Vec<&'a Value> {
+        pages
+            .as_array()
+            .map(|arr| arr.iter().filter(|page| self.matches(page)).collect())
+            .unwrap_or_default()
+    }
+
+    fn matches(&self, page: &Value) -> bool {
+        let name = page["name"].as_str().unwrap_or_default().to_lowercase();
+        if !self.include_journals && page["journal?"].as_bool() == Some(true) {
+            return false;
+        }
+        if let Some(f) = &self.name_contains
+            && !name.contains(f.as_str())
+        {
+            return false;
+        }
+        if let Some(ns) = &self.namespace
+            && !name
+                .strip_prefix(ns.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+        {
+            return false;
+        }
+        true
+    }
+}
+
 /// Retrieves comprehensive information about a specific page.
 ///
-/// Fetches both the page metadata and the complete block tree structure
-/// for the specified page. This provides a complete view of the page's
-/// content and organization.
+/// Fetches the page metadata and the complete block tree structure for the
+/// specified page by default. Each part is a separate API call, so callers
+/// that only need one can skip the other.
 ///
 /// # Parameters
 ///
 /// - `page_name` (required): The name of the page to retrieve
+/// - `include_metadata` (optional): Fetch page metadata (default: true)
+/// - `include_blocks` (optional): Fetch the block tree (default: true)
+/// - `include_properties` (optional): Also fetch page-level properties
+///   (default: false)
 ///
 /// # Returns
 ///
-/// JSON object containing:
+/// JSON object containing (each null when not requested):
 /// - `page`: Page metadata (name, UUID, properties)
 /// - `blocks`: Complete hierarchical block tree for the page
+/// - `properties`: Page-level property map; empty object if the page has none
 ///
 /// # Errors
 ///
 /// Returns an error if the page_name parameter is missing or if the page
 /// doesn't exist in the graph.
-pub async fn get_page(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn get_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let page_name = params["page_name"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("page_name parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let include_metadata = params["include_metadata"].as_bool().unwrap_or(true);
+    let include_blocks = params["include_blocks"].as_bool().unwrap_or(true);
+    let include_properties = params["include_properties"].as_bool().unwrap_or(false);
 
-    // Fetch both page metadata and block structure for complete information
-    let page_info = client.get_page(page_name).await?;
-    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let page_info = if include_metadata {
+        client.get_page(page_name).await?
+    } else {
+        Value::Null
+    };
+    let blocks = if include_blocks {
+        client.get_page_blocks_tree(page_name).await?
+    } else {
+        Value::Null
+    };
+    let properties = if include_properties {
+        // Pages without properties come back as null; report those as `{}`
+        match client.get_page_properties(page_name).await? {
+            props @ Value::Object(_) => props,
+            _ => serde_json::json!({}),
+        }
+    } else {
+        Value::Null
+    };
 
     Ok(serde_json::json!({
         "page": page_info,
-        "blocks": blocks
+        "blocks": blocks,
+        "properties": properties
     }))
 }
 
@@ -133,24 +226,40 @@ pub async fn get_page(client: &LogseqClient, params: Value) -> Result<Value> {
 /// # Parameters
 ///
 /// - `uuid` (required): The unique identifier of the block to retrieve
+/// - `include_children` (optional): Include nested child blocks (default: false)
+/// - `max_depth` (optional): Levels of children to keep when
+///   `include_children` is set (default: 3)
 ///
 /// # Returns
 ///
-/// JSON object containing the complete block information including content,
-/// properties, relationships, and metadata.
+/// JSON object containing:
+/// - `block`: The complete block information including content, properties,
+///   relationships, and metadata, with children pruned at `max_depth`
+/// - `truncated`: Whether pruning removed any children
 ///
 /// # Errors
 ///
 /// Returns an error if the uuid parameter is missing or if no block exists
 /// with the specified UUID.
-pub async fn get_block(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn get_block(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let uuid = params["uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
+    let include_children = params["include_children"].as_bool().unwrap_or(false);
+    let max_depth = params["max_depth"].as_u64().unwrap_or(3) as usize;
 
-    let block = client.get_block(uuid).await?;
+    let (block, truncated) = if include_children {
+        // getBlock returns the whole subtree, so depth is limited here
+        let mut block = client.get_block_with_tree(uuid, true).await?;
+        let truncated = prune_tree(&mut block, 0, max_depth);
+        (block, truncated)
+    } else {
+        (client.get_block(uuid).await?, false)
+    };
     Ok(serde_json::json!({
-        "block": block
+        "block": block,
+        "truncated": truncated
     }))
 }
 
@@ -163,13 +272,19 @@ pub async fn get_block(client: &LogseqClient, params: Value) -> Result<Value> {
 /// # Parameters
 ///
 /// - `query` (required): The search terms to look for
+/// - `timeout_ms` (optional): Request timeout for this search, overriding
+///   `LOGSEQ_REQUEST_TIMEOUT_MS`
+/// - `type` (optional): `all` (default), `block` or `page`
+/// - `page_name_only` (optional): Shorthand for `type: page` (default: false)
+/// - `limit` (optional): Maximum number of results to return after filtering
 ///
 /// # Returns
 ///
-/// JSON object containing an array of search results, each with:
-/// - Matching content snippets
-/// - Page/block context information
-/// - Relevance ranking from Logseq's search algorithm
+/// JSON object containing:
+/// - `results`: Matches in relevance order, each tagged with `type`
+///   (`block` items keep Logseq's block fields; `page` items have `name`)
+/// - `total_before_limit`: Number of matches after filtering, before `limit`
+/// - `query`: The search query
 ///
 /// # Search Features
 ///
@@ -181,15 +296,45 @@ pub async fn get_block(client: &LogseqClient, params: Value) -> Result<Value> {
 ///
 /// # Errors
 ///
-/// Returns an error if the query parameter is missing.
-pub async fn search(client: &LogseqClient, params: Value) -> Result<Value> {
+/// Returns an error if the query parameter is missing, `McpError::InvalidParams`
+/// for an unknown `type`, or `McpError::Timeout` if Logseq doesn't answer in time.
+pub async fn search(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let query = params["query"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("query parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("query parameter is required".to_string()))?;
+
+    let type_filter = if params["page_name_only"].as_bool() == Some(true) {
+        "page"
+    } else {
+        params["type"].as_str().unwrap_or("all")
+    };
+    if !["all", "block", "page"].contains(&type_filter) {
+        return Err(McpError::InvalidParams(format!(
+            "Invalid type '{}', expected all, block or page",
+            type_filter
+        )));
+    }
+
+    let raw = match params["timeout_ms"].as_u64() {
+        Some(ms) => {
+            client
+                .search_with_timeout(query, std::time::Duration::from_millis(ms))
+                .await?
+        }
+        None => client.search(query).await?,
+    };
+
+    let results: Vec<Value> = search_items(raw)
+        .into_iter()
+        .filter(|item| type_filter == "all" || item["type"] == type_filter)
+        .collect();
+    let total = results.len();
+    let limit = params["limit"].as_u64().map_or(total, |l| l as usize);
 
-    let results = client.search(query).await?;
     Ok(serde_json::json!({
-        "results": results
+        "results": paginate(results, 0, limit),
+        "total_before_limit": total,
+        "query": query
     }))
 }
 
@@ -208,12 +353,12 @@ pub async fn search(client: &LogseqClient, params: Value) -> Result<Value> {
 /// # Returns
 ///
 /// Raw query results as returned by Datascript.
-pub async fn query(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn query(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let q = params["query"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("query parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("query parameter is required".to_string()))?;
 
-    let results = client.datascript_query(q).await?;
+    let results = client.datascript_query(q, Vec::new()).await?;
     Ok(serde_json::json!({ "results": results }))
 }
 
@@ -226,14 +371,14 @@ pub async fn query(client: &LogseqClient, params: Value) -> Result<Value> {
 ///
 /// JSON object with `page` metadata and `blocks` content tree for today's journal.
 /// Returns an error if the journal page doesn't exist yet (not yet created in Logseq).
-pub async fn get_today_journal(client: &LogseqClient, _params: Value) -> Result<Value> {
+pub async fn get_today_journal(client: &LogseqClient, _params: Value) -> McpResult<Value> {
     let formatter = client.get_date_formatter().await?;
 
     // Logseq uses Java-style date format tokens. Map common ones to chrono equivalents.
     let fmt_str = formatter.as_str().unwrap_or("MMM do, yyyy");
 
     // Format the date and lowercase it (Logseq stores journal pages in lowercase)
-    let page_name = format_journal_date(fmt_str).to_lowercase();
+    let page_name = format_journal_date(Local::now().date_naive(), fmt_str).to_lowercase();
 
     let page_info = client.get_page(&page_name).await?;
     let blocks = client.get_page_blocks_tree(&page_name).await?;
@@ -257,15 +402,41 @@ pub async fn get_today_journal(client: &LogseqClient, _params: Value) -> Result<
 /// # Returns
 ///
 /// Array of `[page, [blocks]]` pairs where each block references the given page.
-pub async fn get_page_references(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn get_page_references(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let page_name = params["page_name"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("page_name parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
 
-    let refs = client.get_page_linked_references(page_name).await?;
+    let refs = client.get_linked_references(page_name).await?;
     Ok(serde_json::json!({ "references": refs }))
 }
 
+/// Gets the linked references (backlinks) of a page, tagged with the page name.
+///
+/// Same data as `get_page_references`, but the response echoes the page so
+/// results from several calls can be told apart.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to find references for
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `page`: The requested page name
+/// - `references`: Array of `[page, [blocks]]` pairs; empty if nothing links here
+pub async fn get_linked_references(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let refs = client.get_linked_references(page_name).await?;
+    Ok(serde_json::json!({
+        "page": page_name,
+        "references": refs
+    }))
+}
+
 /// Gets all properties on a block.
 ///
 /// Retrieves the Logseq property key-value pairs attached to a block,
@@ -278,130 +449,5354 @@ pub async fn get_page_references(client: &LogseqClient, params: Value) -> Result
 /// # Returns
 ///
 /// JSON object mapping property names to their values.
-pub async fn get_block_properties(client: &LogseqClient, params: Value) -> Result<Value> {
+pub async fn get_block_properties(client: &LogseqClient, params: Value) -> McpResult<Value> {
     let uuid = params["uuid"]
         .as_str()
-        .ok_or_else(|| anyhow::anyhow!("uuid parameter is required"))?;
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
 
     let props = client.get_block_properties(uuid).await?;
     Ok(serde_json::json!({ "properties": props }))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Gets all scheduled and deadline items for a Monday–Sunday week.
+///
+/// A weekly planning view: collects every block with a `SCHEDULED:` or
+/// `DEADLINE:` date that falls within the requested week.
+///
+/// # Parameters
+///
+/// - `week_offset` (optional): Weeks relative to the current one
+///   (0 = this week, 1 = next week, -1 = last week). Defaults to 0.
+///
+/// # Returns
+///
+/// JSON object with the `week_start`/`week_end` dates and an `items` array
+/// of `{date, uuid, content, type, page}` entries ordered by day, where
+/// `type` is either `"scheduled"` or `"deadline"`.
+pub async fn get_all_scheduled_items_for_week(
+    client: &LogseqClient,
+    params: Value,
+) -> McpResult<Value> {
+    let offset = params["week_offset"].as_i64().unwrap_or(0);
+    let (week_start, week_end) = compute_week_bounds(offset)?;
 
-    #[test]
-    fn test_list_pages_filter_applies() {
-        // format_journal_date is pure — test it directly as a proxy for filtering logic
-        let result = format_journal_date("yyyy-MM-dd");
-        // Should be a date string like 2026-04-09
-        assert!(
-            result.len() == 10,
-            "Expected ISO date string, got: {}",
-            result
-        );
-        assert!(result.contains('-'));
+    let results = client.get_scheduled_blocks(week_start, week_end).await?;
+    let mut items = scheduled_items(&results, week_start, week_end);
+    items.sort_by(|a, b| a["date"].as_str().cmp(&b["date"].as_str()));
+
+    Ok(serde_json::json!({
+        "week_start": week_start.to_string(),
+        "week_end": week_end.to_string(),
+        "items": items
+    }))
+}
+
+/// Computes the Monday and Sunday bounding the week `offset` weeks from now.
+///
+/// # Errors
+///
+/// Returns `InvalidParams` if the week is outside the supported date range.
+fn compute_week_bounds(offset: i64) -> McpResult<(NaiveDate, NaiveDate)> {
+    let today = Local::now().date_naive();
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    Duration::try_weeks(offset)
+        .and_then(|weeks| this_monday.checked_add_signed(weeks))
+        .and_then(|monday| Some((monday, monday.checked_add_signed(Duration::days(6))?)))
+        .ok_or_else(|| McpError::InvalidParams(format!("week_offset {} is out of range", offset)))
+}
+
+/// Parses a Logseq `yyyyMMdd` day integer (e.g. `20240115`) into a date.
+fn parse_logseq_day(day: i64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(
+        (day / 10000) as i32,
+        ((day / 100) % 100) as u32,
+        (day % 100) as u32,
+    )
+}
+
+/// Flattens Datascript scheduled-block results into `{date, uuid, content, type, page}`
+/// items. A block with both a scheduled and a deadline date in range yields two items.
+fn scheduled_items(results: &Value, start: NaiveDate, end: NaiveDate) -> Vec<Value> {
+    let mut items = Vec::new();
+    for block in pulled_blocks(results) {
+        for kind in ["scheduled", "deadline"] {
+            let Some(date) = block[kind].as_i64().and_then(parse_logseq_day) else {
+                continue;
+            };
+            if date < start || date > end {
+                continue;
+            }
+            items.push(serde_json::json!({
+                "date": date.to_string(),
+                "uuid": block["uuid"],
+                "content": block["content"],
+                "type": kind,
+                "page": block["page"]["originalName"]
+            }));
+        }
     }
+    items
+}
 
-    #[test]
-    fn test_format_journal_date_iso() {
-        let result = format_journal_date("yyyy-MM-dd");
-        // Should match YYYY-MM-DD pattern
-        let parts: Vec<&str> = result.split('-').collect();
-        assert_eq!(parts.len(), 3, "Expected 3 date parts");
-        assert_eq!(parts[0].len(), 4, "Expected 4-digit year");
+/// Unwraps Datascript `:find (pull ...)` results, whose rows are single-element arrays.
+fn pulled_blocks(results: &Value) -> Vec<&Value> {
+    results
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|row| row.get(0).unwrap_or(row))
+        .collect()
+}
+
+/// Exports a page's flashcard blocks in a shape suitable for Anki import.
+///
+/// A block is treated as a card if it carries the card property (or the
+/// matching `#tag` in its content). The block's own content becomes the
+/// front of the card and its first child block becomes the back.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page holding the flashcard deck
+/// - `card_property` (optional): Property marking card blocks, defaults to `"card"`
+///
+/// # Returns
+///
+/// JSON object with `deck_name`, a `cards` array of `{front, back, uuid, tags}`
+/// and an `anki_tsv` string (one `front<TAB>back<TAB>tags` line per card)
+/// that Anki's "Import File" dialog accepts directly.
+pub async fn get_page_as_anki_cards(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let card_property = params["card_property"].as_str().unwrap_or("card");
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let mut all_blocks = Vec::new();
+    flatten_blocks(&blocks, &mut all_blocks);
+
+    let cards: Vec<Value> = all_blocks
+        .into_iter()
+        .filter(|block| is_card_block(block, card_property))
+        .map(|block| {
+            let front = card_text(block["content"].as_str().unwrap_or(""), card_property);
+            let back = block["children"]
+                .get(0)
+                .and_then(|child| child["content"].as_str())
+                .map(|content| card_text(content, card_property))
+                .unwrap_or_default();
+            serde_json::json!({
+                "front": front,
+                "back": back,
+                "uuid": block["uuid"],
+                "tags": property_list(&block["properties"]["tags"])
+            })
+        })
+        .collect();
+
+    let anki_tsv = cards
+        .iter()
+        .map(|card| {
+            let tags: Vec<&str> = card["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|t| t.as_str())
+                .collect();
+            format!(
+                "{}\t{}\t{}",
+                tsv_field(card["front"].as_str().unwrap_or("")),
+                tsv_field(card["back"].as_str().unwrap_or("")),
+                tags.join(" ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(serde_json::json!({
+        "deck_name": page_name,
+        "cards": cards,
+        "anki_tsv": anki_tsv
+    }))
+}
+
+/// Reads a Logseq property that may be either an array or a comma-separated string.
+fn property_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect(),
+        Value::String(s) => s
+            .split(',')
+            .map(|t| t.trim().trim_start_matches("[[").trim_end_matches("]]"))
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Checks whether a block is marked as a flashcard via property or `#tag`.
+fn is_card_block(block: &Value, card_property: &str) -> bool {
+    if !block["properties"][card_property].is_null() {
+        return true;
     }
+    let tag = format!("#{}", card_property);
+    block["content"]
+        .as_str()
+        .map(|c| {
+            c.split_whitespace()
+                .any(|word| word.eq_ignore_ascii_case(&tag))
+        })
+        .unwrap_or(false)
+}
 
-    #[test]
-    fn test_format_journal_date_month_name() {
-        let result = format_journal_date("MMM do, yyyy");
-        // Should contain comma, space, and ordinal suffix (e.g. "Apr 9th, 2026")
-        assert!(result.contains(','));
-        // Should contain an ordinal suffix (st, nd, rd, or th)
-        assert!(
-            result.contains("st,") || result.contains("nd,") ||
-            result.contains("rd,") || result.contains("th,"),
-            "Expected ordinal suffix in: {}", result
-        );
-    }
-
-    #[test]
-    fn test_get_ordinal_suffix() {
-        assert_eq!(get_ordinal_suffix(1), "st");
-        assert_eq!(get_ordinal_suffix(2), "nd");
-        assert_eq!(get_ordinal_suffix(3), "rd");
-        assert_eq!(get_ordinal_suffix(4), "th");
-        assert_eq!(get_ordinal_suffix(11), "th"); // Special case
-        assert_eq!(get_ordinal_suffix(12), "th");
-        assert_eq!(get_ordinal_suffix(13), "th");
-        assert_eq!(get_ordinal_suffix(21), "st");
-        assert_eq!(get_ordinal_suffix(22), "nd");
-        assert_eq!(get_ordinal_suffix(23), "rd");
-        assert_eq!(get_ordinal_suffix(31), "st");
-    }
-}
-
-/// Public alias for use in mutate.rs (append_to_journal).
-pub fn format_journal_date_pub(logseq_fmt: &str) -> String {
-    format_journal_date(logseq_fmt)
-}
-
-/// Maps a subset of Logseq/Java date format tokens to chrono format strings,
-/// then formats today's date. Falls back to ISO date on unknown format tokens.
-fn format_journal_date(logseq_fmt: &str) -> String {
-    let now = Local::now();
-
-    // Handle ordinal day format "do" specially (1st, 2nd, 3rd, etc.)
-    let result = if logseq_fmt.contains("do") {
-        let day = now.day();
-        let ordinal = get_ordinal_suffix(day);
-        let day_with_ordinal = format!("{}{}", day, ordinal);
-
-        // First replace "do" with a placeholder, then do other replacements
-        let temp_fmt = logseq_fmt.replace("do", "<<DAY_ORDINAL>>");
-        let chrono_fmt = temp_fmt
-            .replace("yyyy", "%Y")
-            .replace("yy", "%y")
-            .replace("MMMM", "%B")
-            .replace("MMM", "%b")
-            .replace("MM", "%m")
-            .replace("dd", "%d")
-            .replace("EEE", "%a")
-            .replace("EEEE", "%A");
-
-        let formatted = now.format(&chrono_fmt).to_string();
-        formatted.replace("<<DAY_ORDINAL>>", &day_with_ordinal)
+/// Strips `key:: value` property lines and the card tag from block content.
+fn card_text(content: &str, card_property: &str) -> String {
+    let tag = format!("#{}", card_property);
+    content
+        .lines()
+        .filter(|line| !line.contains(":: "))
+        .map(|line| {
+            line.split_whitespace()
+                .filter(|word| !word.eq_ignore_ascii_case(&tag))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Makes a field safe for a tab-separated line, using `<br>` for newlines as Anki expects.
+fn tsv_field(text: &str) -> String {
+    text.replace('\t', " ").replace('\n', "<br>")
+}
+
+/// Computes a content hash of a page for cheap change detection.
+///
+/// Clients can poll this and compare hashes instead of re-fetching the
+/// full block tree. Blocks are sorted by UUID before hashing so the result
+/// only changes when block content changes.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to hash
+///
+/// # Returns
+///
+/// JSON object with `page_name`, hex-encoded SHA-256 `hash`, `block_count`
+/// and `computed_at` (RFC 3339 timestamp).
+pub async fn get_page_content_hash(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    page_content_hash(client, page_name).await
+}
+
+/// Computes content hashes for several pages in one call.
+///
+/// # Parameters
+///
+/// - `page_names` (required): Array of page names to hash
+///
+/// # Returns
+///
+/// JSON object with a `hashes` array in the same shape as
+/// `get_page_content_hash`. Pages that fail to load are reported with an
+/// `error` field instead of failing the whole call.
+pub async fn get_multiple_page_hashes(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_names = params["page_names"]
+        .as_array()
+        .ok_or_else(|| McpError::InvalidParams("page_names parameter is required".to_string()))?;
+
+    let mut hashes = Vec::with_capacity(page_names.len());
+    for name in page_names.iter().filter_map(|n| n.as_str()) {
+        match page_content_hash(client, name).await {
+            Ok(hash) => hashes.push(hash),
+            Err(e) => hashes.push(serde_json::json!({
+                "page_name": name,
+                "error": e.to_string()
+            })),
+        }
+    }
+
+    Ok(serde_json::json!({
+        "hashes": hashes,
+        "count": hashes.len()
+    }))
+}
+
+/// Fetches a page's block tree and builds the hash response for it.
+async fn page_content_hash(client: &LogseqClient, page_name: &str) -> McpResult<Value> {
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let (hash, block_count) = hash_block_tree(&blocks);
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "hash": hash,
+        "block_count": block_count,
+        "computed_at": Utc::now().to_rfc3339()
+    }))
+}
+
+/// Hashes the content of every block in a tree, canonicalized by UUID order.
+fn hash_block_tree(blocks: &Value) -> (String, usize) {
+    let mut all_blocks = Vec::new();
+    flatten_blocks(blocks, &mut all_blocks);
+    all_blocks.sort_by(|a, b| a["uuid"].as_str().cmp(&b["uuid"].as_str()));
+
+    let mut hasher = Sha256::new();
+    for block in &all_blocks {
+        hasher.update(block["content"].as_str().unwrap_or("").as_bytes());
+        hasher.update(b"\n");
+    }
+    let hash = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    (hash, all_blocks.len())
+}
+
+/// Gets every page that contains tasks, with its tasks, for a global overview.
+///
+/// This is the cross-page task dashboard used in weekly review workflows.
+/// Pages are ordered by how many matching tasks they contain.
+///
+/// # Parameters
+///
+/// - `states` (optional): Array of task markers to include (e.g. `["TODO", "DOING"]`).
+///   Defaults to all task states.
+/// - `limit` (optional): Maximum number of pages to return (default: 20)
+///
+/// # Returns
+///
+/// JSON object with a `pages` array of `{name, tasks: [{uuid, content, state}]}`
+/// and `total_tasks`, the number of matching tasks across all pages.
+pub async fn get_all_pages_with_tasks(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let states: Option<Vec<String>> = params["states"].as_array().map(|arr| {
+        arr.iter()
+            .filter_map(|s| s.as_str())
+            .map(|s| s.to_uppercase())
+            .collect()
+    });
+    let limit = params["limit"].as_u64().unwrap_or(20) as usize;
+
+    let results = client
+        .datascript_query(
+            "[:find (pull ?b [:block/uuid :block/content :block/marker \
+             {:block/page [:block/original-name]}]) \
+             :where [?b :block/marker ?m]]",
+            Vec::new(),
+        )
+        .await?;
+
+    let (pages, total_tasks) = tasks_by_page(&results, states.as_deref(), limit);
+
+    Ok(serde_json::json!({
+        "pages": pages
+            .into_iter()
+            .map(|(name, tasks)| serde_json::json!({ "name": name, "tasks": tasks }))
+            .collect::<Vec<_>>(),
+        "total_tasks": total_tasks
+    }))
+}
+
+/// Groups pulled task blocks by page, keeping only markers in `states` (all
+/// markers when `None`).
+///
+/// Returns up to `limit` pages with the most tasks first (ties by name), and
+/// the number of matching tasks across all pages.
+fn tasks_by_page(
+    results: &Value,
+    states: Option<&[String]>,
+    limit: usize,
+) -> (Vec<(String, Vec<Value>)>, usize) {
+    let mut pages: Vec<(String, Vec<Value>)> = Vec::new();
+    let mut total_tasks = 0;
+    for block in pulled_blocks(results) {
+        let Some(state) = block["marker"].as_str() else {
+            continue;
+        };
+        if states.is_some_and(|s| !s.iter().any(|wanted| wanted == state)) {
+            continue;
+        }
+        let page = block["page"]["originalName"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+        let task = serde_json::json!({
+            "uuid": block["uuid"],
+            "content": block["content"],
+            "state": state
+        });
+        match pages.iter_mut().find(|(name, _)| *name == page) {
+            Some((_, tasks)) => tasks.push(task),
+            None => pages.push((page, vec![task])),
+        }
+        total_tasks += 1;
+    }
+
+    pages.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+    pages.truncate(limit);
+    (pages, total_tasks)
+}
+
+/// Finds blocks by their content when the UUID is not known.
+///
+/// # Parameters
+///
+/// - `content` (required): The block content to look for
+/// - `exact` (optional): Match the whole content exactly (default: true);
+///   when false, matches blocks containing `content` as a substring
+/// - `page_name` (optional): Restrict the lookup to a single page
+///
+/// # Returns
+///
+/// JSON object with the matching `blocks` (`{uuid, content, page}`), their
+/// `count`, and `first_match_uuid` for convenience (null when nothing matched).
+pub async fn get_block_by_content(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let content = params["content"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("content parameter is required".to_string()))?;
+    let exact = params["exact"].as_bool().unwrap_or(true);
+    let page_name = params["page_name"].as_str();
+
+    let query = block_content_query(content, exact, page_name);
+    let results = client.datascript_query(&query, Vec::new()).await?;
+
+    let blocks: Vec<Value> = pulled_blocks(&results)
+        .into_iter()
+        .map(|block| {
+            serde_json::json!({
+                "uuid": block["uuid"],
+                "content": block["content"],
+                "page": block["page"]["originalName"]
+            })
+        })
+        .collect();
+    let first_match_uuid = blocks.first().map(|b| b["uuid"].clone());
+
+    Ok(serde_json::json!({
+        "blocks": blocks,
+        "count": blocks.len(),
+        "first_match_uuid": first_match_uuid
+    }))
+}
+
+/// Builds the Datalog query used by `get_block_by_content`.
+fn block_content_query(content: &str, exact: bool, page_name: Option<&str>) -> String {
+    let predicate = if exact {
+        format!("[(= ?c {})]", datalog_string(content))
     } else {
-        // Standard format without ordinals
-        let chrono_fmt = logseq_fmt
-            .replace("yyyy", "%Y")
-            .replace("yy", "%y")
-            .replace("MMMM", "%B")
-            .replace("MMM", "%b")
-            .replace("MM", "%m")
-            .replace("dd", "%d")
-            .replace("EEE", "%a")
-            .replace("EEEE", "%A");
+        format!(
+            "[(clojure.string/includes? ?c {})]",
+            datalog_string(content)
+        )
+    };
+    let page_clause = page_name
+        .map(|name| {
+            format!(
+                " [?b :block/page ?p] [?p :block/name {}]",
+                datalog_string(&name.to_lowercase())
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        "[:find (pull ?b [:block/uuid :block/content {{:block/page [:block/original-name]}}]) \
+         :where [?b :block/content ?c] {predicate}{page_clause}]"
+    )
+}
+
+/// Quotes a string for safe embedding in a Datalog query.
+fn datalog_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Finds the journal entries that led to a page being created.
+///
+/// Looks up the page's creation time, then collects blocks on journal pages
+/// from that day (±1 day) that reference the page. This answers "why did I
+/// create this page?" for rediscovering forgotten content.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to investigate
+///
+/// # Returns
+///
+/// JSON object with `page_name`, `created_at` (RFC 3339, null if Logseq has
+/// no creation time for the page) and a `creation_context` array of
+/// `{journal_date, block_uuid, block_content}`.
+///
+/// # Errors
+///
+/// Returns an error if the page does not exist.
+pub async fn get_page_creation_context(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let page = client.get_page(page_name).await?;
+    if page.is_null() {
+        return Err(McpError::Internal(format!(
+            "Page '{}' not found",
+            page_name
+        )));
+    }
+
+    let Some(created_at) = page["createdAt"]
+        .as_i64()
+        .and_then(DateTime::from_timestamp_millis)
+    else {
+        return Ok(serde_json::json!({
+            "page_name": page_name,
+            "created_at": null,
+            "creation_context": []
+        }));
+    };
+
+    let created_day = created_at.with_timezone(&Local).date_naive();
+    let from = (created_day - Duration::days(1)).format("%Y%m%d");
+    let to = (created_day + Duration::days(1)).format("%Y%m%d");
+    let query = format!(
+        "[:find (pull ?b [:block/uuid :block/content {{:block/page [:block/journal-day]}}]) \
+         :where [?target :block/name {}] [?b :block/refs ?target] \
+         [?b :block/page ?j] [?j :block/journal-day ?d] [(>= ?d {from})] [(<= ?d {to})]]",
+        datalog_string(&page_name.to_lowercase())
+    );
+    let results = client.datascript_query(&query, Vec::new()).await?;
+
+    let mut context: Vec<Value> = pulled_blocks(&results)
+        .into_iter()
+        .map(|block| {
+            let journal_date = block["page"]["journalDay"]
+                .as_i64()
+                .and_then(parse_logseq_day)
+                .map(|d| d.to_string());
+            serde_json::json!({
+                "journal_date": journal_date,
+                "block_uuid": block["uuid"],
+                "block_content": block["content"]
+            })
+        })
+        .collect();
+    context.sort_by(|a, b| a["journal_date"].as_str().cmp(&b["journal_date"].as_str()));
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "created_at": created_at.to_rfc3339(),
+        "creation_context": context
+    }))
+}
+
+/// Finds the days with the most activity in the graph.
+///
+/// Groups page or block timestamps by calendar day (local time) to surface
+/// writing-habit patterns.
+///
+/// # Parameters
+///
+/// - `limit` (optional): Number of days to return (default: 10)
+/// - `metric` (optional): One of `"pages_created"`, `"blocks_created"` or
+///   `"blocks_modified"` (default)
+///
+/// # Returns
+///
+/// JSON object with `most_active_days` (`[{date, count}]`, busiest first),
+/// the `metric` used and `total_days_with_activity`.
+pub async fn get_graph_most_active_days(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let limit = params["limit"].as_u64().unwrap_or(10) as usize;
+    let metric = params["metric"].as_str().unwrap_or("blocks_modified");
 
-        now.format(&chrono_fmt).to_string()
+    let where_clause = match metric {
+        "pages_created" => "[?e :block/name] [?e :block/created-at ?t]",
+        "blocks_created" => "[?e :block/content] [?e :block/created-at ?t]",
+        "blocks_modified" => "[?e :block/content] [?e :block/updated-at ?t]",
+        other => {
+            return Err(McpError::InvalidParams(format!(
+                "Invalid metric '{}': expected pages_created, blocks_created or blocks_modified",
+                other
+            )));
+        }
     };
+    // Find the entity id too, otherwise identical timestamps collapse in the result set
+    let query = format!("[:find ?e ?t :where {where_clause}]");
+    let results = client.datascript_query(&query, Vec::new()).await?;
+
+    let timestamps = results
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| row.get(1).and_then(|t| t.as_i64()));
+    let days = count_by_day(timestamps);
+    let total_days = days.len();
 
-    result
+    Ok(serde_json::json!({
+        "most_active_days": days
+            .into_iter()
+            .take(limit)
+            .map(|(date, count)| serde_json::json!({ "date": date.to_string(), "count": count }))
+            .collect::<Vec<_>>(),
+        "metric": metric,
+        "total_days_with_activity": total_days
+    }))
 }
 
-/// Returns the ordinal suffix for a day number (st, nd, rd, th).
-fn get_ordinal_suffix(day: u32) -> &'static str {
-    // Special cases: 11th, 12th, 13th (not 11st, 12nd, 13rd)
-    if (11..=13).contains(&day) {
-        return "th";
+/// Buckets millisecond timestamps by local calendar day, busiest day first.
+fn count_by_day(timestamps: impl Iterator<Item = i64>) -> Vec<(NaiveDate, usize)> {
+    let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+    for ts in timestamps {
+        if let Some(dt) = DateTime::from_timestamp_millis(ts) {
+            *counts
+                .entry(dt.with_timezone(&Local).date_naive())
+                .or_default() += 1;
+        }
     }
+    let mut days: Vec<(NaiveDate, usize)> = counts.into_iter().collect();
+    days.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    days
+}
+
+/// Gets the icon shown next to a page.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page whose icon to read
+///
+/// # Returns
+///
+/// JSON object with `page_name` and `icon` (null if the page has no icon).
+pub async fn get_page_icon(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let icon = client.get_page_icon(page_name).await?;
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "icon": icon
+    }))
+}
+
+/// Lists pages updated after a point in time, with cursor-based pagination.
+///
+/// Intended for incremental sync: call with the last sync time, then keep
+/// passing `next_cursor` back as `cursor` until `has_more` is false.
+///
+/// # Parameters
+///
+/// - `timestamp` (required): Unix time in milliseconds; only pages with a
+///   later `updatedAt` are returned
+/// - `limit` (optional): Maximum pages per call (default: 50)
+/// - `cursor` (optional): The `next_cursor` value from the previous call
+///
+/// # Returns
+///
+/// JSON object with `pages` (oldest update first, ties by UUID), `has_more`,
+/// and `next_cursor` (an opaque `"<updatedAt>:<uuid>"` string naming the last
+/// returned page, or null when done).
+///
+/// # Notes
+///
+/// The cursor holds both the timestamp and the UUID, so pages sharing the
+/// last returned page's `updatedAt` are neither skipped nor repeated on the
+/// next call.
+pub async fn get_pages_updated_since(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let timestamp = params["timestamp"]
+        .as_i64()
+        .ok_or_else(|| McpError::InvalidParams("timestamp parameter is required".to_string()))?;
+    let limit = params["limit"].as_u64().unwrap_or(50) as usize;
+    let cursor = params["cursor"]
+        .as_str()
+        .map(|raw| {
+            parse_update_cursor(raw)
+                .ok_or_else(|| McpError::InvalidParams(format!("Invalid cursor: {}", raw)))
+        })
+        .transpose()?;
+
+    let pages = client.get_all_pages().await?;
+    let (page, has_more) = pages_updated_after(&pages, timestamp, cursor, limit);
+    let next_cursor = if has_more {
+        page.last()
+            .and_then(|p| update_key(p))
+            .map(|(t, uuid)| format!("{}:{}", t, uuid))
+    } else {
+        None
+    };
+
+    Ok(serde_json::json!({
+        "pages": page,
+        "has_more": has_more,
+        "next_cursor": next_cursor
+    }))
+}
+
+/// A page's position in update order: its `updatedAt`, then its UUID.
+fn update_key(page: &Value) -> Option<(i64, &str)> {
+    Some((
+        page["updatedAt"].as_i64()?,
+        page["uuid"].as_str().unwrap_or(""),
+    ))
+}
+
+/// Splits a `"<updatedAt>:<uuid>"` cursor.
+fn parse_update_cursor(cursor: &str) -> Option<(i64, &str)> {
+    let (timestamp, uuid) = cursor.split_once(':')?;
+    Some((timestamp.parse().ok()?, uuid))
+}
+
+/// Returns up to `limit` pages updated after `since` and positioned after
+/// `cursor` in update order, oldest first, and whether more remain.
+fn pages_updated_after<'a>(
+    pages: &'a Value,
+    since: i64,
+    cursor: Option<(i64, &str)>,
+    limit: usize,
+) -> (Vec<&'a Value>, bool) {
+    let mut updated: Vec<&Value> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|p| {
+            update_key(p).is_some_and(|key| key.0 > since && cursor.is_none_or(|c| key > c))
+        })
+        .collect();
+    updated.sort_by_key(|p| update_key(p));
+    let has_more = updated.len() > limit;
+    updated.truncate(limit);
+    (updated, has_more)
+}
+
+/// Scores how structurally complex a page is, to spot pages that need restructuring.
+///
+/// The score (0-100) is the sum of capped factors derived from the block tree:
+///
+/// | Factor         | Contribution                              | Cap |
+/// |----------------|-------------------------------------------|-----|
+/// | `depth`        | 6 per nesting level                       | 30  |
+/// | `block_count`  | 1 per 2 blocks                            | 25  |
+/// | `links`        | 1 per internal `[[link]]` or external URL | 20  |
+/// | `tasks`        | 2 per task block                          | 15  |
+/// | `rich_content` | 5 for code blocks, 5 for tables           | 10  |
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to analyze
+///
+/// # Returns
+///
+/// JSON object with `page_name`, the total `score` and the per-factor `factors`.
+pub async fn get_page_complexity_score(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let factors = complexity_factors(&blocks);
+    let score: u64 = factors.values().filter_map(|v| v.as_u64()).sum();
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "score": score,
+        "factors": factors
+    }))
+}
+
+/// Computes the capped complexity factors for a block tree.
+fn complexity_factors(blocks: &Value) -> serde_json::Map<String, Value> {
+    let mut all_blocks = Vec::new();
+    flatten_blocks(blocks, &mut all_blocks);
+
+    let mut links = 0;
+    let mut tasks = 0;
+    let mut has_code = false;
+    let mut has_table = false;
+    for block in &all_blocks {
+        let content = block["content"].as_str().unwrap_or("");
+        links += content.matches("[[").count()
+            + content.matches("http://").count()
+            + content.matches("https://").count();
+        if !block["marker"].is_null() {
+            tasks += 1;
+        }
+        has_code |= content.contains("```");
+        has_table |= content.lines().any(|l| l.trim_start().starts_with('|'));
+    }
+
+    let mut factors = serde_json::Map::new();
+    factors.insert("depth".into(), (tree_depth(blocks) * 6).min(30).into());
+    factors.insert("block_count".into(), (all_blocks.len() / 2).min(25).into());
+    factors.insert("links".into(), links.min(20).into());
+    factors.insert("tasks".into(), (tasks * 2).min(15).into());
+    factors.insert(
+        "rich_content".into(),
+        (usize::from(has_code) * 5 + usize::from(has_table) * 5).into(),
+    );
+    factors
+}
+
+/// Returns the maximum nesting depth of a block tree (top-level blocks are depth 1).
+fn tree_depth(blocks: &Value) -> usize {
+    blocks
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|b| 1 + tree_depth(&b["children"]))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Tracks how a page property changed over time using the graph's git history.
+///
+/// Logseq can auto-commit the graph folder to git. This walks `git log -p`
+/// for the page's file and extracts before/after values of `key:: value` lines.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to inspect
+/// - `property_key` (required): The property whose history to extract
+///
+/// # Returns
+///
+/// JSON object with `page_name`, `property` and `history`, an array of
+/// `{commit, date, from_value, to_value}` ordered newest first. `from_value`
+/// is null when the property was added and `to_value` is null when removed.
+/// The history is empty (not an error) if git is unavailable or the graph
+/// is not a git repository.
+pub async fn get_page_property_history(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let property_key = params["property_key"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("property_key parameter is required".to_string()))?;
+
+    let graph = client.get_current_graph().await?;
+    let file_path = page_file_path(client, page_name).await?;
+
+    let history = match (graph["path"].as_str(), file_path) {
+        (Some(graph_path), Some(file_path)) => git_output(
+            graph_path,
+            &["log", "-p", "--format=%x1e%H %aI", "--", &file_path],
+        )
+        .await
+        .map(|log| parse_property_history(&log, property_key))
+        .unwrap_or_else(|e| {
+            tracing::debug!("No git history for '{}': {}", page_name, e);
+            Vec::new()
+        }),
+        _ => Vec::new(),
+    };
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "property": property_key,
+        "history": history
+    }))
+}
+
+/// Looks up the path of the file backing a page, if the page has one.
+async fn page_file_path(client: &LogseqClient, page_name: &str) -> McpResult<Option<String>> {
+    let query = format!(
+        "[:find ?path :where [?p :block/name {}] [?p :block/file ?f] [?f :file/path ?path]]",
+        datalog_string(&page_name.to_lowercase())
+    );
+    let results = client.datascript_query(&query, Vec::new()).await?;
+    Ok(results
+        .get(0)
+        .and_then(|row| row.get(0))
+        .and_then(|p| p.as_str())
+        .map(str::to_string))
+}
+
+/// Runs git in `dir` and returns stdout.
+///
+/// # Errors
+///
+/// Returns an error if git is not installed or the command exits unsuccessfully.
+async fn git_output(dir: &str, args: &[&str]) -> McpResult<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| McpError::Internal(format!("git is not available: {}", e)))?;
+    if !output.status.success() {
+        return Err(McpError::Internal(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Extracts property value changes from `git log -p --format=%x1e%H %aI` output.
+fn parse_property_history(log: &str, key: &str) -> Vec<Value> {
+    let prefix = format!("{}::", key);
+    let property_value = |line: &str| {
+        line.trim()
+            .strip_prefix(&prefix)
+            .map(|v| v.trim().to_string())
+    };
+
+    let mut history = Vec::new();
+    // Each commit starts with the record separator emitted by the format string
+    for entry in log.split('\x1e').filter(|e| !e.trim().is_empty()) {
+        let mut lines = entry.lines();
+        let header = lines.next().unwrap_or("");
+        let (commit, date) = header.split_once(' ').unwrap_or((header, ""));
+
+        let mut from_value = None;
+        let mut to_value = None;
+        for line in lines {
+            if line.starts_with("---") || line.starts_with("+++") {
+                continue;
+            }
+            if let Some(removed) = line.strip_prefix('-') {
+                from_value = from_value.or_else(|| property_value(removed));
+            } else if let Some(added) = line.strip_prefix('+') {
+                to_value = to_value.or_else(|| property_value(added));
+            }
+        }
+
+        if (from_value.is_some() || to_value.is_some()) && from_value != to_value {
+            history.push(serde_json::json!({
+                "commit": commit,
+                "date": date,
+                "from_value": from_value,
+                "to_value": to_value
+            }));
+        }
+    }
+    history
+}
+
+/// Computes writing-style statistics for the blocks on a page.
+///
+/// Sentences are split on `.`, `!` and `?`; words are runs of alphanumeric
+/// characters. Property lines (`key:: value`) are ignored and common English
+/// stop words are excluded from `common_words`.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to analyze
+///
+/// # Returns
+///
+/// JSON object with `blocks_analyzed`, `avg_words_per_block`,
+/// `avg_sentence_length` (words per sentence), `longest_block_words` and the
+/// ten most `common_words` as `[{word, count}]`.
+pub async fn get_block_content_statistics(
+    client: &LogseqClient,
+    params: Value,
+) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let mut stats = content_statistics(&blocks);
+    stats.insert("page_name".into(), page_name.into());
+    Ok(Value::Object(stats))
+}
+
+/// Common English words excluded from word frequency counts.
+const STOP_WORDS: [&str; 50] = [
+    "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on",
+    "with", "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we", "say",
+    "her", "she", "or", "an", "will", "my", "one", "all", "would", "there", "their", "what", "so",
+    "up", "out", "if", "about", "who", "get", "which", "go", "me",
+];
+
+/// Computes sentence and word statistics over every block in a tree.
+fn content_statistics(blocks: &Value) -> serde_json::Map<String, Value> {
+    let mut all_blocks = Vec::new();
+    flatten_blocks(blocks, &mut all_blocks);
+
+    let mut blocks_analyzed = 0usize;
+    let mut total_words = 0usize;
+    let mut total_sentences = 0usize;
+    let mut longest_block_words = 0usize;
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+
+    for block in all_blocks {
+        let text: String = block["content"]
+            .as_str()
+            .unwrap_or("")
+            .lines()
+            .filter(|line| !line.contains(":: "))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+        if words.is_empty() {
+            continue;
+        }
+
+        blocks_analyzed += 1;
+        total_words += words.len();
+        longest_block_words = longest_block_words.max(words.len());
+        total_sentences += text
+            .split(['.', '!', '?'])
+            .filter(|s| s.chars().any(char::is_alphanumeric))
+            .count();
+        for word in words {
+            if !STOP_WORDS.contains(&word.as_str()) {
+                *frequencies.entry(word).or_default() += 1;
+            }
+        }
+    }
+
+    let mut common_words: Vec<(String, usize)> = frequencies.into_iter().collect();
+    common_words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    common_words.truncate(10);
+
+    let ratio = |num: usize, den: usize| {
+        if den == 0 {
+            0.0
+        } else {
+            num as f64 / den as f64
+        }
+    };
+
+    let mut stats = serde_json::Map::new();
+    stats.insert("blocks_analyzed".into(), blocks_analyzed.into());
+    stats.insert(
+        "avg_words_per_block".into(),
+        ratio(total_words, blocks_analyzed).into(),
+    );
+    stats.insert(
+        "avg_sentence_length".into(),
+        ratio(total_words, total_sentences).into(),
+    );
+    stats.insert("longest_block_words".into(), longest_block_words.into());
+    stats.insert(
+        "common_words".into(),
+        common_words
+            .into_iter()
+            .map(|(word, count)| serde_json::json!({ "word": word, "count": count }))
+            .collect(),
+    );
+    stats
+}
+
+/// Lists the UUIDs of all blocks on a page, for preparing bulk operations.
+///
+/// A lightweight alternative to fetching the full block tree when only
+/// block identifiers are needed.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to read
+/// - `depth_limit` (optional): Maximum nesting depth to descend
+///   (1 = top-level blocks only). Unlimited by default.
+///
+/// # Returns
+///
+/// JSON object with `page_name`, `uuids` in document (depth-first) order,
+/// their `count`, and `top_level_uuids` for the page's root blocks.
+pub async fn get_all_block_uuids_on_page(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let depth_limit = params["depth_limit"]
+        .as_u64()
+        .map_or(usize::MAX, |d| d as usize);
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let mut uuids = Vec::new();
+    collect_uuids(&blocks, 1, depth_limit, &mut uuids);
+    let top_level_uuids: Vec<&Value> = blocks
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|b| &b["uuid"])
+        .collect();
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "uuids": uuids,
+        "count": uuids.len(),
+        "top_level_uuids": top_level_uuids
+    }))
+}
+
+/// Collects block UUIDs depth-first, stopping below `depth_limit`.
+fn collect_uuids<'a>(
+    blocks: &'a Value,
+    depth: usize,
+    depth_limit: usize,
+    out: &mut Vec<&'a Value>,
+) {
+    if depth > depth_limit {
+        return;
+    }
+    for block in blocks.as_array().into_iter().flatten() {
+        out.push(&block["uuid"]);
+        collect_uuids(&block["children"], depth + 1, depth_limit, out);
+    }
+}
+
+/// Shows how many tasks were completed versus created over time.
+///
+/// Completed tasks are `DONE` blocks bucketed by their last update time;
+/// created tasks are any task blocks bucketed by creation time. Comparing
+/// the two per period shows whether the task backlog is growing or shrinking.
+///
+/// # Parameters
+///
+/// - `days` (optional): How many days back to look (default: 30)
+/// - `granularity` (optional): `"day"` (default) or `"week"`
+///
+/// # Returns
+///
+/// JSON object with `trend`, an array of `{period, completed, created}`
+/// covering every period in the window (oldest first), and `days`. Weekly
+/// periods are labelled by their Monday.
+pub async fn get_task_completion_trend(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let days = params["days"].as_u64().unwrap_or(30);
+    let out_of_range = || McpError::InvalidParams(format!("days {} is out of range", days));
+    let end = Local::now().date_naive();
+    let start = i64::try_from(days.saturating_sub(1))
+        .ok()
+        .and_then(Duration::try_days)
+        .and_then(|span| end.checked_sub_signed(span))
+        .ok_or_else(out_of_range)?;
+    let weekly = match params["granularity"].as_str().unwrap_or("day") {
+        "day" => false,
+        "week" => true,
+        other => {
+            return Err(McpError::InvalidParams(format!(
+                "Invalid granularity '{}': expected day or week",
+                other
+            )));
+        }
+    };
+
+    let done = client
+        .datascript_query(
+            "[:find ?b ?t :where [?b :block/marker \"DONE\"] [?b :block/updated-at ?t]]",
+            Vec::new(),
+        )
+        .await?;
+    let created = client
+        .datascript_query(
+            "[:find ?b ?t :where [?b :block/marker] [?b :block/created-at ?t]]",
+            Vec::new(),
+        )
+        .await?;
+
+    let timestamps = |results: &Value| -> Vec<i64> {
+        results
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|row| row.get(1).and_then(|t| t.as_i64()))
+            .collect()
+    };
+
+    let trend = completion_trend(
+        &timestamps(&done),
+        &timestamps(&created),
+        start,
+        end,
+        weekly,
+    );
+
+    Ok(serde_json::json!({
+        "trend": trend,
+        "days": days
+    }))
+}
+
+/// Buckets completed and created task timestamps into day or week periods
+/// between `start` and `end` inclusive.
+fn completion_trend(
+    done: &[i64],
+    created: &[i64],
+    start: NaiveDate,
+    end: NaiveDate,
+    weekly: bool,
+) -> Vec<Value> {
+    let period_of = |date: NaiveDate| {
+        if weekly {
+            date - Duration::days(date.weekday().num_days_from_monday() as i64)
+        } else {
+            date
+        }
+    };
+
+    // Pre-populate every period so quiet days/weeks show up as zeros
+    let mut periods: Vec<(NaiveDate, usize, usize)> = Vec::new();
+    let mut day = start;
+    while day <= end {
+        let period = period_of(day);
+        if periods.last().is_none_or(|p| p.0 != period) {
+            periods.push((period, 0, 0));
+        }
+        day += Duration::days(1);
+    }
+
+    let mut tally = |timestamps: &[i64], completed: bool| {
+        for &ts in timestamps {
+            let Some(dt) = DateTime::from_timestamp_millis(ts) else {
+                continue;
+            };
+            let date = dt.with_timezone(&Local).date_naive();
+            if date < start || date > end {
+                continue;
+            }
+            let period = period_of(date);
+            if let Some(p) = periods.iter_mut().find(|p| p.0 == period) {
+                if completed {
+                    p.1 += 1;
+                } else {
+                    p.2 += 1;
+                }
+            }
+        }
+    };
+    tally(done, true);
+    tally(created, false);
+
+    periods
+        .into_iter()
+        .map(|(period, completed, created)| {
+            serde_json::json!({
+                "period": period.to_string(),
+                "completed": completed,
+                "created": created
+            })
+        })
+        .collect()
+}
+
+/// Exports the tabular data on a page as CSV.
+///
+/// Markdown table blocks (lines starting with `|`) are parsed into rows.
+/// With `table_only: false`, blocks using the property-per-block pattern
+/// (`key:: value` lines) are also exported as one extra table whose columns
+/// are the union of property names.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to export
+/// - `table_only` (optional): Only export markdown tables (default: true)
+///
+/// # Returns
+///
+/// JSON object with `page_name`, `csv` (tables separated by a blank line,
+/// rows separated by `\n`) and `tables_found`.
+pub async fn get_page_as_csv(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let table_only = params["table_only"].as_bool().unwrap_or(true);
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let mut all_blocks = Vec::new();
+    flatten_blocks(&blocks, &mut all_blocks);
+
+    let mut tables: Vec<Vec<Vec<String>>> = all_blocks
+        .iter()
+        .filter_map(|b| parse_markdown_table(b["content"].as_str().unwrap_or("")))
+        .collect();
+
+    if !table_only {
+        let property_blocks: Vec<&serde_json::Map<String, Value>> = all_blocks
+            .iter()
+            .filter_map(|b| b["properties"].as_object())
+            .filter(|props| !props.is_empty())
+            .collect();
+        if !property_blocks.is_empty() {
+            let mut header: Vec<String> = property_blocks
+                .iter()
+                .flat_map(|props| props.keys().cloned())
+                .collect();
+            header.sort();
+            header.dedup();
+            let mut rows = vec![header.clone()];
+            for props in property_blocks {
+                rows.push(
+                    header
+                        .iter()
+                        .map(|key| match props.get(key) {
+                            Some(Value::String(s)) => s.clone(),
+                            Some(Value::Null) | None => String::new(),
+                            Some(Value::Array(items)) => items
+                                .iter()
+                                .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            Some(other) => other.to_string(),
+                        })
+                        .collect(),
+                );
+            }
+            tables.push(rows);
+        }
+    }
+
+    let csv = tables
+        .iter()
+        .map(|rows| rows_to_csv(rows))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "csv": csv,
+        "tables_found": tables.len()
+    }))
+}
+
+/// Parses a markdown table out of block content, skipping the `|---|` separator row.
+///
+/// Returns `None` if the content contains no table rows.
+fn parse_markdown_table(content: &str) -> Option<Vec<Vec<String>>> {
+    let rows: Vec<Vec<String>> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('|'))
+        .filter(|line| {
+            !line
+                .chars()
+                .all(|c| matches!(c, '|' | '-' | ':' | ' ' | '+'))
+        })
+        .map(|line| {
+            line.trim_matches('|')
+                .split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        })
+        .collect();
+    (!rows.is_empty()).then_some(rows)
+}
+
+/// Renders rows as CSV, quoting fields that contain commas, quotes or newlines.
+fn rows_to_csv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|field| {
+                    if field.contains([',', '"', '\n']) {
+                        format!("\"{}\"", field.replace('"', "\"\""))
+                    } else {
+                        field.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Presents a page as a Kanban board.
+///
+/// Each heading block (`# Title` content or a `heading::` property) becomes a
+/// column holding the task blocks nested beneath it. Pages without headings
+/// are shown as a single `"Backlog"` column containing every task.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to present
+///
+/// # Returns
+///
+/// JSON object with `page_name` and `columns`, an array of
+/// `{name, tasks: [{uuid, content, priority, scheduled}]}`.
+pub async fn get_page_kanban_view(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "columns": kanban_columns(&blocks)
+    }))
+}
+
+/// Groups task blocks under their nearest heading block.
+fn kanban_columns(blocks: &Value) -> Vec<Value> {
+    let mut all_blocks = Vec::new();
+    flatten_blocks(blocks, &mut all_blocks);
+
+    let task_card = |block: &Value| {
+        serde_json::json!({
+            "uuid": block["uuid"],
+            "content": block["content"],
+            "priority": block["priority"],
+            "scheduled": block["scheduled"]
+                .as_i64()
+                .and_then(parse_logseq_day)
+                .map(|d| d.to_string())
+        })
+    };
+    let tasks_under = |block: &Value| {
+        let mut descendants = Vec::new();
+        flatten_blocks(&block["children"], &mut descendants);
+        descendants
+            .into_iter()
+            .filter(|b| !b["marker"].is_null() && heading_text(b).is_none())
+            .map(task_card)
+            .collect::<Vec<_>>()
+    };
+
+    let columns: Vec<Value> = all_blocks
+        .iter()
+        .filter_map(|block| {
+            heading_text(block)
+                .map(|name| serde_json::json!({ "name": name, "tasks": tasks_under(block) }))
+        })
+        .collect();
+
+    if columns.is_empty() {
+        let backlog: Vec<Value> = all_blocks
+            .into_iter()
+            .filter(|b| !b["marker"].is_null())
+            .map(task_card)
+            .collect();
+        return vec![serde_json::json!({ "name": "Backlog", "tasks": backlog })];
+    }
+    columns
+}
+
+/// Returns the heading text of a block, if it is a markdown heading or
+/// carries the `heading::` property.
+fn heading_text(block: &Value) -> Option<String> {
+    let content = block["content"].as_str().unwrap_or("");
+    let first_line = content.lines().next().unwrap_or("").trim();
+    let hashes = first_line.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) && first_line[hashes..].starts_with(' ') {
+        return Some(first_line[hashes..].trim().to_string());
+    }
+    if block["properties"]["heading"].as_bool() == Some(true) {
+        return Some(first_line.to_string());
+    }
+    None
+}
+
+/// Renders a block and its children as markdown with references resolved.
+///
+/// Block references (`((uuid))`) are replaced inline with the referenced
+/// block's content, and embeds (`{{embed ((uuid))}}`) are expanded into the
+/// embedded block's tree, indented beneath the embedding block. Referenced
+/// content is itself resolved up to `resolve_depth` levels deep.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The block to render
+/// - `resolve_depth` (optional): How many levels of references to follow
+///   (default: 1, max: 3)
+///
+/// # Returns
+///
+/// JSON object with `uuid`, the `rendered` markdown, `references_resolved`
+/// and `circular_refs_skipped` (references back to a block already being
+/// rendered, which are left unresolved).
+pub async fn get_block_markdown_rendered(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    let resolve_depth = params["resolve_depth"].as_u64().unwrap_or(1).min(3) as usize;
+
+    let block = client.get_block_with_tree(uuid, true).await?;
+    if block.is_null() {
+        return Err(McpError::Internal(format!("Block '{}' not found", uuid)));
+    }
+
+    let mut renderer = MarkdownRenderer {
+        client,
+        resolve_depth,
+        references_resolved: 0,
+        circular_refs_skipped: 0,
+    };
+    let mut visiting = vec![uuid.to_string()];
+    let rendered = renderer
+        .render_blocks(std::slice::from_ref(&block), 0, 0, &mut visiting)
+        .await?;
+
+    Ok(serde_json::json!({
+        "uuid": uuid,
+        "rendered": rendered,
+        "references_resolved": renderer.references_resolved,
+        "circular_refs_skipped": renderer.circular_refs_skipped
+    }))
+}
+
+/// Renders block trees to markdown, resolving block references and embeds.
+struct MarkdownRenderer<'a> {
+    client: &'a LogseqClient,
+    resolve_depth: usize,
+    references_resolved: usize,
+    circular_refs_skipped: usize,
+}
+
+impl MarkdownRenderer<'_> {
+    /// Renders blocks as nested bullets. `depth` is the reference-resolution
+    /// depth; `visiting` holds the UUIDs currently being rendered.
+    fn render_blocks<'f>(
+        &'f mut self,
+        blocks: &'f [Value],
+        indent: usize,
+        depth: usize,
+        visiting: &'f mut Vec<String>,
+    ) -> BoxFuture<'f, McpResult<String>> {
+        Box::pin(async move {
+            let pad = "  ".repeat(indent);
+            let mut out = String::new();
+            for block in blocks {
+                let content = block["content"].as_str().unwrap_or("");
+                let (text, embeds) = self.resolve_content(content, depth, visiting).await?;
+
+                let mut lines = text.lines();
+                out.push_str(&format!("{}- {}\n", pad, lines.next().unwrap_or("")));
+                for line in lines {
+                    out.push_str(&format!("{}  {}\n", pad, line));
+                }
+
+                for embed in embeds {
+                    let embed_uuid = embed["uuid"].as_str().unwrap_or("").to_string();
+                    visiting.push(embed_uuid);
+                    out.push_str(
+                        &self
+                            .render_blocks(
+                                std::slice::from_ref(&embed),
+                                indent + 1,
+                                depth + 1,
+                                visiting,
+                            )
+                            .await?,
+                    );
+                    visiting.pop();
+                }
+
+                if let Some(children) = block["children"].as_array() {
+                    out.push_str(
+                        &self
+                            .render_blocks(children, indent + 1, depth, visiting)
+                            .await?,
+                    );
+                }
+            }
+            Ok(out)
+        })
+    }
+
+    /// Replaces inline references in `content` and returns the embedded
+    /// block trees that should be rendered beneath it.
+    fn resolve_content<'f>(
+        &'f mut self,
+        content: &'f str,
+        depth: usize,
+        visiting: &'f mut Vec<String>,
+    ) -> BoxFuture<'f, McpResult<(String, Vec<Value>)>> {
+        Box::pin(async move {
+            let refs = find_block_refs(content);
+            if refs.is_empty() || depth >= self.resolve_depth {
+                return Ok((content.to_string(), Vec::new()));
+            }
+
+            let mut text = String::new();
+            let mut embeds = Vec::new();
+            let mut cursor = 0;
+            for block_ref in refs {
+                text.push_str(&content[cursor..block_ref.start]);
+                cursor = block_ref.end;
+                let original = &content[block_ref.start..block_ref.end];
+
+                if visiting.contains(&block_ref.uuid) {
+                    self.circular_refs_skipped += 1;
+                    text.push_str(original);
+                    continue;
+                }
+
+                if block_ref.embed {
+                    let embedded = self
+                        .client
+                        .get_block_with_tree(&block_ref.uuid, true)
+                        .await?;
+                    if embedded.is_null() {
+                        text.push_str(original);
+                        continue;
+                    }
+                    self.references_resolved += 1;
+                    embeds.push(embedded);
+                } else {
+                    let referenced = self.client.get_block(&block_ref.uuid).await?;
+                    let Some(ref_content) = referenced["content"].as_str() else {
+                        text.push_str(original);
+                        continue;
+                    };
+                    self.references_resolved += 1;
+                    visiting.push(block_ref.uuid.clone());
+                    let (resolved, _) = self
+                        .resolve_content(ref_content, depth + 1, visiting)
+                        .await?;
+                    visiting.pop();
+                    text.push_str(resolved.lines().next().unwrap_or(""));
+                }
+            }
+            text.push_str(&content[cursor..]);
+            Ok((text.trim_end().to_string(), embeds))
+        })
+    }
+}
+
+/// A `((uuid))` block reference or `{{embed ((uuid))}}` found in block content.
+#[derive(Debug, PartialEq)]
+struct BlockRef {
+    start: usize,
+    end: usize,
+    uuid: String,
+    embed: bool,
+}
+
+/// Finds block references and embeds in content, in order of appearance.
+fn find_block_refs(content: &str) -> Vec<BlockRef> {
+    const EMBED_OPEN: &str = "{{embed ";
+    let mut refs = Vec::new();
+    let mut search_from = 0;
+    while let Some(offset) = content[search_from..].find("((") {
+        let open = search_from + offset;
+        let Some(close) = content[open + 2..].find("))").map(|i| open + 2 + i) else {
+            break;
+        };
+        let inner = content[open + 2..close].trim();
+        search_from = close + 2;
+        if !is_block_uuid(inner) {
+            continue;
+        }
+
+        let (mut start, mut end, mut embed) = (open, close + 2, false);
+        if content[..open].ends_with(EMBED_OPEN) && content[end..].starts_with("}}") {
+            start = open - EMBED_OPEN.len();
+            end += 2;
+            embed = true;
+        }
+        refs.push(BlockRef {
+            start,
+            end,
+            uuid: inner.to_string(),
+            embed,
+        });
+        search_from = end;
+    }
+    refs
+}
+
+/// Lists pages that have deadlines coming up soon.
+///
+/// # Parameters
+///
+/// - `days_ahead` (optional): Size of the window starting today (default: 7)
+///
+/// # Returns
+///
+/// JSON object with `days_ahead` and `pages`, an array of
+/// `{page_name, deadlines: [{uuid, content, deadline_date}]}` with each
+/// page's deadlines sorted soonest first. Pages are ordered by their
+/// earliest deadline.
+pub async fn get_pages_with_deadline_soon(
+    client: &LogseqClient,
+    params: Value,
+) -> McpResult<Value> {
+    let days_ahead = params["days_ahead"].as_u64().unwrap_or(7);
+    let today = Local::now().date_naive();
+    let until = i64::try_from(days_ahead)
+        .ok()
+        .and_then(Duration::try_days)
+        .and_then(|span| today.checked_add_signed(span))
+        .ok_or_else(|| {
+            McpError::InvalidParams(format!("days_ahead {} is out of range", days_ahead))
+        })?;
+
+    let results = client.get_scheduled_blocks(today, until).await?;
+    let mut deadlines: Vec<Value> = scheduled_items(&results, today, until)
+        .into_iter()
+        .filter(|item| item["type"] == "deadline")
+        .collect();
+    deadlines.sort_by(|a, b| a["date"].as_str().cmp(&b["date"].as_str()));
+
+    // Deadlines are already sorted, so pages come out ordered by earliest deadline
+    let mut pages: Vec<(String, Vec<Value>)> = Vec::new();
+    for item in deadlines {
+        let page = item["page"].as_str().unwrap_or("").to_string();
+        let deadline = serde_json::json!({
+            "uuid": item["uuid"],
+            "content": item["content"],
+            "deadline_date": item["date"]
+        });
+        match pages.iter_mut().find(|(name, _)| *name == page) {
+            Some((_, list)) => list.push(deadline),
+            None => pages.push((page, vec![deadline])),
+        }
+    }
+
+    Ok(serde_json::json!({
+        "pages": pages
+            .into_iter()
+            .map(|(page_name, deadlines)| {
+                serde_json::json!({ "page_name": page_name, "deadlines": deadlines })
+            })
+            .collect::<Vec<_>>(),
+        "days_ahead": days_ahead
+    }))
+}
+
+/// Fetches the subtree below a block, level by level, with depth control.
+///
+/// Each level is fetched as one [`LogseqClient::get_blocks`] batch.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The root block
+/// - `max_depth` (optional): Deepest level to fetch (1 = immediate children
+///   only, default: 5, at most 20)
+/// - `include_content` (optional): Include each block's content (default: true)
+///
+/// # Returns
+///
+/// JSON object with `root_uuid`, the nested `tree` of
+/// `{uuid, content, depth, children}` nodes, `total_blocks` fetched, and
+/// `truncated`, true when blocks below `max_depth` were left out.
+///
+/// # Errors
+///
+/// Returns an error if the root block doesn't exist.
+pub async fn get_block_children_recursive(
+    client: &LogseqClient,
+    params: Value,
+) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    let max_depth = params["max_depth"].as_u64().unwrap_or(5) as usize;
+    let include_content = params["include_content"].as_bool().unwrap_or(true);
+
+    let root = client.get_block(uuid).await?;
+    if root.is_null() {
+        return Err(McpError::Internal(format!("Block not found: {}", uuid)));
+    }
+    let root_children = child_block_uuids(&root);
+
+    // One batch per level, so API calls stay bounded by the client's
+    // concurrency limit however wide the tree is
+    let mut blocks: HashMap<String, Value> = HashMap::new();
+    let mut level = root_children.clone();
+    for _ in 0..max_depth {
+        if level.is_empty() {
+            break;
+        }
+        let fetched = client.get_blocks(&level).await?;
+        let mut next = Vec::new();
+        for (child_uuid, block) in level.into_iter().zip(fetched) {
+            if !block.is_null() {
+                next.extend(child_block_uuids(&block));
+                blocks.insert(child_uuid, block);
+            }
+        }
+        level = next;
+    }
+
+    Ok(serde_json::json!({
+        "root_uuid": uuid,
+        "tree": children_tree(&root_children, &blocks, 1, include_content),
+        "total_blocks": blocks.len(),
+        "truncated": !level.is_empty()
+    }))
+}
+
+/// Nests the fetched `blocks` below `uuids`, leaving out blocks that
+/// weren't fetched (missing, or deeper than the depth limit).
+fn children_tree(
+    uuids: &[String],
+    blocks: &HashMap<String, Value>,
+    depth: usize,
+    include_content: bool,
+) -> Vec<Value> {
+    uuids
+        .iter()
+        .filter_map(|uuid| blocks.get(uuid))
+        .map(|block| {
+            let children = children_tree(
+                &child_block_uuids(block),
+                blocks,
+                depth + 1,
+                include_content,
+            );
+            let mut node = serde_json::json!({
+                "uuid": block["uuid"],
+                "depth": depth,
+                "children": children
+            });
+            if include_content {
+                node["content"] = block["content"].clone();
+            }
+            node
+        })
+        .collect()
+}
+
+/// Estimates which writing systems the graph's content is written in.
+///
+/// Samples up to 100 pages and classifies each letter by Unicode script
+/// range. Whitespace, digits and punctuation are ignored.
+///
+/// # Returns
+///
+/// JSON object with `scripts`, mapping each script that appears (`latin`,
+/// `cjk`, `cyrillic`, `arabic`, `greek`, `hebrew`, `devanagari`, `other`) to
+/// its share of letters (0.0-1.0, rounded to two decimals), and `pages_sampled`.
+pub async fn get_graph_language_stats(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    const SAMPLE_SIZE: usize = 100;
+    const CONCURRENCY: usize = 10;
+
+    let pages = client.get_all_pages().await?;
+    let names: Vec<&str> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p["name"].as_str())
+        .take(SAMPLE_SIZE)
+        .collect();
+
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    for chunk in names.chunks(CONCURRENCY) {
+        let trees = join_all(chunk.iter().map(|name| client.get_page_blocks_tree(name))).await;
+        for tree in trees {
+            let tree = tree?;
+            let mut blocks = Vec::new();
+            flatten_blocks(&tree, &mut blocks);
+            for block in blocks {
+                count_scripts(block["content"].as_str().unwrap_or(""), &mut counts);
+            }
+        }
+    }
+
+    let total: usize = counts.values().sum();
+    let scripts: serde_json::Map<String, Value> = counts
+        .into_iter()
+        .map(|(script, n)| {
+            let share = (n as f64 / total as f64 * 100.0).round() / 100.0;
+            (script.to_string(), share.into())
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "scripts": scripts,
+        "pages_sampled": names.len()
+    }))
+}
+
+/// Adds the letters in `text` to per-script counts.
+fn count_scripts(text: &str, counts: &mut HashMap<&'static str, usize>) {
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        *counts.entry(script_of(c)).or_default() += 1;
+    }
+}
+
+/// Classifies a letter by the Unicode script block its scalar value falls in.
+fn script_of(c: char) -> &'static str {
+    match c as u32 {
+        0x0041..=0x024F | 0x1E00..=0x1EFF => "latin",
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => "greek",
+        0x0400..=0x052F => "cyrillic",
+        0x0590..=0x05FF => "hebrew",
+        0x0600..=0x06FF | 0x0750..=0x077F | 0x08A0..=0x08FF => "arabic",
+        0x0900..=0x097F => "devanagari",
+        0x3040..=0x30FF // Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK extension A
+        | 0x4E00..=0x9FFF // CJK unified ideographs
+        | 0xAC00..=0xD7AF // Hangul syllables
+        | 0x20000..=0x2A6DF => "cjk",
+        _ => "other",
+    }
+}
+
+/// Collects fenced code blocks from across the graph.
+///
+/// Finds blocks containing ` ``` ` fences with a Datascript query, then
+/// extracts each fenced snippet and its language on the Rust side.
+///
+/// # Parameters
+///
+/// - `language` (optional): Only return snippets in this language (case-insensitive)
+/// - `page_name` (optional): Restrict the search to a single page
+///
+/// # Returns
+///
+/// JSON object with `code_blocks` (`[{uuid, page, language, code, line_count}]`
+/// sorted by language then page), their `count`, and `languages`, a map of
+/// language to snippet count. Snippets without a language are `"plain"`.
+pub async fn get_all_code_blocks(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let language = params["language"].as_str().map(|l| l.to_lowercase());
+    let page_clause = params["page_name"]
+        .as_str()
+        .map(|name| {
+            format!(
+                " [?b :block/page ?p] [?p :block/name {}]",
+                datalog_string(&name.to_lowercase())
+            )
+        })
+        .unwrap_or_default();
+
+    let query = format!(
+        "[:find (pull ?b [:block/uuid :block/content {{:block/page [:block/original-name]}}]) \
+         :where [?b :block/content ?c] [(clojure.string/includes? ?c \"```\")]{page_clause}]"
+    );
+    let results = client.datascript_query(&query, Vec::new()).await?;
+
+    let mut code_blocks = Vec::new();
+    let mut languages: HashMap<String, usize> = HashMap::new();
+    for block in pulled_blocks(&results) {
+        for (lang, code) in extract_code_fences(block["content"].as_str().unwrap_or("")) {
+            if language.as_ref().is_some_and(|wanted| *wanted != lang) {
+                continue;
+            }
+            *languages.entry(lang.clone()).or_default() += 1;
+            code_blocks.push(serde_json::json!({
+                "uuid": block["uuid"],
+                "page": block["page"]["originalName"],
+                "language": lang,
+                "line_count": code.lines().count(),
+                "code": code
+            }));
+        }
+    }
+    code_blocks.sort_by(|a, b| {
+        a["language"]
+            .as_str()
+            .cmp(&b["language"].as_str())
+            .then_with(|| a["page"].as_str().cmp(&b["page"].as_str()))
+    });
+
+    Ok(serde_json::json!({
+        "code_blocks": code_blocks,
+        "count": code_blocks.len(),
+        "languages": languages
+    }))
+}
+
+/// Extracts `(language, code)` pairs from the fenced code blocks in content.
+///
+/// The language is lowercased and defaults to `"plain"`. An unterminated
+/// fence runs to the end of the content.
+fn extract_code_fences(content: &str) -> Vec<(String, String)> {
+    let mut fences = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        match current.take() {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    let lang = info.split_whitespace().next().unwrap_or("");
+                    let lang = if lang.is_empty() { "plain" } else { lang };
+                    current = Some((lang.to_lowercase(), Vec::new()));
+                }
+            }
+            Some((lang, lines)) if trimmed.starts_with("```") => {
+                fences.push((lang, lines.join("\n")));
+            }
+            Some((lang, mut lines)) => {
+                lines.push(line);
+                current = Some((lang, lines));
+            }
+        }
+    }
+    if let Some((lang, lines)) = current {
+        fences.push((lang, lines.join("\n")));
+    }
+    fences
+}
+
+/// Compares a page's file at a past git commit with its current committed version.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to compare
+/// - `commit_hash` (required): The git commit (hash or ref) to compare against
+///
+/// # Returns
+///
+/// JSON object with `page_name`, `commit`, `added_lines`, `removed_lines` and
+/// `diff`, a unified diff from the commit's version to `HEAD`.
+///
+/// # Errors
+///
+/// Returns an error if git is unavailable, the graph is not a git repository,
+/// the page has no backing file, or the commit does not exist.
+pub async fn get_page_version_diff(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let commit_hash = params["commit_hash"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("commit_hash parameter is required".to_string()))?;
+
+    // Refuse anything git could interpret as an option or a revision range
+    if commit_hash.starts_with('-') || commit_hash.contains(char::is_whitespace) {
+        return Err(McpError::InvalidParams(format!(
+            "Invalid commit hash '{}'",
+            commit_hash
+        )));
+    }
+
+    let graph = client.get_current_graph().await?;
+    let graph_path = graph["path"]
+        .as_str()
+        .ok_or_else(|| McpError::Internal("Current graph has no local path".to_string()))?;
+    let file_path = page_file_path(client, page_name)
+        .await?
+        .ok_or_else(|| McpError::Internal(format!("Page '{}' has no backing file", page_name)))?;
+    let relative = file_path
+        .strip_prefix(graph_path)
+        .unwrap_or(&file_path)
+        .trim_start_matches('/');
+
+    let old = git_output(
+        graph_path,
+        &["show", &format!("{}:./{}", commit_hash, relative)],
+    )
+    .await?;
+    let new = git_output(graph_path, &["show", &format!("HEAD:./{}", relative)]).await?;
+
+    let diff = diff_lines(&old, &new);
+    let added_lines = diff.iter().filter(|(op, _)| *op == '+').count();
+    let removed_lines = diff.iter().filter(|(op, _)| *op == '-').count();
+
+    Ok(serde_json::json!({
+        "page_name": page_name,
+        "commit": commit_hash,
+        "added_lines": added_lines,
+        "removed_lines": removed_lines,
+        "diff": unified_diff(&diff, relative, commit_hash)
+    }))
+}
+
+/// Computes a line diff.
+///
+/// Each entry is `(' ' | '-' | '+', line)` in output order. Lines are split
+/// first so a missing final newline doesn't make the last line differ.
+fn diff_lines(old: &str, new: &str) -> Vec<(char, String)> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    TextDiff::from_slices(&old, &new)
+        .iter_all_changes()
+        .map(|change| {
+            let op = match change.tag() {
+                ChangeTag::Equal => ' ',
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+            };
+            (op, change.value().to_string())
+        })
+        .collect()
+}
+
+/// Formats a line diff as a single-hunk unified diff.
+fn unified_diff(diff: &[(char, String)], file: &str, commit: &str) -> String {
+    let old_len = diff.iter().filter(|(op, _)| *op != '+').count();
+    let new_len = diff.iter().filter(|(op, _)| *op != '-').count();
+    let mut out =
+        format!("--- a/{file} ({commit})\n+++ b/{file} (HEAD)\n@@ -1,{old_len} +1,{new_len} @@\n");
+    for (op, line) in diff {
+        out.push(*op);
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Measures how well-connected the graph is by counting outgoing links per page.
+///
+/// Uses a single Datascript query for all block content, then extracts
+/// `[[wiki links]]` per page. A page's link count is the number of distinct
+/// pages it links to.
+///
+/// # Returns
+///
+/// JSON object with `average_links_per_page`, `median_links`, a
+/// `distribution` over the buckets `"0"`, `"1-5"`, `"6-10"` and `"11+"`,
+/// `most_connected_page` and up to ten `least_connected_pages`.
+pub async fn get_graph_link_density(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    let pages = client.get_all_pages().await?;
+    let contents = client
+        .datascript_query(
+            "[:find ?b ?name ?c :where [?b :block/content ?c] \
+             [?b :block/page ?p] [?p :block/original-name ?name]]",
+            Vec::new(),
+        )
+        .await?;
+
+    // Start every page at zero so pages without blocks are counted too
+    let mut links: HashMap<String, HashSet<String>> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p["originalName"].as_str().or(p["name"].as_str()))
+        .map(|name| (name.to_string(), HashSet::new()))
+        .collect();
+    for row in contents.as_array().into_iter().flatten() {
+        let (Some(name), Some(content)) = (row[1].as_str(), row[2].as_str()) else {
+            continue;
+        };
+        links
+            .entry(name.to_string())
+            .or_default()
+            .extend(extract_page_links(content));
+    }
+
+    let counts: Vec<(String, usize)> = links
+        .into_iter()
+        .map(|(name, targets)| (name, targets.len()))
+        .collect();
+    Ok(Value::Object(link_density_summary(counts)))
+}
+
+/// Matches `[[Page Name]]` wiki links, capturing the page name.
+static PAGE_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[([^\[\]]+)\]\]").expect("valid page link regex"));
+
+/// Extracts the target page names of `[[wiki links]]` in block content.
+fn extract_page_links(content: &str) -> Vec<String> {
+    PAGE_LINK_RE
+        .captures_iter(content)
+        .map(|c| c[1].trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Summarizes per-page link counts into averages, a histogram and extremes.
+fn link_density_summary(mut counts: Vec<(String, usize)>) -> serde_json::Map<String, Value> {
+    // Sort by count, then name, so extremes are deterministic
+    counts.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+    let n = counts.len();
+    let average = if n == 0 {
+        0.0
+    } else {
+        counts.iter().map(|(_, c)| *c).sum::<usize>() as f64 / n as f64
+    };
+    let median = match n {
+        0 => 0.0,
+        _ if n % 2 == 1 => counts[n / 2].1 as f64,
+        _ => (counts[n / 2 - 1].1 + counts[n / 2].1) as f64 / 2.0,
+    };
+
+    let mut distribution = serde_json::Map::new();
+    for bucket in ["0", "1-5", "6-10", "11+"] {
+        distribution.insert(bucket.into(), 0.into());
+    }
+    for (_, count) in &counts {
+        let bucket = match count {
+            0 => "0",
+            1..=5 => "1-5",
+            6..=10 => "6-10",
+            _ => "11+",
+        };
+        let slot = distribution.get_mut(bucket).expect("bucket initialized");
+        *slot = (slot.as_u64().unwrap_or(0) + 1).into();
+    }
+
+    let most_connected = counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .map(|(name, _)| name.clone());
+    let least_connected: Vec<&String> = counts
+        .iter()
+        .take_while(|(_, c)| Some(*c) == counts.first().map(|f| f.1))
+        .take(10)
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut summary = serde_json::Map::new();
+    summary.insert("average_links_per_page".into(), average.into());
+    summary.insert("median_links".into(), median.into());
+    summary.insert("distribution".into(), distribution.into());
+    summary.insert("most_connected_page".into(), most_connected.into());
+    summary.insert(
+        "least_connected_pages".into(),
+        serde_json::json!(least_connected),
+    );
+    summary
+}
+
+/// Runs a Datalog query with optional input bindings.
+///
+/// Like `query`, but supports parameterised queries through Datascript's
+/// `:in` clause so callers don't have to splice values into the query text.
+///
+/// # Parameters
+///
+/// - `query` (required): Datalog query string, e.g.
+///   `[:find ?c :in $ ?name :where [?p :block/name ?name] [?b :block/page ?p] [?b :block/content ?c]]`
+/// - `bindings` (optional): Array of inputs for the `:in` clause after `$`.
+///   Strings are read as EDN by Logseq, so a literal string needs quotes: `"\"my page\""`
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `results`: Raw query results as returned by Datascript
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The query parameter is missing or not a bracketed Datalog form (`McpError::InvalidParams`)
+/// - Logseq fails to parse or execute the query
+pub async fn datalog_query(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let q = params["query"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("query parameter is required".to_string()))?;
+
+    check_datalog_syntax(q).map_err(McpError::InvalidParams)?;
+
+    let bindings = params["bindings"].as_array().cloned().unwrap_or_default();
+
+    let results = client
+        .datascript_query(q, bindings)
+        .await
+        .map_err(|e| match e {
+            McpError::LogseqApi { method, message } => McpError::LogseqApi {
+                method,
+                message: format!("Datalog query failed: {}", message),
+            },
+            other => other,
+        })?;
+    Ok(serde_json::json!({ "results": results }))
+}
+
+/// Cheap structural check run before sending a query to Logseq, whose own
+/// parse errors come back as opaque ClojureScript exceptions.
+fn check_datalog_syntax(query: &str) -> std::result::Result<(), String> {
+    let trimmed = query.trim();
+    if !(trimmed.starts_with('[') || trimmed.starts_with('{')) {
+        return Err(
+            "Datalog query must be a vector or map, e.g. [:find ?x :where ...]".to_string(),
+        );
+    }
+
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '(' | '{' => stack.push(c),
+            ']' | ')' | '}' => {
+                let open = match c {
+                    ']' => '[',
+                    ')' => '(',
+                    _ => '{',
+                };
+                if stack.pop() != Some(open) {
+                    return Err(format!("Unbalanced '{}' in Datalog query", c));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if in_string {
+        return Err("Unterminated string in Datalog query".to_string());
+    }
+    if !stack.is_empty() {
+        return Err("Unclosed bracket in Datalog query".to_string());
+    }
+    if !trimmed.contains(":find") {
+        return Err("Datalog query is missing a :find clause".to_string());
+    }
+    Ok(())
+}
+
+/// Gets the page-level properties of a page.
+///
+/// Page properties live in the first block of the page (`tags:: a, b`,
+/// `alias:: x`, custom `key:: value` pairs).
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to read properties from
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `page`: The requested page name
+/// - `properties`: Property map; empty object if the page has none
+/// - `tags`: The `tags` property as an array of page names (empty if absent)
+pub async fn get_page_properties(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let props = client.get_page_properties(page_name).await?;
+    Ok(page_properties_response(page_name, props))
+}
+
+/// Shapes a `getPageProperties` result, normalizing null to `{}` and `tags` to an array.
+fn page_properties_response(page_name: &str, props: Value) -> Value {
+    let properties = if props.is_object() {
+        props
+    } else {
+        Value::Object(serde_json::Map::new())
+    };
+    let tags = property_list(&properties["tags"]);
+    serde_json::json!({
+        "page": page_name,
+        "properties": properties,
+        "tags": tags
+    })
+}
+
+/// Lists journal (daily note) pages, most recent first.
+///
+/// # Parameters
+///
+/// - `limit` (optional): Maximum number of journals to return
+/// - `since` (optional): Only include journals on or after this ISO 8601 date (`YYYY-MM-DD`)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `journals`: Journal page objects sorted by `journalDay` descending
+///
+/// # Errors
+///
+/// Returns an error if `since` is not a valid `YYYY-MM-DD` date.
+pub async fn get_journal_pages(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let since = params["since"]
+        .as_str()
+        .map(|s| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+                McpError::InvalidParams(format!("Invalid since date '{}', expected YYYY-MM-DD", s))
+            })
+        })
+        .transpose()?;
+    let limit = params["limit"].as_u64().map(|l| l as usize);
+
+    let pages = client.get_all_pages().await?;
+    Ok(serde_json::json!({
+        "journals": journal_pages(&pages, since, limit)
+    }))
+}
+
+/// Filters `getAllPages` output down to journals, newest first.
+fn journal_pages(pages: &Value, since: Option<NaiveDate>, limit: Option<usize>) -> Vec<Value> {
+    let mut journals: Vec<&Value> = pages
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter(|p| p["journal?"].as_bool() == Some(true))
+                .filter(|p| {
+                    since.is_none_or(|since| {
+                        p["journalDay"]
+                            .as_i64()
+                            .and_then(parse_logseq_day)
+                            .is_some_and(|day| day >= since)
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    journals.sort_by_key(|p| std::cmp::Reverse(p["journalDay"].as_i64().unwrap_or(0)));
+    journals
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect()
+}
+
+/// Flattens a `logseq.App.search` response into one list of typed items.
+///
+/// Logseq answers with `{ "blocks": [...], "pages": ["name", ...] }`; blocks
+/// come first since they carry the content matches. An array response
+/// (older API versions) is passed through, tagging untyped items as blocks.
+fn search_items(raw: Value) -> Vec<Value> {
+    let tag_block = |mut block: Value| {
+        if let Some(obj) = block.as_object_mut() {
+            obj.entry("type").or_insert_with(|| "block".into());
+        }
+        block
+    };
+    match raw {
+        Value::Array(items) => items.into_iter().map(tag_block).collect(),
+        Value::Object(mut obj) => {
+            let blocks = match obj.remove("blocks") {
+                Some(Value::Array(blocks)) => blocks,
+                _ => Vec::new(),
+            };
+            let pages = match obj.remove("pages") {
+                Some(Value::Array(pages)) => pages,
+                _ => Vec::new(),
+            };
+            blocks
+                .into_iter()
+                .map(tag_block)
+                .chain(pages.into_iter().map(|page| match page {
+                    Value::String(name) => serde_json::json!({ "type": "page", "name": name }),
+                    other => serde_json::json!({ "type": "page", "name": other["name"] }),
+                }))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Gets a block together with its parent block.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The block whose parent to look up
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `block`: The requested block
+/// - `parent`: The parent block, or null if the block is top-level on its page
+///
+/// # Errors
+///
+/// Returns an error if the uuid parameter is missing or the block doesn't exist.
+pub async fn get_block_parent(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+
+    let block = client.get_block_with_tree(uuid, false).await?;
+    if block.is_null() {
+        return Err(McpError::Internal(format!("Block not found: {}", uuid)));
+    }
+
+    let parent = match parent_block_ref(&block) {
+        Some(BlockParent::Uuid(parent_uuid)) => client.get_block(&parent_uuid).await?,
+        Some(BlockParent::Id(id)) => client.get_block_by_id(id).await?,
+        None => Value::Null,
+    };
+
+    Ok(serde_json::json!({
+        "block": block,
+        "parent": parent
+    }))
+}
+
+/// How a block refers to its parent block.
+#[derive(Debug, PartialEq)]
+enum BlockParent {
+    Uuid(String),
+    Id(i64),
+}
+
+/// Extracts the parent block reference, or None when the parent is the page itself.
+fn parent_block_ref(block: &Value) -> Option<BlockParent> {
+    let parent = &block["parent"];
+    let parent_id = parent["id"].as_i64();
+    if parent_id.is_some() && parent_id == block["page"]["id"].as_i64() {
+        return None;
+    }
+    parent["uuid"]
+        .as_str()
+        .map(|u| BlockParent::Uuid(u.to_string()))
+        .or(parent_id.map(BlockParent::Id))
+}
+
+/// Lists every tag used in the graph, from page `tags::` properties and inline `#tags`.
+///
+/// Tags are matched case-insensitively (as Logseq does) and reported in
+/// lowercase, sorted alphabetically.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `tags`: Deduplicated, sorted tag names
+/// - `count`: Number of distinct tags
+/// - `page_count_per_tag`: Map of tag → number of distinct pages using it
+///   (as a page tag or in any of the page's blocks)
+pub async fn get_all_tags(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    let pages = client.get_all_pages().await?;
+    let blocks = client
+        .datascript_query(
+            "[:find ?c ?name :where [?b :block/content ?c] \
+             [?b :block/page ?p] [?p :block/original-name ?name]]",
+            Vec::new(),
+        )
+        .await?;
+
+    let tag_pages = collect_tags(&pages, &blocks);
+    let page_count_per_tag: serde_json::Map<String, Value> = tag_pages
+        .iter()
+        .map(|(tag, pages)| (tag.clone(), pages.len().into()))
+        .collect();
+
+    Ok(serde_json::json!({
+        "tags": tag_pages.keys().collect::<Vec<_>>(),
+        "count": tag_pages.len(),
+        "page_count_per_tag": page_count_per_tag
+    }))
+}
+
+/// Matches inline `#tag` and `#[[multi word tag]]`, but not `# heading` or URL fragments.
+static INLINE_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:^|\s)#(?:\[\[([^\[\]]+)\]\]|([^\s#\[\],.!?;:()"']+))"#)
+        .expect("valid inline tag regex")
+});
+
+/// Maps each lowercased tag to the set of (lowercased) pages that use it.
+///
+/// `pages` is `getAllPages` output; `blocks` are `[content, page-name]` rows.
+fn collect_tags(
+    pages: &Value,
+    blocks: &Value,
+) -> std::collections::BTreeMap<String, HashSet<String>> {
+    let mut tags: std::collections::BTreeMap<String, HashSet<String>> = Default::default();
+
+    for page in pages.as_array().into_iter().flatten() {
+        let Some(name) = page["name"].as_str() else {
+            continue;
+        };
+        for tag in property_list(&page["properties"]["tags"]) {
+            tags.entry(tag.to_lowercase())
+                .or_default()
+                .insert(name.to_lowercase());
+        }
+    }
+
+    for row in blocks.as_array().into_iter().flatten() {
+        let (Some(content), Some(page)) = (row[0].as_str(), row[1].as_str()) else {
+            continue;
+        };
+        for cap in INLINE_TAG_RE.captures_iter(content) {
+            let tag = cap.get(1).or(cap.get(2)).map_or("", |m| m.as_str()).trim();
+            if !tag.is_empty() {
+                tags.entry(tag.to_lowercase())
+                    .or_default()
+                    .insert(page.to_lowercase());
+            }
+        }
+    }
+
+    tags
+}
+
+/// Finds pages that no other block links to.
+///
+/// Backlinks are looked up with `call_api_batch`, a batch at a time, and the scan
+/// stops as soon as `limit` orphans have been found. Progress is reported
+/// after each batch when the client supplied a progress token.
+///
+/// # Parameters
+///
+/// - `limit` (optional): Maximum number of orphans to return (default: 50)
+/// - `include_journals` (optional): Also consider journal pages (default: false)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `orphaned_pages`: `[{ "name", "uuid" }]` in `getAllPages` order
+/// - `count`: Number of orphans returned
+pub async fn find_orphaned_pages(
+    client: &LogseqClient,
+    params: Value,
+    progress: &ProgressReporter,
+) -> McpResult<Value> {
+    const BATCH_SIZE: usize = 16;
+
+    let limit = params["limit"].as_u64().unwrap_or(50) as usize;
+    let include_journals = params["include_journals"].as_bool().unwrap_or(false);
+
+    let pages = client.get_all_pages().await?;
+    let candidates: Vec<&Value> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|p| p["name"].is_string())
+        .filter(|p| include_journals || p["journal?"].as_bool() != Some(true))
+        .collect();
+
+    let mut orphans = Vec::new();
+    let mut checked = 0;
+    for batch in candidates.chunks(BATCH_SIZE) {
+        if orphans.len() >= limit {
+            break;
+        }
+        let calls = batch
+            .iter()
+            .map(|page| {
+                (
+                    "logseq.Editor.getPageLinkedReferences".to_string(),
+                    vec![page["name"].clone()],
+                )
+            })
+            .collect();
+        let refs = client.call_api_batch(calls).await?;
+        for (page, refs) in batch.iter().zip(refs) {
+            if has_no_references(&refs) {
+                orphans.push(serde_json::json!({
+                    "name": page["originalName"].as_str().or(page["name"].as_str()),
+                    "uuid": page["uuid"]
+                }));
+            }
+        }
+        checked += batch.len();
+        progress.report(
+            checked as f64,
+            candidates.len() as f64,
+            &format!("Checked {} of {} pages", checked, candidates.len()),
+        );
+    }
+    orphans.truncate(limit);
+
+    Ok(serde_json::json!({
+        "count": orphans.len(),
+        "orphaned_pages": orphans
+    }))
+}
+
+/// True when `[[page, [blocks]], ...]` linked references contain no blocks.
+fn has_no_references(refs: &Value) -> bool {
+    refs.as_array().is_none_or(|groups| {
+        groups
+            .iter()
+            .all(|group| group[1].as_array().is_none_or(|blocks| blocks.is_empty()))
+    })
+}
+
+/// Gets a block together with its breadcrumb of ancestor blocks.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The block to locate
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `block`: The requested block
+/// - `page`: Name of the page the block lives on
+/// - `path`: Ancestors as `{uuid, content}` from the top-level block down to
+///   the block's direct parent; empty when the block is top-level on its page.
+///   `content` is the first line of each ancestor
+///
+/// # Errors
+///
+/// Returns an error if the uuid parameter is missing, the block doesn't exist,
+/// or the block can't be found in its page's tree.
+pub async fn get_block_path(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+
+    let block = client.get_block(uuid).await?;
+    if block.is_null() {
+        return Err(McpError::Internal(format!("Block not found: {}", uuid)));
+    }
+
+    let page_name = block_page_name(client, &block, uuid).await?;
+
+    let tree = client.get_page_blocks_tree(&page_name).await?;
+    let path = block_path(&tree, uuid).ok_or_else(|| {
+        McpError::Internal(format!("Block {} not found on page {}", uuid, page_name))
+    })?;
+
+    Ok(serde_json::json!({
+        "block": block,
+        "path": path,
+        "page": page_name
+    }))
+}
+
+/// Resolves the name of the page a fetched block lives on.
+///
+/// Blocks usually reference their page by id only, in which case the page
+/// is looked up.
+pub(crate) async fn block_page_name(
+    client: &LogseqClient,
+    block: &Value,
+    uuid: &str,
+) -> McpResult<String> {
+    if let Some(name) = page_name_of(&block["page"]) {
+        return Ok(name);
+    }
+    let page_id = block["page"]["id"]
+        .as_i64()
+        .ok_or_else(|| McpError::Internal(format!("Block {} has no page reference", uuid)))?;
+    let page = client.get_page_by_id(page_id).await?;
+    page_name_of(&page).ok_or_else(|| McpError::Internal(format!("Page not found: {}", page_id)))
+}
+
+/// Reads a page's display name, preferring the original casing.
+fn page_name_of(page: &Value) -> Option<String> {
+    page["originalName"]
+        .as_str()
+        .or_else(|| page["name"].as_str())
+        .map(String::from)
+}
+
+/// Finds the ancestors of `uuid` in a block tree, top-level first.
+///
+/// Returns `None` if the block isn't in the tree, and an empty path if it is
+/// one of the top-level blocks.
+fn block_path(tree: &Value, uuid: &str) -> Option<Vec<Value>> {
+    for block in tree.as_array()? {
+        if block["uuid"].as_str() == Some(uuid) {
+            return Some(Vec::new());
+        }
+        if let Some(mut rest) = block_path(&block["children"], uuid) {
+            let content = block["content"].as_str().unwrap_or("");
+            rest.insert(
+                0,
+                serde_json::json!({
+                    "uuid": block["uuid"],
+                    "content": content.lines().next().unwrap_or("")
+                }),
+            );
+            return Some(rest);
+        }
+    }
+    None
+}
+
+/// Gets a page's content as a markdown outline instead of a raw block tree.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to render
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `page`: The page name
+/// - `markdown`: The block tree as nested `- ` bullets
+///
+/// # Errors
+///
+/// Returns an error if the page_name parameter is missing or the page doesn't exist.
+pub async fn get_page_content_as_markdown(
+    client: &LogseqClient,
+    params: Value,
+) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let page = client.get_page(page_name).await?;
+    if page.is_null() {
+        return Err(McpError::Internal(format!("Page not found: {}", page_name)));
+    }
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+
+    Ok(serde_json::json!({
+        "page": page_name,
+        "markdown": format_block_tree_as_markdown(&blocks, 0)
+    }))
+}
+
+/// Counts the words, blocks and characters on a page.
+///
+/// Words are split on Unicode word boundaries, so punctuation isn't counted
+/// and CJK text is handled sensibly.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to analyze
+///
+/// # Returns
+///
+/// JSON object with `page`, `word_count`, `block_count` and `character_count`.
+///
+/// # Errors
+///
+/// Returns an error if the page_name parameter is missing.
+pub async fn get_page_word_count(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    let tree = client.get_page_blocks_tree(page_name).await?;
+    let stats = TextStats::of(&collect_block_texts(&tree));
+
+    Ok(serde_json::json!({
+        "page": page_name,
+        "word_count": stats.words,
+        "block_count": stats.blocks,
+        "character_count": stats.characters
+    }))
+}
+
+/// Counts words across every page in the graph.
+///
+/// Pages are fetched ten at a time so large graphs don't flood the Logseq API.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `total_words`, `total_blocks`, `total_characters`: Graph-wide sums
+/// - `page_count`: Number of pages counted
+/// - `pages`: `[{ "page", "word_count", "block_count", "character_count" }]`,
+///   sorted by word count descending
+pub async fn get_graph_word_count(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    const CONCURRENCY: usize = 10;
+
+    let pages = client.get_all_pages().await?;
+    let names: Vec<&str> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p["originalName"].as_str().or(p["name"].as_str()))
+        .collect();
+
+    let mut per_page = Vec::with_capacity(names.len());
+    for chunk in names.chunks(CONCURRENCY) {
+        let trees = join_all(chunk.iter().map(|name| client.get_page_blocks_tree(name))).await;
+        for (name, tree) in chunk.iter().zip(trees) {
+            per_page.push((*name, TextStats::of(&collect_block_texts(&tree?))));
+        }
+    }
+    per_page.sort_by(|a, b| b.1.words.cmp(&a.1.words).then_with(|| a.0.cmp(b.0)));
+
+    Ok(serde_json::json!({
+        "total_words": per_page.iter().map(|(_, s)| s.words).sum::<usize>(),
+        "total_blocks": per_page.iter().map(|(_, s)| s.blocks).sum::<usize>(),
+        "total_characters": per_page.iter().map(|(_, s)| s.characters).sum::<usize>(),
+        "page_count": per_page.len(),
+        "pages": per_page
+            .iter()
+            .map(|(name, s)| serde_json::json!({
+                "page": name,
+                "word_count": s.words,
+                "block_count": s.blocks,
+                "character_count": s.characters
+            }))
+            .collect::<Vec<_>>()
+    }))
+}
+
+/// Collects the `content` of every block in a tree, in document order.
+fn collect_block_texts(blocks: &Value) -> Vec<String> {
+    let mut flat = Vec::new();
+    flatten_blocks(blocks, &mut flat);
+    flat.into_iter()
+        .map(|block| block["content"].as_str().unwrap_or("").to_string())
+        .collect()
+}
+
+/// Word, block and character totals for a set of block texts.
+#[derive(Debug, PartialEq)]
+struct TextStats {
+    words: usize,
+    blocks: usize,
+    characters: usize,
+}
+
+impl TextStats {
+    fn of(texts: &[String]) -> Self {
+        let joined = texts.join(" ");
+        TextStats {
+            words: joined.unicode_words().count(),
+            blocks: texts.len(),
+            characters: texts.iter().map(|t| t.chars().count()).sum(),
+        }
+    }
+}
+
+/// Lists the pages modified most recently within a time window.
+///
+/// # Parameters
+///
+/// - `since_hours` (optional): How far back to look (default: 24)
+/// - `limit` (optional): Maximum pages to return (default: 10)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `pages`: `[{ "name", "uuid", "updated_at" }]`, most recent first, with
+///   `updated_at` as an ISO 8601 timestamp
+/// - `since`: The ISO 8601 cutoff
+/// - `count`: Number of pages returned
+pub async fn get_recently_modified_pages(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let since_hours = params["since_hours"].as_i64().unwrap_or(24);
+    let limit = params["limit"].as_u64().unwrap_or(10) as usize;
+
+    let since = Some(since_hours)
+        .filter(|&hours| hours > 0)
+        .and_then(Duration::try_hours)
+        .and_then(|span| Utc::now().checked_sub_signed(span))
+        .ok_or_else(|| {
+            McpError::InvalidParams(format!("since_hours {} is out of range", since_hours))
+        })?;
+    let pages = client.get_all_pages().await?;
+    let recent = recently_modified(&pages, since, limit);
+
+    Ok(serde_json::json!({
+        "count": recent.len(),
+        "pages": recent,
+        "since": since.to_rfc3339()
+    }))
+}
+
+/// Returns up to `limit` pages with `updatedAt` at or after `since`, newest first.
+fn recently_modified(pages: &Value, since: DateTime<Utc>, limit: usize) -> Vec<Value> {
+    let mut recent: Vec<(&Value, DateTime<Utc>)> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            let updated = DateTime::from_timestamp_millis(p["updatedAt"].as_i64()?)?;
+            (updated >= since).then_some((p, updated))
+        })
+        .collect();
+    recent.sort_by_key(|&(_, updated)| std::cmp::Reverse(updated));
+    recent
+        .into_iter()
+        .take(limit)
+        .map(|(page, updated)| {
+            serde_json::json!({
+                "name": page["originalName"].as_str().or(page["name"].as_str()),
+                "uuid": page["uuid"],
+                "updated_at": updated.to_rfc3339()
+            })
+        })
+        .collect()
+}
+
+/// Finds incomplete tasks across the graph using full-text search.
+///
+/// Searches once for the marker text, then keeps only blocks whose content
+/// actually starts with the marker (the search also matches the word
+/// anywhere in a block).
+///
+/// # Parameters
+///
+/// - `marker` (optional): One of `TODO`, `LATER`, `IN-PROGRESS`, `WAITING`,
+///   `DOING` (default: `TODO`)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `tasks`: `[{ "uuid", "content", "page", "marker" }]`
+/// - `count`: Number of tasks found
+///
+/// # Notes
+///
+/// Logseq's search caps its result count, so very large graphs may have
+/// more matching tasks than are returned.
+pub async fn find_incomplete_tasks(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let marker = params["marker"].as_str().unwrap_or("TODO");
+    if !INCOMPLETE_TASK_MARKERS.contains(&marker) {
+        return Err(McpError::InvalidParams(format!(
+            "marker must be one of {}",
+            INCOMPLETE_TASK_MARKERS.join(", ")
+        )));
+    }
+
+    let results = client.search(marker).await?;
+    let tasks = tasks_with_marker(search_items(results), marker);
+
+    Ok(serde_json::json!({
+        "count": tasks.len(),
+        "tasks": tasks
+    }))
+}
+
+/// Keeps the search result blocks whose content starts with `marker`.
+///
+/// Search results use either plain (`uuid`) or namespaced (`block/uuid`) keys
+/// depending on the Logseq version, so both are accepted.
+fn tasks_with_marker(items: Vec<Value>, marker: &str) -> Vec<Value> {
+    let field = |item: &Value, key: &str| -> Value {
+        let value = &item[key];
+        if value.is_null() {
+            item[format!("block/{}", key)].clone()
+        } else {
+            value.clone()
+        }
+    };
+    items
+        .iter()
+        .filter(|item| item["type"] == "block")
+        .filter_map(|item| {
+            let content = field(item, "content");
+            let text = content.as_str()?.trim_start();
+            let rest = text.strip_prefix(marker)?;
+            (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| {
+                serde_json::json!({
+                    "uuid": field(item, "uuid"),
+                    "content": content,
+                    "page": field(item, "page"),
+                    "marker": marker
+                })
+            })
+        })
+        .collect()
+}
+
+/// Lists the pages under a namespace such as `Projects` (`Projects/2025/Q1`, ...).
+///
+/// # Parameters
+///
+/// - `namespace` (required): The namespace prefix, matched case-insensitively
+/// - `recursive` (optional): Include nested sub-namespaces (default: true);
+///   when false only direct children are returned
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `namespace`: The namespace as given
+/// - `pages`: `[{ "name", "full_name", "uuid" }]` where `name` is relative to
+///   the namespace, sorted by name so each sub-namespace's pages are grouped
+/// - `count`: Number of pages returned
+pub async fn get_namespace_pages(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let namespace = params["namespace"]
+        .as_str()
+        .map(|ns| ns.trim().trim_end_matches('/'))
+        .filter(|ns| !ns.is_empty())
+        .ok_or_else(|| McpError::InvalidParams("namespace parameter is required".to_string()))?;
+    let recursive = params["recursive"].as_bool().unwrap_or(true);
+
+    let pages = client.get_all_pages().await?;
+    let children = namespace_pages(&pages, namespace, recursive);
+
+    Ok(serde_json::json!({
+        "namespace": namespace,
+        "count": children.len(),
+        "pages": children
+    }))
+}
+
+/// Picks the pages under `namespace/` out of `getAllPages` output, sorted by
+/// their name relative to the namespace.
+fn namespace_pages(pages: &Value, namespace: &str, recursive: bool) -> Vec<Value> {
+    let prefix = format!("{}/", namespace.to_lowercase());
+    let mut children: Vec<(String, Value)> = pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|page| {
+            let full_name = page["originalName"].as_str().or(page["name"].as_str())?;
+            let head = full_name.get(..prefix.len())?;
+            if head.to_lowercase() != prefix {
+                return None;
+            }
+            let name = &full_name[prefix.len()..];
+            if name.is_empty() || (!recursive && name.contains('/')) {
+                return None;
+            }
+            Some((
+                name.to_lowercase(),
+                serde_json::json!({
+                    "name": name,
+                    "full_name": full_name,
+                    "uuid": page["uuid"]
+                }),
+            ))
+        })
+        .collect();
+    children.sort_by(|a, b| a.0.cmp(&b.0));
+    children.into_iter().map(|(_, page)| page).collect()
+}
+
+/// Maximum characters of block content kept in a backlink's `context`.
+const BACKLINK_CONTEXT_CHARS: usize = 200;
+
+/// Gets a page's backlinks as a flat list of referencing blocks.
+///
+/// Same source as `get_linked_references`, but each referencing block
+/// becomes one entry with its page and a short excerpt, so clients don't
+/// have to unpack Logseq's `[page, [blocks]]` pairs.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to find backlinks for
+/// - `limit` (optional): Maximum backlinks to return
+/// - `include_journal` (optional): Include references from journal pages
+///   (default: true)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `page`: The requested page name
+/// - `backlinks`: `[{ "source_page", "source_uuid", "context" }]`, where
+///   `context` is the block content with whitespace collapsed, cut to 200
+///   characters
+/// - `count`: Number of backlinks returned
+pub async fn get_page_backlinks(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let limit = params["limit"].as_u64().map(|l| l as usize);
+    let include_journal = params["include_journal"].as_bool().unwrap_or(true);
+
+    let refs = client.get_linked_references(page_name).await?;
+    let mut backlinks = backlinks(&refs, include_journal);
+    if let Some(limit) = limit {
+        backlinks.truncate(limit);
+    }
+
+    Ok(serde_json::json!({
+        "page": page_name,
+        "count": backlinks.len(),
+        "backlinks": backlinks
+    }))
+}
+
+/// Flattens `getPageLinkedReferences` output into one entry per block.
+fn backlinks(refs: &Value, include_journal: bool) -> Vec<Value> {
+    refs.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|pair| {
+            let page = pair.get(0)?;
+            if !include_journal && page["journal?"].as_bool() == Some(true) {
+                return None;
+            }
+            let source_page = page["originalName"].as_str().or(page["name"].as_str());
+            Some(pair.get(1)?.as_array()?.iter().map(move |block| {
+                serde_json::json!({
+                    "source_page": source_page,
+                    "source_uuid": block["uuid"],
+                    "context": backlink_context(block["content"].as_str().unwrap_or(""))
+                })
+            }))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Collapses whitespace in `content` and cuts it to
+/// [`BACKLINK_CONTEXT_CHARS`] characters, marking the cut with `…`.
+fn backlink_context(content: &str) -> String {
+    let collapsed = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= BACKLINK_CONTEXT_CHARS {
+        return collapsed;
+    }
+    let mut excerpt: String = collapsed.chars().take(BACKLINK_CONTEXT_CHARS).collect();
+    excerpt.truncate(excerpt.trim_end().len());
+    excerpt.push('…');
+    excerpt
+}
+
+/// Gets the graph's Logseq configuration plus a summary of common settings.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `config`: The raw `getUserConfigs` object
+/// - `summary`: `{ "preferred_format", "journal_file_name_format",
+///   "enable_block_timestamps" }`, falling back to Logseq's defaults
+///   (`markdown`, `yyyy_MM_dd`, `false`) for settings the API doesn't report
+pub async fn get_graph_config(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    let config = client.get_user_configs().await?;
+    let summary = config_summary(&config);
+    Ok(serde_json::json!({
+        "config": config,
+        "summary": summary
+    }))
+}
+
+/// Pulls the commonly needed settings out of a user config object.
+///
+/// Accepts both camelCase API keys and the `config.edn` key names.
+fn config_summary(config: &Value) -> Value {
+    let field = |keys: &[&str]| keys.iter().map(|key| &config[*key]).find(|v| !v.is_null());
+    let preferred_format = field(&["preferredFormat", "preferred-format"])
+        .and_then(Value::as_str)
+        .unwrap_or("markdown")
+        .to_lowercase();
+    let journal_file_name_format = field(&["journalFileNameFormat", "journal/file-name-format"])
+        .and_then(Value::as_str)
+        .unwrap_or("yyyy_MM_dd");
+    let enable_block_timestamps =
+        field(&["enableBlockTimestamps", "feature/enable-block-timestamps?"])
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+    serde_json::json!({
+        "preferred_format": preferred_format,
+        "journal_file_name_format": journal_file_name_format,
+        "enable_block_timestamps": enable_block_timestamps
+    })
+}
+
+/// Reports the running Logseq version alongside this server's version.
+///
+/// # Returns
+///
+/// JSON object containing `version`, `build`, `commit` and `platform` as
+/// reported by Logseq (`null` for any field it doesn't provide), plus
+/// `mcp_server_version`.
+pub async fn get_logseq_version(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    let info = client.get_app_info().await?;
+    Ok(version_info(&info))
+}
+
+/// Picks the version fields out of `getInfo` output.
+fn version_info(info: &Value) -> Value {
+    serde_json::json!({
+        "version": info["version"],
+        "build": info["build"],
+        "commit": info["commit"],
+        "platform": info["platform"],
+        "mcp_server_version": SERVER_VERSION
+    })
+}
+
+/// Finds the blocks that reference a block with `((uuid))`.
+///
+/// Check this before deleting or rewriting a block to avoid leaving
+/// dangling references.
+///
+/// # Parameters
+///
+/// - `uuid` (required): UUID of the referenced block
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `uuid`: The requested block UUID
+/// - `referenced_by`: `[{ "uuid", "content", "page" }]`; empty if nothing
+///   references the block
+/// - `count`: Number of referencing blocks
+///
+/// # Errors
+///
+/// Returns `McpError::InvalidParams` if `uuid` isn't a UUID.
+pub async fn get_block_refs(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+    require_block_uuid("uuid", uuid)?;
+
+    let results = client.get_block_refs(uuid).await?;
+    let referenced_by: Vec<Value> = pulled_blocks(&results)
+        .into_iter()
+        .map(|block| {
+            serde_json::json!({
+                "uuid": block["uuid"],
+                "content": block["content"],
+                "page": block["page"]["originalName"]
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "uuid": uuid,
+        "count": referenced_by.len(),
+        "referenced_by": referenced_by
+    }))
+}
+
+/// Counts a page's outgoing `[[links]]` and incoming linked references.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to analyze
+/// - `direction` (optional): `outgoing`, `incoming` or `both` (default: `both`)
+///
+/// # Returns
+///
+/// JSON object containing `page` and `total`, plus `outgoing_count` and
+/// `outgoing_pages` and/or `incoming_count` and `incoming_pages` for the
+/// requested directions. Page lists are de-duplicated case-insensitively
+/// and keep first-seen order.
+pub async fn get_page_link_count(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let direction = params["direction"].as_str().unwrap_or("both");
+    if !["outgoing", "incoming", "both"].contains(&direction) {
+        return Err(McpError::InvalidParams(
+            "direction must be one of outgoing, incoming, both".to_string(),
+        ));
+    }
+
+    let mut result = serde_json::json!({ "page": page_name });
+    let mut total = 0;
+    if direction != "incoming" {
+        let blocks = client.get_page_blocks_tree(page_name).await?;
+        let outgoing = outgoing_links(&blocks);
+        total += outgoing.len();
+        result["outgoing_count"] = outgoing.len().into();
+        result["outgoing_pages"] = outgoing.into();
+    }
+    if direction != "outgoing" {
+        let refs = client.get_linked_references(page_name).await?;
+        let incoming = referencing_pages(&refs);
+        total += incoming.len();
+        result["incoming_count"] = incoming.len().into();
+        result["incoming_pages"] = incoming.into();
+    }
+    result["total"] = total.into();
+
+    Ok(result)
+}
+
+/// Distinct `[[link]]` targets in a block tree.
+fn outgoing_links(blocks: &Value) -> Vec<String> {
+    dedup_names(
+        collect_block_texts(blocks)
+            .iter()
+            .flat_map(|text| extract_page_links(text)),
+    )
+}
+
+/// Distinct page names in `getPageLinkedReferences` output.
+fn referencing_pages(refs: &Value) -> Vec<String> {
+    dedup_names(refs.as_array().into_iter().flatten().filter_map(|pair| {
+        let page = &pair[0];
+        page["originalName"]
+            .as_str()
+            .or(page["name"].as_str())
+            .map(str::to_string)
+    }))
+}
+
+/// Drops page names already seen, ignoring case, keeping the first spelling.
+fn dedup_names(names: impl Iterator<Item = String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    names
+        .filter(|name| seen.insert(name.to_lowercase()))
+        .collect()
+}
+
+/// Lists the graph's templates (pages with a `template::` property).
+///
+/// Use this to find names for `execute_template`.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `templates`: `[{ "name", "page", "uuid", "description" }]` sorted by
+///   name, where `description` is the page's `description::` property or null
+/// - `count`: Number of templates
+pub async fn get_templates(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    let pages = client.get_all_pages().await?;
+    let templates: Vec<Value> = template_pages(&pages)
+        .into_iter()
+        .map(|t| {
+            serde_json::json!({
+                "name": t.name,
+                "page": t.page,
+                "uuid": t.uuid,
+                "description": t.description
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "count": templates.len(),
+        "templates": templates
+    }))
+}
+
+/// Summarizes the graph's pages from a single `getAllPages` call.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `total_pages`, `journal_pages`, `regular_pages`: Page counts
+/// - `namespaces`: Number of distinct top-level namespaces (the first
+///   segment of `a/b` page names)
+/// - `recently_created`, `recently_modified`: Pages created or updated in
+///   the last 7 days
+///
+/// Block counts aren't included, since they would need every page's block
+/// tree.
+pub async fn get_graph_statistics(client: &LogseqClient, _params: Value) -> McpResult<Value> {
+    let pages = client.get_all_pages().await?;
+    Ok(graph_statistics(&pages, Utc::now()))
+}
+
+/// Computes `get_graph_statistics` output from `getAllPages` output.
+fn graph_statistics(pages: &Value, now: DateTime<Utc>) -> Value {
+    let pages: Vec<&Value> = pages.as_array().into_iter().flatten().collect();
+    let week_ago = (now - Duration::days(7)).timestamp_millis();
+    let since_week_ago = |key: &str| {
+        pages
+            .iter()
+            .filter(|p| p[key].as_i64().is_some_and(|ts| ts >= week_ago))
+            .count()
+    };
+
+    let journal_pages = pages
+        .iter()
+        .filter(|p| p["journal?"].as_bool() == Some(true))
+        .count();
+    let namespaces: HashSet<String> = pages
+        .iter()
+        .filter_map(|p| p["name"].as_str())
+        .filter_map(|name| name.split_once('/'))
+        .map(|(namespace, _)| namespace.to_lowercase())
+        .collect();
+
+    serde_json::json!({
+        "total_pages": pages.len(),
+        "journal_pages": journal_pages,
+        "regular_pages": pages.len() - journal_pages,
+        "namespaces": namespaces.len(),
+        "recently_created": since_week_ago("createdAt"),
+        "recently_modified": since_week_ago("updatedAt")
+    })
+}
+
+/// Checks a UUID's format and whether a block with it exists.
+///
+/// Lets agents verify a UUID before passing it to a destructive tool. The
+/// graph is only queried when the format is valid.
+///
+/// # Parameters
+///
+/// - `uuid` (required): The UUID to check
+///
+/// # Returns
+///
+/// `{ "valid_format": bool, "exists_in_graph": bool, "block": <block or null> }`
+pub async fn validate_uuid(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let uuid = params["uuid"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("uuid parameter is required".to_string()))?;
+
+    let valid_format = is_block_uuid(uuid);
+    let block = if valid_format {
+        client.get_block(uuid).await?
+    } else {
+        Value::Null
+    };
+
+    Ok(serde_json::json!({
+        "valid_format": valid_format,
+        "exists_in_graph": !block.is_null(),
+        "block": block
+    }))
+}
+
+/// Exports a page's content as Org-mode text.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to export
+/// - `include_properties` (optional): Render `key:: value` properties as
+///   `:PROPERTIES:` drawers (default: true)
+///
+/// # Returns
+///
+/// `{ "page": name, "org": "..." }`; see [`crate::org_serializer`] for the
+/// conversion rules.
+pub async fn get_page_as_org(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let include_properties = params["include_properties"].as_bool().unwrap_or(true);
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let org = if include_properties {
+        block_tree_to_org(&blocks, 0)
+    } else {
+        block_tree_to_org_without_properties(&blocks, 0)
+    };
+
+    Ok(serde_json::json!({
+        "page": page_name,
+        "org": org
+    }))
+}
+
+/// Builds a table of contents from a page's heading blocks.
+///
+/// Headings are blocks whose first line is a markdown heading (`# `,
+/// `## `, ...), which gives their level, plus every non-empty block at
+/// nesting depth 1 or 2 and any block with `heading:: true`, which are
+/// levelled by their depth and titled by their first line. An outline page
+/// without markdown headings thus still gets its top two levels.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to index
+/// - `max_depth` (optional): Deepest heading level to include (default: 3)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `page`: The requested page name
+/// - `toc`: `[{ "level", "text", "uuid", "anchor" }]` in page order, where
+///   `anchor` is `#<uuid>`; empty if the page has no headings
+/// - `markdown`: The same entries as a nested list of `[text](#uuid)` links
+pub async fn generate_table_of_contents(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+    let max_depth = params["max_depth"].as_u64().unwrap_or(3) as usize;
+
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+    let mut toc = Vec::new();
+    collect_headings(&blocks, 1, max_depth, &mut toc);
+    let markdown = toc_markdown(&toc);
+
+    Ok(serde_json::json!({
+        "page": page_name,
+        "toc": toc,
+        "markdown": markdown
+    }))
+}
+
+/// Appends a TOC entry for each heading block up to `max_depth`, in page
+/// order. `depth` is the 1-based nesting depth of `blocks`.
+fn collect_headings(blocks: &Value, depth: usize, max_depth: usize, toc: &mut Vec<Value>) {
+    for block in blocks.as_array().into_iter().flatten() {
+        let text = heading_text(block).or_else(|| {
+            let content = block["content"].as_str().unwrap_or("");
+            let first_line = content.lines().next().unwrap_or("").trim();
+            (depth <= 2 && !first_line.is_empty()).then(|| first_line.to_string())
+        });
+        if let Some(text) = text {
+            let first_line = block["content"].as_str().unwrap_or("").trim_start();
+            let hashes = first_line.chars().take_while(|&c| c == '#').count();
+            let level = if (1..=6).contains(&hashes) {
+                hashes
+            } else {
+                depth
+            };
+            if level <= max_depth {
+                let uuid = block["uuid"].as_str().unwrap_or("");
+                toc.push(serde_json::json!({
+                    "level": level,
+                    "text": text,
+                    "uuid": uuid,
+                    "anchor": format!("#{}", uuid)
+                }));
+            }
+        }
+        collect_headings(&block["children"], depth + 1, max_depth, toc);
+    }
+}
+
+/// Renders TOC entries as a nested markdown list, indented relative to the
+/// shallowest level present.
+fn toc_markdown(toc: &[Value]) -> String {
+    let level = |entry: &Value| entry["level"].as_u64().unwrap_or(1) as usize;
+    let min_level = toc.iter().map(level).min().unwrap_or(1);
+    toc.iter()
+        .map(|entry| {
+            format!(
+                "{}- [{}]({})\n",
+                "  ".repeat(level(entry) - min_level),
+                entry["text"].as_str().unwrap_or(""),
+                entry["anchor"].as_str().unwrap_or("")
+            )
+        })
+        .collect()
+}
+
+/// Finds `[[links]]` whose target page doesn't exist.
+///
+/// Logseq creates an empty placeholder page for every link target, so a
+/// target only counts as existing if its page is backed by a file or is a
+/// journal. All block contents are read with one Datascript query (as in
+/// `get_graph_link_density`) rather than one block-tree call per page.
+///
+/// # Parameters
+///
+/// - `limit` (optional): Maximum broken links to return (default: 100)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `broken_links`: `[{ "link_target", "found_in_page",
+///   "found_in_block_uuid", "context" }]`, one per missing target (its
+///   first occurrence), ordered by page name
+/// - `count`: Number of broken links returned
+pub async fn find_broken_links(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let limit = params["limit"].as_u64().unwrap_or(100) as usize;
+
+    let pages = client.get_all_pages().await?;
+    let contents = client
+        .datascript_query(
+            "[:find ?uuid ?name ?c :where [?b :block/content ?c] [?b :block/uuid ?uuid] \
+             [?b :block/page ?p] [?p :block/original-name ?name]]",
+            Vec::new(),
+        )
+        .await?;
+
+    let broken = broken_links(&existing_page_names(&pages), &contents, limit);
+    Ok(serde_json::json!({
+        "count": broken.len(),
+        "broken_links": broken
+    }))
+}
+
+/// Lowercased names of pages that really exist (have a file or are journals).
+fn existing_page_names(pages: &Value) -> HashSet<String> {
+    pages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|p| !p["file"].is_null() || p["journal?"].as_bool() == Some(true))
+        .filter_map(|p| p["name"].as_str())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Scans `[uuid, page, content]` rows for links to pages not in `existing`,
+/// keeping the first occurrence of each target.
+fn broken_links(existing: &HashSet<String>, rows: &Value, limit: usize) -> Vec<Value> {
+    let mut rows: Vec<(&str, &str, &str)> = rows
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| Some((row[0].as_str()?, row[1].as_str()?, row[2].as_str()?)))
+        .collect();
+    rows.sort_by_key(|&(uuid, page, _)| (page, uuid));
+
+    let mut seen = HashSet::new();
+    let mut broken = Vec::new();
+    for (uuid, page, content) in rows {
+        for target in extract_page_links(content) {
+            let key = target.to_lowercase();
+            if existing.contains(&key) || !seen.insert(key) {
+                continue;
+            }
+            broken.push(serde_json::json!({
+                "link_target": target,
+                "found_in_page": page,
+                "found_in_block_uuid": uuid,
+                "context": backlink_context(content)
+            }));
+            if broken.len() >= limit {
+                return broken;
+            }
+        }
+    }
+    broken
+}
+
+/// Lists the current graph's favorite pages.
+///
+/// Favorites come back in the order the user arranged them in Logseq.
+/// Logseq only reports page names, so each page is looked up to fill in
+/// its UUID.
+///
+/// # Parameters
+///
+/// - `include_page_details` (optional): Include each favorite's full page
+///   object as `page` (default: false)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `favorites`: `[{ "name", "uuid", "page"? }]`; `uuid` is null for a
+///   favorite whose page no longer exists
+/// - `count`: Number of favorites
+pub async fn get_favorites(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let include_page_details = params["include_page_details"].as_bool().unwrap_or(false);
+
+    let names = favorite_names(&client.get_favorites().await?);
+    let pages = join_all(names.iter().map(|name| client.get_page(name))).await;
+
+    let mut favorites = Vec::with_capacity(names.len());
+    for (name, page) in names.into_iter().zip(pages) {
+        let page = page?;
+        let mut favorite = serde_json::json!({
+            "name": name,
+            "uuid": page["uuid"]
+        });
+        if include_page_details {
+            favorite["page"] = page;
+        }
+        favorites.push(favorite);
+    }
+
+    Ok(serde_json::json!({
+        "count": favorites.len(),
+        "favorites": favorites
+    }))
+}
+
+/// Page names from `getCurrentGraphFavorites`, which returns either names
+/// or page objects.
+fn favorite_names(favorites: &Value) -> Vec<String> {
+    favorites
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|fav| {
+            fav.as_str()
+                .or(fav["originalName"].as_str())
+                .or(fav["name"].as_str())
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+/// Finds blocks that carry a property, optionally with a given value.
+///
+/// Matches `key:: value` properties on blocks, including page-properties
+/// blocks, so `search_by_property` with `type` / `book` finds pages typed
+/// as books as well as individual blocks. Values are compared
+/// case-insensitively; a multi-value property such as `tags::` matches if
+/// any of its values does.
+///
+/// # Parameters
+///
+/// - `property_name` (required): Property key, e.g. `status` (case-insensitive)
+/// - `property_value` (optional): Only return blocks whose value matches
+/// - `exact_match` (optional): Compare whole values (default: true); when
+///   false, matches values containing `property_value` as a substring
+///
+/// # Returns
+///
+/// JSON object with the `property` name, the `value` filtered on (null when
+/// none), the matching `results` (`{uuid, content, page, value}`) and their
+/// `count`.
+///
+/// # Errors
+///
+/// Returns an error if `property_name` is missing or isn't a valid property
+/// key (letters, digits, `-`, `_`, `.` and `?`).
+pub async fn search_by_property(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let name = params["property_name"].as_str().ok_or_else(|| {
+        McpError::InvalidParams("property_name parameter is required".to_string())
+    })?;
+    let value = params["property_value"].as_str();
+    let exact = params["exact_match"].as_bool().unwrap_or(true);
+
+    // The key is spliced into the query as a keyword, so keep it to keyword characters
+    let key = name.trim().to_lowercase();
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '?'))
+    {
+        return Err(McpError::InvalidParams(format!(
+            "Invalid property name '{}'",
+            name
+        )));
+    }
+
+    let query = format!(
+        "[:find (pull ?b [:block/uuid :block/content {{:block/page [:block/original-name]}}]) ?v \
+         :where [?b :block/properties ?props] [(get ?props :{key}) ?v]]"
+    );
+    let rows = client.datascript_query(&query, Vec::new()).await?;
+
+    let results: Vec<Value> = rows
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|row| value.is_none_or(|wanted| property_value_matches(&row[1], wanted, exact)))
+        .map(|row| {
+            let block = &row[0];
+            serde_json::json!({
+                "uuid": block["uuid"],
+                "content": block["content"],
+                "page": block["page"]["originalName"],
+                "value": row[1]
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "property": key,
+        "value": value,
+        "count": results.len(),
+        "results": results
+    }))
+}
+
+/// Checks a stored property value against a wanted value, ignoring case.
+fn property_value_matches(stored: &Value, wanted: &str, exact: bool) -> bool {
+    let wanted = wanted.to_lowercase();
+    let matches = |text: &str| {
+        let text = text.to_lowercase();
+        if exact {
+            text == wanted
+        } else {
+            text.contains(&wanted)
+        }
+    };
+    match stored {
+        Value::String(s) => matches(s),
+        Value::Number(_) | Value::Bool(_) => matches(&stored.to_string()),
+        Value::Array(items) => items
+            .iter()
+            .any(|item| property_value_matches(item, &wanted, exact)),
+        _ => false,
+    }
+}
+
+/// Summarizes the content of a page's blocks.
+///
+/// # Parameters
+///
+/// - `page_name` (required): The page to analyze
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - `total_blocks` / `empty_blocks`: All blocks, and those with no content
+/// - `avg_content_length`: Mean length in characters of the non-empty blocks
+/// - `longest_block`: `{uuid, length}` of the longest block (null for an empty page)
+/// - `blocks_with_properties` / `blocks_with_children`: Block counts
+/// - `max_depth`: Deepest nesting level; top-level blocks are depth 1
+/// - `task_summary`: Task blocks per marker, e.g. `{"TODO": 3, "DONE": 5}`
+///
+/// # Errors
+///
+/// Returns an error if page_name is missing or the page doesn't exist.
+pub async fn get_block_statistics(client: &LogseqClient, params: Value) -> McpResult<Value> {
+    let page_name = params["page_name"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("page_name parameter is required".to_string()))?;
+
+    if client.get_page(page_name).await?.is_null() {
+        return Err(McpError::Internal(format!("Page not found: {}", page_name)));
+    }
+    let blocks = client.get_page_blocks_tree(page_name).await?;
+
+    let mut stats = BlockStats::default();
+    analyze_block_tree(&blocks, 1, &mut stats);
+    Ok(block_statistics_response(page_name, &stats))
+}
+
+fn block_statistics_response(page_name: &str, stats: &BlockStats) -> Value {
+    serde_json::json!({
+        "page_name": page_name,
+        "total_blocks": stats.total_blocks,
+        "empty_blocks": stats.empty_blocks,
+        "avg_content_length": stats.avg_content_length(),
+        "longest_block": stats
+            .longest_block
+            .as_ref()
+            .map(|(uuid, length)| serde_json::json!({"uuid": uuid, "length": length})),
+        "blocks_with_properties": stats.blocks_with_properties,
+        "blocks_with_children": stats.blocks_with_children,
+        "max_depth": stats.max_depth,
+        "task_summary": stats.task_counts
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use wiremock::matchers::{body_json, body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A client pointed at `server`.
+    fn test_client(server: &MockServer) -> LogseqClient {
+        LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            ..Config::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compute_week_bounds_spans_monday_to_sunday() {
+        let (start, end) = compute_week_bounds(0).unwrap();
+        assert_eq!(start.weekday(), chrono::Weekday::Mon);
+        assert_eq!(end.weekday(), chrono::Weekday::Sun);
+        assert_eq!((end - start).num_days(), 6);
+
+        let today = Local::now().date_naive();
+        assert!(start <= today && today <= end);
+    }
+
+    #[test]
+    fn test_compute_week_bounds_offsets() {
+        let (this_start, _) = compute_week_bounds(0).unwrap();
+        let (next_start, _) = compute_week_bounds(1).unwrap();
+        let (last_start, _) = compute_week_bounds(-1).unwrap();
+        assert_eq!((next_start - this_start).num_days(), 7);
+        assert_eq!((this_start - last_start).num_days(), 7);
+    }
+
+    #[test]
+    fn test_compute_week_bounds_rejects_overflow() {
+        for offset in [i64::MAX, i64::MIN, 100_000_000] {
+            assert!(matches!(
+                compute_week_bounds(offset),
+                Err(McpError::InvalidParams(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_scheduled_items_extracts_both_kinds_in_range() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 1, 21).unwrap();
+        let results = serde_json::json!([
+            [{"uuid": "a", "content": "TODO a", "scheduled": 20240116, "deadline": 20240130,
+              "page": {"originalName": "Work"}}],
+            [{"uuid": "b", "content": "TODO b", "deadline": 20240121}]
+        ]);
+        let items = scheduled_items(&results, start, end);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["date"], "2024-01-16");
+        assert_eq!(items[0]["type"], "scheduled");
+        assert_eq!(items[0]["page"], "Work");
+        assert_eq!(items[1]["type"], "deadline");
+    }
+
+    #[test]
+    fn test_is_card_block_by_property_or_tag() {
+        let by_prop = serde_json::json!({"content": "Q", "properties": {"card": true}});
+        let by_tag = serde_json::json!({"content": "What is Rust? #card", "properties": {}});
+        let plain = serde_json::json!({"content": "Just a note", "properties": {}});
+        assert!(is_card_block(&by_prop, "card"));
+        assert!(is_card_block(&by_tag, "card"));
+        assert!(!is_card_block(&plain, "card"));
+        assert!(!is_card_block(&by_prop, "flashcard"));
+    }
+
+    #[test]
+    fn test_card_text_strips_tag_and_properties() {
+        let content = "What is Rust? #card\ncard-last-interval:: 4";
+        assert_eq!(card_text(content, "card"), "What is Rust?");
+    }
+
+    #[test]
+    fn test_property_list_handles_arrays_and_strings() {
+        assert_eq!(
+            property_list(&serde_json::json!(["rust", "lang"])),
+            vec!["rust", "lang"]
+        );
+        assert_eq!(
+            property_list(&serde_json::json!("[[rust]], lang")),
+            vec!["rust", "lang"]
+        );
+        assert!(property_list(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_hash_block_tree_is_order_independent() {
+        let a = serde_json::json!([
+            {"uuid": "1", "content": "first", "children": [{"uuid": "3", "content": "child"}]},
+            {"uuid": "2", "content": "second"}
+        ]);
+        let b = serde_json::json!([
+            {"uuid": "2", "content": "second"},
+            {"uuid": "1", "content": "first", "children": [{"uuid": "3", "content": "child"}]}
+        ]);
+        let (hash_a, count) = hash_block_tree(&a);
+        assert_eq!(hash_a, hash_block_tree(&b).0);
+        assert_eq!(count, 3);
+        assert_eq!(hash_a.len(), 64);
+    }
+
+    #[test]
+    fn test_hash_block_tree_detects_content_change() {
+        let before = serde_json::json!([{"uuid": "1", "content": "draft"}]);
+        let after = serde_json::json!([{"uuid": "1", "content": "final"}]);
+        assert_ne!(hash_block_tree(&before).0, hash_block_tree(&after).0);
+    }
+
+    #[test]
+    fn test_datalog_string_escapes_quotes_and_backslashes() {
+        assert_eq!(datalog_string("plain"), "\"plain\"");
+        assert_eq!(datalog_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(datalog_string(r"C:\temp"), r#""C:\\temp""#);
+    }
+
+    #[test]
+    fn test_block_content_query_modes() {
+        let exact = block_content_query("Buy milk", true, None);
+        assert!(exact.contains(r#"[(= ?c "Buy milk")]"#));
+        assert!(!exact.contains(":block/name"));
+
+        let fuzzy = block_content_query("milk", false, Some("Groceries"));
+        assert!(fuzzy.contains(r#"clojure.string/includes? ?c "milk""#));
+        assert!(fuzzy.contains(r#"[?p :block/name "groceries"]"#));
+    }
+
+    #[test]
+    fn test_count_by_day_orders_busiest_first() {
+        let day = |d: u32| {
+            NaiveDate::from_ymd_opt(2024, 1, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .timestamp_millis()
+        };
+        let days = count_by_day([day(15), day(16), day(16), day(17), day(16)].into_iter());
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0], (NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(), 3));
+        // Ties are broken by most recent date first
+        assert_eq!(days[1].0, NaiveDate::from_ymd_opt(2024, 1, 17).unwrap());
+    }
+
+    #[test]
+    fn test_pages_updated_after_paginates_oldest_first() {
+        let pages = serde_json::json!([
+            {"name": "c", "uuid": "u-c", "updatedAt": 300},
+            {"name": "old", "uuid": "u-old", "updatedAt": 50},
+            {"name": "a", "uuid": "u-a", "updatedAt": 100},
+            {"name": "b", "uuid": "u-b", "updatedAt": 200},
+            {"name": "no-timestamp"}
+        ]);
+
+        let (first, has_more) = pages_updated_after(&pages, 60, None, 2);
+        assert!(has_more);
+        assert_eq!(first[0]["name"], "a");
+        assert_eq!(first[1]["name"], "b");
+
+        let (rest, has_more) = pages_updated_after(&pages, 60, Some((200, "u-b")), 2);
+        assert!(!has_more);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0]["name"], "c");
+    }
+
+    #[tokio::test]
+    async fn test_pages_updated_since_keeps_ties_across_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"name": "d", "uuid": "u-d", "updatedAt": 200},
+                {"name": "b", "uuid": "u-b", "updatedAt": 100},
+                {"name": "a", "uuid": "u-a", "updatedAt": 100},
+                {"name": "c", "uuid": "u-c", "updatedAt": 100}
+            ])))
+            .mount(&server)
+            .await;
+        let client = test_client(&server);
+
+        // Three pages share updatedAt 100, and the limit falls between them
+        let mut names = Vec::new();
+        let mut cursor = Value::Null;
+        loop {
+            let result = get_pages_updated_since(
+                &client,
+                serde_json::json!({"timestamp": 0, "limit": 2, "cursor": cursor}),
+            )
+            .await
+            .unwrap();
+            for page in result["pages"].as_array().unwrap() {
+                names.push(page["name"].as_str().unwrap().to_string());
+            }
+            if result["has_more"] == false {
+                assert!(result["next_cursor"].is_null());
+                break;
+            }
+            cursor = result["next_cursor"].clone();
+        }
+        assert_eq!(names, ["a", "b", "c", "d"]);
+
+        let err = get_pages_updated_since(
+            &client,
+            serde_json::json!({"timestamp": 0, "cursor": "nonsense"}),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, McpError::InvalidParams(_)));
+    }
+
+    #[test]
+    fn test_complexity_factors() {
+        let blocks = serde_json::json!([
+            {"content": "See [[Rust]] and https://rust-lang.org", "children": [
+                {"content": "TODO learn", "marker": "TODO", "children": [
+                    {"content": "```rust\nfn main() {}\n```"}
+                ]}
+            ]},
+            {"content": "| a | b |\n| 1 | 2 |"}
+        ]);
+        let factors = complexity_factors(&blocks);
+        assert_eq!(factors["depth"], 18);
+        assert_eq!(factors["block_count"], 2);
+        assert_eq!(factors["links"], 2);
+        assert_eq!(factors["tasks"], 2);
+        assert_eq!(factors["rich_content"], 10);
+    }
+
+    #[test]
+    fn test_tree_depth_of_empty_page_is_zero() {
+        assert_eq!(tree_depth(&serde_json::json!([])), 0);
+        assert_eq!(tree_depth(&Value::Null), 0);
+    }
+
+    #[test]
+    fn test_parse_property_history() {
+        let log = "\x1eabc123 2024-02-01T10:00:00+00:00\n\
+                   diff --git a/pages/p.md b/pages/p.md\n\
+                   --- a/pages/p.md\n\
+                   +++ b/pages/p.md\n\
+                   @@ -1,2 +1,2 @@\n\
+                   -status:: draft\n\
+                   +status:: published\n\
+                   \x1edef456 2024-01-01T10:00:00+00:00\n\
+                   +status:: draft\n\
+                   +title:: Page\n\
+                   \x1eghi789 2023-12-01T10:00:00+00:00\n\
+                   +unrelated:: value\n";
+        let history = parse_property_history(log, "status");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0]["commit"], "abc123");
+        assert_eq!(history[0]["from_value"], "draft");
+        assert_eq!(history[0]["to_value"], "published");
+        assert_eq!(history[1]["date"], "2024-01-01T10:00:00+00:00");
+        assert!(history[1]["from_value"].is_null());
+    }
+
+    #[test]
+    fn test_content_statistics() {
+        let blocks = serde_json::json!([
+            {"content": "Rust is fast. Rust is safe!", "children": [
+                {"content": "type:: note"}
+            ]},
+            {"content": "I like Rust"}
+        ]);
+        let stats = content_statistics(&blocks);
+        assert_eq!(stats["blocks_analyzed"], 2);
+        assert_eq!(stats["avg_words_per_block"], 4.5);
+        assert_eq!(stats["avg_sentence_length"], 3.0);
+        assert_eq!(stats["longest_block_words"], 6);
+        assert_eq!(stats["common_words"][0]["word"], "rust");
+        assert_eq!(stats["common_words"][0]["count"], 3);
+    }
+
+    #[test]
+    fn test_collect_uuids_respects_depth_limit() {
+        let blocks = serde_json::json!([
+            {"uuid": "a", "children": [{"uuid": "a1", "children": [{"uuid": "a1x"}]}]},
+            {"uuid": "b"}
+        ]);
+        let mut all = Vec::new();
+        collect_uuids(&blocks, 1, usize::MAX, &mut all);
+        assert_eq!(all, vec!["a", "a1", "a1x", "b"]);
+
+        let mut shallow = Vec::new();
+        collect_uuids(&blocks, 1, 2, &mut shallow);
+        assert_eq!(shallow, vec!["a", "a1", "b"]);
+    }
+
+    #[test]
+    fn test_completion_trend_daily_and_weekly() {
+        let ts = |y, m, d| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .timestamp_millis()
+        };
+        // Monday 2024-01-15 through Sunday 2024-01-28
+        let start = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 1, 28).unwrap();
+        let done = [
+            ts(2024, 1, 15),
+            ts(2024, 1, 16),
+            ts(2024, 1, 22),
+            ts(2023, 12, 1),
+        ];
+        let created = [ts(2024, 1, 15), ts(2024, 1, 15)];
+
+        let daily = completion_trend(&done, &created, start, end, false);
+        assert_eq!(daily.len(), 14);
+        assert_eq!(daily[0]["period"], "2024-01-15");
+        assert_eq!(daily[0]["completed"], 1);
+        assert_eq!(daily[0]["created"], 2);
+        assert_eq!(daily[2]["completed"], 0);
+
+        let weekly = completion_trend(&done, &created, start, end, true);
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[0]["completed"], 2);
+        assert_eq!(weekly[1]["period"], "2024-01-22");
+        assert_eq!(weekly[1]["completed"], 1);
+    }
+
+    #[test]
+    fn test_parse_markdown_table_skips_separator() {
+        let content = "Budget\n| Item | Cost |\n|------|-----:|\n| Rent | 1,000 |";
+        let rows = parse_markdown_table(content).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], vec!["Item", "Cost"]);
+        assert_eq!(rows[1], vec!["Rent", "1,000"]);
+        assert!(parse_markdown_table("no table here").is_none());
+    }
+
+    #[test]
+    fn test_rows_to_csv_quotes_special_fields() {
+        let rows = vec![
+            vec!["name".to_string(), "note".to_string()],
+            vec!["a,b".to_string(), r#"say "hi""#.to_string()],
+        ];
+        assert_eq!(rows_to_csv(&rows), "name,note\n\"a,b\",\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_kanban_columns_from_headings() {
+        let blocks = serde_json::json!([
+            {"content": "## Doing", "children": [
+                {"uuid": "t1", "content": "DOING write", "marker": "DOING", "priority": "A"},
+                {"uuid": "n1", "content": "a note"}
+            ]},
+            {"content": "## Done", "children": [
+                {"uuid": "t2", "content": "DONE ship", "marker": "DONE", "scheduled": 20240115}
+            ]}
+        ]);
+        let columns = kanban_columns(&blocks);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0]["name"], "Doing");
+        assert_eq!(columns[0]["tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(columns[0]["tasks"][0]["priority"], "A");
+        assert_eq!(columns[1]["tasks"][0]["scheduled"], "2024-01-15");
+    }
+
+    #[test]
+    fn test_kanban_columns_without_headings_uses_backlog() {
+        let blocks = serde_json::json!([
+            {"uuid": "t1", "content": "TODO one", "marker": "TODO"},
+            {"uuid": "n1", "content": "#tag is not a heading"}
+        ]);
+        let columns = kanban_columns(&blocks);
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0]["name"], "Backlog");
+        assert_eq!(columns[0]["tasks"][0]["uuid"], "t1");
+    }
+
+    #[test]
+    fn test_find_block_refs_inline_and_embed() {
+        let a = "6571a2b0-1c2d-4e5f-8a9b-0c1d2e3f4a5b";
+        let b = "6571a2b0-1c2d-4e5f-8a9b-0c1d2e3f4a5c";
+        let content = format!("See (({a})) and {{{{embed (({b}))}}}} but not ((text))");
+        let refs = find_block_refs(&content);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].uuid, a);
+        assert!(!refs[0].embed);
+        assert_eq!(&content[refs[0].start..refs[0].end], format!("(({a}))"));
+        assert!(refs[1].embed);
+        assert_eq!(
+            &content[refs[1].start..refs[1].end],
+            format!("{{{{embed (({b}))}}}}")
+        );
+    }
+
+    #[test]
+    fn test_count_scripts_ignores_non_letters() {
+        let mut counts = HashMap::new();
+        count_scripts("Hello, мир! 你好 123 مرحبا", &mut counts);
+        assert_eq!(counts["latin"], 5);
+        assert_eq!(counts["cyrillic"], 3);
+        assert_eq!(counts["cjk"], 2);
+        assert_eq!(counts["arabic"], 5);
+        assert!(!counts.contains_key("other"));
+    }
+
+    #[test]
+    fn test_extract_code_fences() {
+        let content = "Example:\n```Rust\nfn main() {\n}\n```\ntext\n```\nplain text\n```";
+        let fences = extract_code_fences(content);
+        assert_eq!(fences.len(), 2);
+        assert_eq!(
+            fences[0],
+            ("rust".to_string(), "fn main() {\n}".to_string())
+        );
+        assert_eq!(fences[1].0, "plain");
+        assert!(extract_code_fences("no code").is_empty());
+    }
+
+    #[test]
+    fn test_diff_lines_and_unified_output() {
+        let old = "- one\n- two\n- three";
+        let new = "- one\n- 2\n- three\n- four";
+        let diff = diff_lines(old, new);
+        let expected = [
+            (' ', "- one"),
+            ('-', "- two"),
+            ('+', "- 2"),
+            (' ', "- three"),
+            ('+', "- four"),
+        ];
+        assert_eq!(
+            diff,
+            expected.map(|(op, line)| (op, line.to_string())).to_vec()
+        );
+        let unified = unified_diff(&diff, "pages/p.md", "abc");
+        assert!(unified.starts_with("--- a/pages/p.md (abc)\n+++ b/pages/p.md (HEAD)\n"));
+        assert!(unified.contains("@@ -1,3 +1,4 @@"));
+    }
+
+    #[test]
+    fn test_extract_page_links() {
+        assert_eq!(
+            extract_page_links("See [[Rust]] and [[ Project Plan ]], not [link](url)"),
+            vec!["Rust", "Project Plan"]
+        );
+        assert!(extract_page_links("[[]] nothing").is_empty());
+    }
+
+    #[test]
+    fn test_extract_page_links_edge_cases() {
+        // Only the innermost link of nested brackets is a page
+        assert_eq!(
+            extract_page_links("[[outer [[inner]] text]]"),
+            vec!["inner"]
+        );
+        // Unclosed and single-bracket forms aren't links
+        assert!(extract_page_links("[[unclosed and [single]").is_empty());
+        // Backslashes and other punctuation are part of the name
+        assert_eq!(
+            extract_page_links(r"[[C\+\+]] [[a/b: c?]]"),
+            vec![r"C\+\+", "a/b: c?"]
+        );
+        assert_eq!(extract_page_links("[[A]][[B]]"), vec!["A", "B"]);
+        assert_eq!(extract_page_links("#[[Tag Page]]"), vec!["Tag Page"]);
+    }
+
+    #[test]
+    fn test_outgoing_and_incoming_links() {
+        let blocks = serde_json::json!([
+            {"content": "See [[Rust]] and [[Async]]", "children": [
+                {"content": "More on [[rust]]"}
+            ]},
+            {"content": "No links"}
+        ]);
+        assert_eq!(outgoing_links(&blocks), vec!["Rust", "Async"]);
+
+        let refs = serde_json::json!([
+            [{"name": "tokio", "originalName": "Tokio"}, [{"uuid": "a"}]],
+            [{"name": "jan 1st, 2025"}, [{"uuid": "b"}]]
+        ]);
+        assert_eq!(referencing_pages(&refs), vec!["Tokio", "jan 1st, 2025"]);
+    }
+
+    #[test]
+    fn test_link_density_summary() {
+        let counts = vec![
+            ("a".to_string(), 0),
+            ("b".to_string(), 3),
+            ("c".to_string(), 12),
+            ("d".to_string(), 7),
+        ];
+        let summary = link_density_summary(counts);
+        assert_eq!(summary["average_links_per_page"], 5.5);
+        assert_eq!(summary["median_links"], 5.0);
+        assert_eq!(
+            summary["distribution"],
+            serde_json::json!({"0": 1, "1-5": 1, "6-10": 1, "11+": 1})
+        );
+        assert_eq!(summary["most_connected_page"], "c");
+        assert_eq!(summary["least_connected_pages"], serde_json::json!(["a"]));
+    }
+
+    #[test]
+    fn test_check_datalog_syntax() {
+        assert!(check_datalog_syntax("[:find ?n :where [?b :block/name ?n]]").is_ok());
+        assert!(check_datalog_syntax(r#"[:find ?b :where [?b :block/content "a ] b"]]"#).is_ok());
+        assert!(check_datalog_syntax("find ?n").is_err());
+        assert!(check_datalog_syntax("[:find ?n :where [?b :block/name ?n]").is_err());
+        assert!(check_datalog_syntax("[:find ?n :where (?b :block/name ?n]]").is_err());
+        assert!(check_datalog_syntax("[:where [?b :block/name ?n]]").is_err());
+    }
+
+    #[test]
+    fn test_page_properties_response_absent() {
+        let out = page_properties_response("Empty", serde_json::json!(null));
+        assert_eq!(
+            out,
+            serde_json::json!({"page": "Empty", "properties": {}, "tags": []})
+        );
+    }
+
+    #[test]
+    fn test_page_properties_response_present() {
+        let out = page_properties_response(
+            "Rust",
+            serde_json::json!({"tags": ["programming", "lang"], "status": "active"}),
+        );
+        assert_eq!(out["page"], "Rust");
+        assert_eq!(out["properties"]["status"], "active");
+        assert_eq!(out["tags"], serde_json::json!(["programming", "lang"]));
+
+        let out = page_properties_response("Rust", serde_json::json!({"tags": "[[a]], b"}));
+        assert_eq!(out["tags"], serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_journal_pages_filters_and_sorts() {
+        let pages = serde_json::json!([
+            {"name": "jan 1st, 2025", "journal?": true, "journalDay": 20250101},
+            {"name": "rust", "journal?": false},
+            {"name": "jan 15th, 2025", "journal?": true, "journalDay": 20250115},
+            {"name": "dec 31st, 2024", "journal?": true, "journalDay": 20241231}
+        ]);
+
+        let all = journal_pages(&pages, None, None);
+        let names: Vec<&str> = all.iter().filter_map(|p| p["name"].as_str()).collect();
+        assert_eq!(names, ["jan 15th, 2025", "jan 1st, 2025", "dec 31st, 2024"]);
+
+        let since = NaiveDate::from_ymd_opt(2025, 1, 1);
+        assert_eq!(journal_pages(&pages, since, None).len(), 2);
+        assert_eq!(
+            journal_pages(&pages, None, Some(1))[0]["journalDay"],
+            20250115
+        );
+    }
+
+    fn sample_pages() -> Value {
+        serde_json::json!([
+            {"name": "projects"},
+            {"name": "projects/2025"},
+            {"name": "projects/archive/old"},
+            {"name": "projectsx"},
+            {"name": "jan 1st, 2025", "journal?": true}
+        ])
+    }
+
+    fn page_filter(namespace: Option<&str>, include_journals: bool) -> PageFilter {
+        PageFilter {
+            name_contains: None,
+            namespace: namespace.map(str::to_string),
+            include_journals,
+        }
+    }
+
+    #[test]
+    fn test_page_filter_namespace() {
+        let pages = sample_pages();
+        let names: Vec<&str> = page_filter(Some("projects"), true)
+            .apply(&pages)
+            .iter()
+            .filter_map(|p| p["name"].as_str())
+            .collect();
+        assert_eq!(names, ["projects/2025", "projects/archive/old"]);
+
+        assert!(page_filter(Some("areas"), true).apply(&pages).is_empty());
+    }
+
+    #[test]
+    fn test_page_filter_excludes_journals() {
+        let pages = sample_pages();
+        assert_eq!(page_filter(None, true).apply(&pages).len(), 5);
+        assert_eq!(page_filter(None, false).apply(&pages).len(), 4);
+    }
+
+    #[test]
+    fn test_list_pages_pagination_edges() {
+        let pages = sample_pages();
+        let matching = page_filter(None, true).apply(&pages);
+        assert!(paginate(matching.clone(), 10, 100).is_empty());
+        assert!(paginate(matching.clone(), 0, 0).is_empty());
+        assert_eq!(paginate(matching.clone(), 4, 100).len(), 1);
+        assert_eq!(paginate(matching, 1, 2)[0]["name"], "projects/2025");
+    }
+
+    #[test]
+    fn test_search_items_flattens_blocks_and_pages() {
+        let raw = serde_json::json!({
+            "blocks": [{"block/uuid": "u1", "block/content": "rust notes"}],
+            "pages": ["Rust", "Rustaceans"],
+            "files": []
+        });
+        let items = search_items(raw);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0]["type"], "block");
+        assert_eq!(items[0]["block/uuid"], "u1");
+        assert_eq!(
+            items[1],
+            serde_json::json!({"type": "page", "name": "Rust"})
+        );
+
+        assert!(search_items(Value::Null).is_empty());
+        let legacy = search_items(serde_json::json!([{"content": "x"}]));
+        assert_eq!(legacy[0]["type"], "block");
+    }
+
+    #[test]
+    fn test_parent_block_ref() {
+        let top_level = serde_json::json!({"parent": {"id": 10}, "page": {"id": 10}});
+        assert_eq!(parent_block_ref(&top_level), None);
+
+        let nested = serde_json::json!({"parent": {"id": 42}, "page": {"id": 10}});
+        assert_eq!(parent_block_ref(&nested), Some(BlockParent::Id(42)));
+
+        let with_uuid =
+            serde_json::json!({"parent": {"id": 42, "uuid": "p-uuid"}, "page": {"id": 10}});
+        assert_eq!(
+            parent_block_ref(&with_uuid),
+            Some(BlockParent::Uuid("p-uuid".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_collect_tags() {
+        let pages = serde_json::json!([
+            {"name": "rust", "properties": {"tags": ["Programming", "lang"]}},
+            {"name": "go", "properties": {"tags": "programming"}},
+            {"name": "empty"}
+        ]);
+        let blocks = serde_json::json!([
+            ["Learning #rust today #[[Side Project]]", "Journal"],
+            ["# Heading, see https://example.com/#anchor", "Notes"],
+            ["#lang again", "rust"]
+        ]);
+
+        let tags = collect_tags(&pages, &blocks);
+        let names: Vec<&str> = tags.keys().map(String::as_str).collect();
+        assert_eq!(names, ["lang", "programming", "rust", "side project"]);
+        assert_eq!(tags["programming"].len(), 2);
+        assert_eq!(tags["lang"].len(), 1);
+        assert!(tags["rust"].contains("journal"));
+    }
+
+    #[test]
+    fn test_has_no_references() {
+        assert!(has_no_references(&serde_json::json!([])));
+        assert!(has_no_references(&Value::Null));
+        assert!(has_no_references(&serde_json::json!([[{"name": "a"}, []]])));
+        assert!(!has_no_references(&serde_json::json!([
+            [{"name": "a"}, [{"content": "see [[b]]"}]]
+        ])));
+    }
+
+    fn path_tree() -> Value {
+        serde_json::json!([
+            {"uuid": "a", "content": "Section\nmore text", "children": [
+                {"uuid": "b", "content": "Subsection", "children": [
+                    {"uuid": "c", "content": "Leaf", "children": []}
+                ]}
+            ]},
+            {"uuid": "d", "content": "Other", "children": []}
+        ])
+    }
+
+    #[test]
+    fn test_block_path_nested() {
+        let path = block_path(&path_tree(), "c").unwrap();
+        assert_eq!(
+            path,
+            vec![
+                serde_json::json!({"uuid": "a", "content": "Section"}),
+                serde_json::json!({"uuid": "b", "content": "Subsection"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_path_top_level_and_missing() {
+        assert_eq!(block_path(&path_tree(), "d"), Some(Vec::new()));
+        assert_eq!(block_path(&path_tree(), "zzz"), None);
+    }
+
+    #[test]
+    fn test_collect_block_texts() {
+        let tree = serde_json::json!([
+            {"content": "One", "children": [{"content": "Two"}]},
+            {"content": "Three"}
+        ]);
+        assert_eq!(collect_block_texts(&tree), vec!["One", "Two", "Three"]);
+    }
+
+    #[test]
+    fn test_text_stats_uses_unicode_words() {
+        let texts = vec![
+            "Hello, world!".to_string(),
+            "naïve café — déjà vu".to_string(),
+        ];
+        assert_eq!(
+            TextStats::of(&texts),
+            TextStats {
+                words: 6,
+                blocks: 2,
+                characters: 33
+            }
+        );
+        assert_eq!(TextStats::of(&[]).words, 0);
+    }
+
+    #[test]
+    fn test_recently_modified_filters_and_sorts() {
+        let pages = serde_json::json!([
+            {"name": "old", "uuid": "u1", "updatedAt": 1_000_000_000_000_i64},
+            {"name": "newer", "originalName": "Newer", "uuid": "u2", "updatedAt": 1_700_000_100_000_i64},
+            {"name": "newest", "uuid": "u3", "updatedAt": 1_700_000_200_000_i64},
+            {"name": "no-timestamp", "uuid": "u4"}
+        ]);
+        let since = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+
+        let recent = recently_modified(&pages, since, 10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0]["name"], "newest");
+        assert_eq!(recent[1]["name"], "Newer");
+        assert_eq!(recent[1]["updated_at"], "2023-11-14T22:15:00+00:00");
+
+        assert_eq!(recently_modified(&pages, since, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_tasks_with_marker() {
+        let items = search_items(serde_json::json!({
+            "blocks": [
+                {"block/uuid": "a", "block/content": "TODO write docs", "block/page": 7},
+                {"uuid": "b", "content": "  TODO\nindented", "page": 8},
+                {"uuid": "c", "content": "Remember the TODO list"},
+                {"uuid": "d", "content": "TODOS are not tasks"},
+                {"uuid": "e", "content": "LATER not this marker"}
+            ],
+            "pages": ["TODO"]
+        }));
+
+        let tasks = tasks_with_marker(items, "TODO");
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks[0],
+            serde_json::json!({"uuid": "a", "content": "TODO write docs", "page": 7, "marker": "TODO"})
+        );
+        assert_eq!(tasks[1]["uuid"], "b");
+    }
+
+    #[test]
+    fn test_namespace_pages() {
+        let pages = serde_json::json!([
+            {"name": "projects/2025/q1", "originalName": "Projects/2025/Q1", "uuid": "a"},
+            {"name": "projects/alpha", "originalName": "Projects/Alpha", "uuid": "b"},
+            {"name": "projects", "originalName": "Projects", "uuid": "c"},
+            {"name": "projectsx/other", "uuid": "d"},
+            {"name": "areas/projects/x", "uuid": "e"}
+        ]);
+
+        let all = namespace_pages(&pages, "projects", true);
+        assert_eq!(
+            all,
+            vec![
+                serde_json::json!({"name": "2025/Q1", "full_name": "Projects/2025/Q1", "uuid": "a"}),
+                serde_json::json!({"name": "Alpha", "full_name": "Projects/Alpha", "uuid": "b"}),
+            ]
+        );
+
+        let direct = namespace_pages(&pages, "PROJECTS", false);
+        assert_eq!(direct.len(), 1);
+        assert_eq!(direct[0]["name"], "Alpha");
+
+        assert!(namespace_pages(&pages, "projects/2025/q1", true).is_empty());
+    }
+
+    #[test]
+    fn test_backlinks_flattens_pairs() {
+        let refs = serde_json::json!([
+            [{"name": "rust", "originalName": "Rust"}, [
+                {"uuid": "b1", "content": "Learning  [[Async]]\nin depth"},
+                {"uuid": "b2", "content": "See [[Async]]"}
+            ]],
+            [{"name": "jan 1st, 2025", "journal?": true}, [
+                {"uuid": "b3", "content": "Read about [[Async]]"}
+            ]]
+        ]);
+
+        let all = backlinks(&refs, true);
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            all[0],
+            serde_json::json!({
+                "source_page": "Rust",
+                "source_uuid": "b1",
+                "context": "Learning [[Async]] in depth"
+            })
+        );
+        assert_eq!(all[2]["source_page"], "jan 1st, 2025");
+
+        let pages_only = backlinks(&refs, false);
+        assert_eq!(pages_only.len(), 2);
+        assert!(backlinks(&serde_json::json!([]), true).is_empty());
+    }
+
+    #[test]
+    fn test_backlink_context_truncates() {
+        let long = "word ".repeat(100);
+        let context = backlink_context(&long);
+        assert!(context.ends_with("word…"));
+        assert_eq!(context.chars().count(), BACKLINK_CONTEXT_CHARS);
+        assert_eq!(backlink_context("  short  "), "short");
+    }
+
+    #[test]
+    fn test_config_summary() {
+        let summary = config_summary(&serde_json::json!({
+            "preferredFormat": "Org",
+            "feature/enable-block-timestamps?": true,
+            "preferredLanguage": "en"
+        }));
+        assert_eq!(
+            summary,
+            serde_json::json!({
+                "preferred_format": "org",
+                "journal_file_name_format": "yyyy_MM_dd",
+                "enable_block_timestamps": true
+            })
+        );
+
+        let defaults = config_summary(&serde_json::json!({}));
+        assert_eq!(defaults["preferred_format"], "markdown");
+        assert_eq!(defaults["enable_block_timestamps"], false);
+    }
+
+    #[test]
+    fn test_version_info_tolerates_missing_fields() {
+        let info = version_info(&serde_json::json!({"version": "0.10.9", "supportDb": false}));
+        assert_eq!(info["version"], "0.10.9");
+        assert!(info["build"].is_null());
+        assert!(info["platform"].is_null());
+        assert_eq!(info["mcp_server_version"], SERVER_VERSION);
+
+        let info = version_info(&Value::Null);
+        assert!(info["version"].is_null());
+    }
+
+    #[test]
+    fn test_graph_statistics() {
+        let now = Utc::now();
+        let recent = (now - Duration::days(1)).timestamp_millis();
+        let old = (now - Duration::days(30)).timestamp_millis();
+        let pages = serde_json::json!([
+            {"name": "projects/alpha", "createdAt": old, "updatedAt": recent},
+            {"name": "projects/beta", "createdAt": recent, "updatedAt": recent},
+            {"name": "areas/health", "createdAt": old, "updatedAt": old},
+            {"name": "rust"},
+            {"name": "jan 1st, 2025", "journal?": true, "createdAt": old}
+        ]);
+
+        assert_eq!(
+            graph_statistics(&pages, now),
+            serde_json::json!({
+                "total_pages": 5,
+                "journal_pages": 1,
+                "regular_pages": 4,
+                "namespaces": 2,
+                "recently_created": 1,
+                "recently_modified": 2
+            })
+        );
+        assert_eq!(
+            graph_statistics(&serde_json::json!(null), now)["total_pages"],
+            0
+        );
+    }
+
+    #[test]
+    fn test_table_of_contents_levels() {
+        let blocks = serde_json::json!([
+            {"uuid": "a", "content": "# Intro", "children": [
+                {"uuid": "b", "content": "## Background\nmore text"},
+                {"uuid": "c", "content": "plain block", "children": [
+                    {"uuid": "d", "content": "#### Too deep"}
+                ]}
+            ]},
+            {"uuid": "e", "content": "Summary\nwith details", "children": [
+                {"uuid": "g", "content": "  "},
+                {"uuid": "h", "content": "Notes", "children": [
+                    {"uuid": "i", "content": "third level text"},
+                    {"uuid": "j", "content": "Flagged", "properties": {"heading": true}}
+                ]}
+            ]},
+            {"uuid": "f", "content": "#tag is not a markdown heading"}
+        ]);
+
+        let mut toc = Vec::new();
+        collect_headings(&blocks, 1, 3, &mut toc);
+        let entry = |level: usize, text: &str, uuid: &str| {
+            serde_json::json!({
+                "level": level, "text": text, "uuid": uuid, "anchor": format!("#{uuid}")
+            })
+        };
+        assert_eq!(
+            toc,
+            vec![
+                entry(1, "Intro", "a"),
+                entry(2, "Background", "b"),
+                entry(2, "plain block", "c"),
+                entry(1, "Summary", "e"),
+                entry(2, "Notes", "h"),
+                entry(3, "Flagged", "j"),
+                entry(1, "#tag is not a markdown heading", "f"),
+            ]
+        );
+        assert_eq!(
+            toc_markdown(&toc[..4]),
+            "- [Intro](#a)\n  - [Background](#b)\n  - [plain block](#c)\n- [Summary](#e)\n"
+        );
+
+        // Depth 1 and 2 only
+        let mut top = Vec::new();
+        collect_headings(&blocks, 1, 2, &mut top);
+        assert_eq!(top.len(), 6);
+
+        let mut all = Vec::new();
+        collect_headings(&blocks, 1, 6, &mut all);
+        assert_eq!(all.len(), 8);
+    }
+
+    #[test]
+    fn test_table_of_contents_without_headings() {
+        let mut toc = Vec::new();
+        collect_headings(&serde_json::json!([]), 1, 3, &mut toc);
+        assert!(toc.is_empty());
+        assert_eq!(toc_markdown(&toc), "");
+
+        // Blank outline blocks and plain text below depth 2 aren't headings
+        let mut toc = Vec::new();
+        collect_headings(
+            &serde_json::json!([{"uuid": "a", "content": "", "children": [
+                {"uuid": "b", "content": " ", "children": [
+                    {"uuid": "c", "content": "just text"}
+                ]}
+            ]}]),
+            1,
+            3,
+            &mut toc,
+        );
+        assert!(toc.is_empty());
+        assert_eq!(toc_markdown(&toc), "");
+
+        // Sub-headings only: indentation starts at the shallowest level
+        let mut toc = Vec::new();
+        collect_headings(
+            &serde_json::json!([{"uuid": "x", "content": "### Deep"}]),
+            1,
+            3,
+            &mut toc,
+        );
+        assert_eq!(toc_markdown(&toc), "- [Deep](#x)\n");
+    }
+
+    #[test]
+    fn test_broken_links() {
+        let pages = serde_json::json!([
+            {"name": "rust", "file": {"id": 1}},
+            {"name": "jan 1st, 2025", "journal?": true},
+            {"name": "ghost"}
+        ]);
+        let existing = existing_page_names(&pages);
+        assert_eq!(existing.len(), 2);
+
+        let rows = serde_json::json!([
+            ["u2", "Notes", "Also [[Ghost]] and [[Missing]]"],
+            [
+                "u1",
+                "Notes",
+                "See [[Rust]], [[ghost]] and [[Jan 1st, 2025]]"
+            ],
+            ["u3", "Other", "[[ghost]] again"]
+        ]);
+        let broken = broken_links(&existing, &rows, 100);
+        assert_eq!(
+            broken,
+            vec![
+                serde_json::json!({
+                    "link_target": "ghost",
+                    "found_in_page": "Notes",
+                    "found_in_block_uuid": "u1",
+                    "context": "See [[Rust]], [[ghost]] and [[Jan 1st, 2025]]"
+                }),
+                serde_json::json!({
+                    "link_target": "Missing",
+                    "found_in_page": "Notes",
+                    "found_in_block_uuid": "u2",
+                    "context": "Also [[Ghost]] and [[Missing]]"
+                }),
+            ]
+        );
+        assert_eq!(broken_links(&existing, &rows, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_favorite_names() {
+        let favorites = serde_json::json!([
+            "Inbox",
+            {"name": "rust", "originalName": "Rust"},
+            {"name": "tokio"},
+            42
+        ]);
+        assert_eq!(favorite_names(&favorites), vec!["Inbox", "Rust", "tokio"]);
+        assert!(favorite_names(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_property_value_matches() {
+        let tags = serde_json::json!(["Rust", "Programming"]);
+        assert!(property_value_matches(&tags, "rust", true));
+        assert!(!property_value_matches(&tags, "prog", true));
+        assert!(property_value_matches(&tags, "prog", false));
+
+        assert!(property_value_matches(
+            &serde_json::json!("In Progress"),
+            "in progress",
+            true
+        ));
+        assert!(property_value_matches(&serde_json::json!(3), "3", true));
+        assert!(property_value_matches(
+            &serde_json::json!(true),
+            "true",
+            true
+        ));
+        assert!(!property_value_matches(&Value::Null, "", false));
+    }
+
+    #[tokio::test]
+    async fn test_search_by_property() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"args": [
+                "[:find (pull ?b [:block/uuid :block/content {:block/page [:block/original-name]}]) ?v \
+                 :where [?b :block/properties ?props] [(get ?props :status) ?v]]"
+            ]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                [{"uuid": "u1", "content": "Task A", "page": {"originalName": "Work"}}, "open"],
+                [{"uuid": "u2", "content": "Task B", "page": {"originalName": "Home"}}, "closed"]
+            ])))
+            .mount(&server)
+            .await;
+        let client = test_client(&server);
+
+        let result = search_by_property(
+            &client,
+            serde_json::json!({"property_name": "Status", "property_value": "OPEN"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result,
+            serde_json::json!({
+                "property": "status",
+                "value": "OPEN",
+                "count": 1,
+                "results": [{"uuid": "u1", "content": "Task A", "page": "Work", "value": "open"}]
+            })
+        );
+
+        let all = search_by_property(&client, serde_json::json!({"property_name": "status"}))
+            .await
+            .unwrap();
+        assert_eq!(all["count"], 2);
+        assert_eq!(all["value"], Value::Null);
+
+        let err = search_by_property(&client, serde_json::json!({"property_name": "a) ?x]"}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid params: Invalid property name 'a) ?x]'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_page_skips_unrequested_calls() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"method": "logseq.Editor.getPage"}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"name": "notes"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"method": "logseq.Editor.getPageBlocksTree"}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{"content": "a"}])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"method": "logseq.Editor.getPageProperties"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Value::Null))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = test_client(&server);
+
+        let metadata_only = get_page(
+            &client,
+            serde_json::json!({"page_name": "Notes", "include_blocks": false}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            metadata_only,
+            serde_json::json!({"page": {"name": "notes"}, "blocks": null, "properties": null})
+        );
+
+        let blocks_and_properties = get_page(
+            &client,
+            serde_json::json!({
+                "page_name": "Notes",
+                "include_metadata": false,
+                "include_properties": true
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            blocks_and_properties,
+            serde_json::json!({"page": null, "blocks": [{"content": "a"}], "properties": {}})
+        );
+    }
+
+    #[test]
+    fn test_block_statistics_response() {
+        let mut stats = BlockStats::default();
+        analyze_block_tree(
+            &serde_json::json!([{"uuid": "u1", "content": "DONE ok", "marker": "DONE"}]),
+            1,
+            &mut stats,
+        );
+        assert_eq!(
+            block_statistics_response("Notes", &stats),
+            serde_json::json!({
+                "page_name": "Notes",
+                "total_blocks": 1,
+                "empty_blocks": 0,
+                "avg_content_length": 7.0,
+                "longest_block": {"uuid": "u1", "length": 7},
+                "blocks_with_properties": 0,
+                "blocks_with_children": 0,
+                "max_depth": 1,
+                "task_summary": {"DONE": 1}
+            })
+        );
+
+        let empty = block_statistics_response("Empty", &BlockStats::default());
+        assert_eq!(empty["longest_block"], Value::Null);
+        assert_eq!(empty["task_summary"], serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_task_completion_trend_rejects_out_of_range_days() {
+        let client = LogseqClient::new(Config::default()).unwrap();
+        let err = get_task_completion_trend(&client, serde_json::json!({"days": u64::MAX}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::InvalidParams(_)));
+    }
+
+    /// Formats a date `days` from today as a Logseq `yyyyMMdd` day integer.
+    fn logseq_day_from_today(days: i64) -> i64 {
+        let date = Local::now().date_naive() + Duration::days(days);
+        date.format("%Y%m%d").to_string().parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pages_with_deadline_soon_filters_window() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"method": "logseq.DB.datascriptQuery"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                [{"uuid": "later", "content": "TODO b", "deadline": logseq_day_from_today(3),
+                  "page": {"originalName": "Work"}}],
+                [{"uuid": "soon", "content": "TODO a", "deadline": logseq_day_from_today(1),
+                  "page": {"originalName": "Work"}}],
+                [{"uuid": "home", "content": "TODO c", "deadline": logseq_day_from_today(2),
+                  "page": {"originalName": "Home"}}],
+                [{"uuid": "far", "content": "TODO d", "deadline": logseq_day_from_today(30),
+                  "page": {"originalName": "Work"}}],
+                [{"uuid": "past", "content": "TODO e", "deadline": logseq_day_from_today(-1),
+                  "page": {"originalName": "Work"}}],
+                [{"uuid": "sched", "content": "TODO f", "scheduled": logseq_day_from_today(1),
+                  "page": {"originalName": "Work"}}]
+            ])))
+            .mount(&server)
+            .await;
+
+        let result = get_pages_with_deadline_soon(
+            &test_client(&server),
+            serde_json::json!({"days_ahead": 7}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["days_ahead"], 7);
+        let pages = result["pages"].as_array().unwrap();
+        let names: Vec<&str> = pages
+            .iter()
+            .map(|p| p["page_name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Work", "Home"]);
+        let work: Vec<&str> = pages[0]["deadlines"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["uuid"].as_str().unwrap())
+            .collect();
+        assert_eq!(work, ["soon", "later"]);
+    }
+
+    #[tokio::test]
+    async fn test_pages_with_deadline_soon_empty() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let result = get_pages_with_deadline_soon(&test_client(&server), serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result, serde_json::json!({"pages": [], "days_ahead": 7}));
+
+        let err = get_pages_with_deadline_soon(
+            &test_client(&server),
+            serde_json::json!({"days_ahead": u64::MAX}),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, McpError::InvalidParams(_)));
+    }
+
+    #[tokio::test]
+    async fn test_recently_modified_pages_rejects_out_of_range_hours() {
+        let client = LogseqClient::new(Config::default()).unwrap();
+        for hours in [0, -5, i64::MAX] {
+            let err =
+                get_recently_modified_pages(&client, serde_json::json!({"since_hours": hours}))
+                    .await
+                    .unwrap_err();
+            assert!(matches!(err, McpError::InvalidParams(_)), "{hours}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_block_children_recursive_limits_depth() {
+        // root -> a -> c -> d, root -> b
+        let server = MockServer::start().await;
+        for (uuid, content, children) in [
+            ("root", "Root", vec!["a", "b"]),
+            ("a", "A", vec!["c"]),
+            ("b", "B", vec![]),
+            ("c", "C", vec!["d"]),
+            ("d", "D", vec![]),
+        ] {
+            let children: Vec<Value> = children
+                .into_iter()
+                .map(|c| serde_json::json!(["uuid", c]))
+                .collect();
+            Mock::given(method("POST"))
+                .and(body_json(
+                    serde_json::json!({"method": "logseq.Editor.getBlock", "args": [uuid]}),
+                ))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "uuid": uuid, "content": content, "children": children
+                })))
+                .mount(&server)
+                .await;
+        }
+        let client = test_client(&server);
+
+        let result = get_block_children_recursive(
+            &client,
+            serde_json::json!({"uuid": "root", "max_depth": 2}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["total_blocks"], 3);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(
+            result["tree"],
+            serde_json::json!([
+                {"uuid": "a", "content": "A", "depth": 1, "children": [
+                    {"uuid": "c", "content": "C", "depth": 2, "children": []}
+                ]},
+                {"uuid": "b", "content": "B", "depth": 1, "children": []}
+            ])
+        );
+        // "d" is below the depth limit and was never fetched
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 4);
+
+        let full = get_block_children_recursive(
+            &client,
+            serde_json::json!({"uuid": "root", "include_content": false}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(full["total_blocks"], 4);
+        assert_eq!(full["truncated"], false);
+        assert!(full["tree"][0].get("content").is_none());
+        assert_eq!(full["tree"][0]["children"][0]["children"][0]["uuid"], "d");
+    }
+
+    #[tokio::test]
+    async fn test_block_children_recursive_missing_root() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Value::Null))
+            .mount(&server)
+            .await;
+
+        let err = get_block_children_recursive(
+            &test_client(&server),
+            serde_json::json!({"uuid": "gone"}),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Block not found: gone");
+    }
+
+    #[test]
+    fn test_tasks_by_page_groups_and_filters() {
+        let results = serde_json::json!([
+            [{"uuid": "1", "content": "TODO a", "marker": "TODO", "page": {"originalName": "Work"}}],
+            [{"uuid": "2", "content": "DONE b", "marker": "DONE", "page": {"originalName": "Work"}}],
+            [{"uuid": "3", "content": "DOING c", "marker": "DOING", "page": {"originalName": "Home"}}],
+            [{"uuid": "4", "content": "plain", "page": {"originalName": "Home"}}],
+            [{"uuid": "5", "content": "TODO d", "marker": "TODO", "page": {"originalName": "Work"}}]
+        ]);
+
+        let (pages, total) = tasks_by_page(&results, None, 20);
+        assert_eq!(total, 4);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].0, "Work");
+        let work: Vec<&str> = pages[0]
+            .1
+            .iter()
+            .map(|t| t["uuid"].as_str().unwrap())
+            .collect();
+        assert_eq!(work, ["1", "2", "5"]);
+        assert_eq!(pages[0].1[1]["state"], "DONE");
+        assert_eq!(pages[1].0, "Home");
+
+        let open = ["TODO".to_string(), "DOING".to_string()];
+        let (pages, total) = tasks_by_page(&results, Some(&open), 20);
+        assert_eq!(total, 3);
+        assert_eq!(pages[0].1.len(), 2);
+
+        // The limit drops pages but not the task total
+        let (pages, total) = tasks_by_page(&results, Some(&open), 1);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(total, 3);
+    }
+
+    #[tokio::test]
+    async fn test_page_creation_context_collects_journal_refs() {
+        let server = MockServer::start().await;
+        // 2024-01-15T12:00:00Z
+        let created_ms = 1_705_320_000_000_i64;
+        Mock::given(method("POST"))
+            .and(body_json(
+                serde_json::json!({"method": "logseq.Editor.getPage", "args": ["My Page"]}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"name": "my page", "createdAt": created_ms})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"method": "logseq.DB.datascriptQuery"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                [{"uuid": "b2", "content": "more on [[My Page]]", "page": {"journalDay": 20240116}}],
+                [{"uuid": "b1", "content": "started [[My Page]]", "page": {"journalDay": 20240115}}]
+            ])))
+            .mount(&server)
+            .await;
+
+        let result = get_page_creation_context(
+            &test_client(&server),
+            serde_json::json!({"page_name": "My Page"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["page_name"], "My Page");
+        let created_at = result["created_at"].as_str().unwrap();
+        assert_eq!(
+            DateTime::parse_from_rfc3339(created_at)
+                .unwrap()
+                .timestamp_millis(),
+            created_ms
+        );
+        assert_eq!(
+            result["creation_context"],
+            serde_json::json!([
+                {"journal_date": "2024-01-15", "block_uuid": "b1", "block_content": "started [[My Page]]"},
+                {"journal_date": "2024-01-16", "block_uuid": "b2", "block_content": "more on [[My Page]]"}
+            ])
+        );
+
+        let requests = server.received_requests().await.unwrap();
+        let query: Value = requests[1].body_json().unwrap();
+        assert!(
+            query["args"][0]
+                .as_str()
+                .unwrap()
+                .contains(r#"[?target :block/name "my page"]"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_pages_timeout_override() {
+        use crate::{progress::ProgressReporter, tools::registry::ToolRegistry};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([]))
+                    .set_delay(std::time::Duration::from_millis(500)),
+            )
+            .mount(&server)
+            .await;
+        let client = LogseqClient::new(Config {
+            logseq_api_url: server.uri(),
+            max_retries: 0,
+            ..Config::default()
+        })
+        .unwrap();
+        let registry = ToolRegistry::global();
+        let progress = ProgressReporter::disabled();
+
+        let err = registry
+            .call(
+                "list_pages",
+                &client,
+                serde_json::json!({"timeout_ms": 0}),
+                &progress,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::InvalidParams(_)));
 
-    // Check last digit for other cases
-    match day % 10 {
-        1 => "st",
-        2 => "nd",
-        3 => "rd",
-        _ => "th",
+        let err = registry
+            .call(
+                "list_pages",
+                &client,
+                serde_json::json!({"timeout_ms": 50}),
+                &progress,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::Timeout(_)));
     }
 }
@@ -0,0 +1,304 @@
+//! # Tool Registry
+//!
+//! Pairs every tool's definition with the function that executes it, so a
+//! tool can't be listed by `tools/list` without also being callable. Each
+//! tool is a zero-sized type implementing [`ToolHandler`]; the
+//! `tool_handlers!` macro in `tools/mod.rs` declares them all in one place.
+
+use super::Tool;
+use crate::{
+    config::Config,
+    error::{McpError, McpResult},
+    logseq_client::LogseqClient,
+    progress::ProgressReporter,
+    utils::Timer,
+    validation::validate_params,
+};
+use futures::future::BoxFuture;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// A tool that can be listed and called through MCP.
+pub trait ToolHandler: Send + Sync {
+    /// The tool's name, description and input schema.
+    fn definition(&self) -> Tool;
+
+    /// Whether the tool modifies the graph (hidden in read-only mode).
+    fn is_mutation(&self) -> bool {
+        false
+    }
+
+    /// Runs the tool with already-validated arguments.
+    fn call<'a>(
+        &'a self,
+        client: &'a LogseqClient,
+        params: Value,
+        progress: &'a ProgressReporter,
+    ) -> BoxFuture<'a, McpResult<Value>>;
+}
+
+/// A registered handler with its definition built once up front.
+struct Entry {
+    definition: Tool,
+    handler: Box<dyn ToolHandler>,
+}
+
+/// All tools the server knows about, keyed by name.
+pub struct ToolRegistry {
+    handlers: HashMap<String, Entry>,
+    /// Registration order, so `tools/list` output is stable
+    order: Vec<String>,
+}
+
+impl ToolRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    /// The registry of every built-in tool, created on first use.
+    pub fn global() -> &'static ToolRegistry {
+        static REGISTRY: LazyLock<ToolRegistry> = LazyLock::new(ToolRegistry::default);
+        &REGISTRY
+    }
+
+    /// Adds a tool. Registering two tools with the same name is a bug.
+    pub fn register(&mut self, handler: Box<dyn ToolHandler>) {
+        let definition = handler.definition();
+        let name = definition.name.clone();
+        let previous = self.handlers.insert(
+            name.clone(),
+            Entry {
+                definition,
+                handler,
+            },
+        );
+        assert!(previous.is_none(), "tool '{}' registered twice", name);
+        self.order.push(name);
+    }
+
+    /// Whether a tool with this name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// Whether the named tool modifies the graph.
+    pub fn is_mutation(&self, name: &str) -> bool {
+        self.handlers
+            .get(name)
+            .is_some_and(|entry| entry.handler.is_mutation())
+    }
+
+    /// Whether `config` lets clients see and call the named tool.
+    ///
+    /// Read-only mode removes mutation tools, and `ALLOWED_TOOLS` (when set)
+    /// removes everything it doesn't name.
+    pub fn is_enabled(&self, config: &Config, name: &str) -> bool {
+        if config.read_only && self.is_mutation(name) {
+            return false;
+        }
+        config
+            .allowed_tools
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(name))
+    }
+
+    /// Every registered tool definition, in registration order.
+    pub fn definitions(&self) -> Vec<Tool> {
+        self.order
+            .iter()
+            .map(|name| self.handlers[name].definition.clone())
+            .collect()
+    }
+
+    /// The tool definitions clients may use under `config`.
+    pub fn available_tools(&self, config: &Config) -> Vec<Tool> {
+        self.definitions()
+            .into_iter()
+            .filter(|tool| self.is_enabled(config, &tool.name))
+            .collect()
+    }
+
+    /// Validates `params` against the tool's schema and runs it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `McpError::InvalidParams` for an unknown tool or arguments
+    /// that don't match the schema, otherwise whatever the tool returns.
+    pub async fn call(
+        &self,
+        name: &str,
+        client: &LogseqClient,
+        params: Value,
+        progress: &ProgressReporter,
+    ) -> McpResult<Value> {
+        self.call_timed(name, client, params, progress, &mut Timer::new())
+            .await
+    }
+
+    /// Like [`call`](Self::call), recording the `validation` and `execution`
+    /// phases in `timer`.
+    pub async fn call_timed(
+        &self,
+        name: &str,
+        client: &LogseqClient,
+        params: Value,
+        progress: &ProgressReporter,
+        timer: &mut Timer,
+    ) -> McpResult<Value> {
+        timer.start_phase("validation");
+        let entry = self
+            .handlers
+            .get(name)
+            .ok_or_else(|| McpError::InvalidParams(format!("Unknown tool: {}", name)))?;
+        validate_params(&params, &entry.definition.input_schema)?;
+        timer.start_phase("execution");
+        entry.handler.call(client, params, progress).await
+    }
+}
+
+impl Default for ToolRegistry {
+    /// A registry with every built-in tool.
+    fn default() -> Self {
+        let mut registry = Self::new();
+        for handler in super::all_handlers() {
+            registry.register(handler);
+        }
+        registry
+    }
+}
+
+/// Declares a zero-sized [`ToolHandler`] per tool, plus `all_handlers()`
+/// returning one of each in declaration order.
+///
+/// Each entry is `kind Type = function, definition;` where `kind` is one of
+/// `query`, `mutation`, `query_with_progress` or `mutation_with_progress`.
+/// The `_with_progress` kinds pass the request's [`ProgressReporter`] as a
+/// third argument to the function.
+macro_rules! tool_handlers {
+    ($($kind:ident $ty:ident = $func:path, $definition:expr;)*) => {
+        $(tool_handlers!(@handler $kind $ty = $func, $definition);)*
+
+        /// One instance of every built-in tool handler, in declaration order.
+        pub fn all_handlers() -> Vec<Box<dyn $crate::tools::registry::ToolHandler>> {
+            vec![$(Box::new($ty)),*]
+        }
+    };
+    (@handler query $ty:ident = $func:path, $definition:expr) => {
+        tool_handlers!(@impl $ty, false, |client, params, _progress| $func(client, params), $definition);
+    };
+    (@handler mutation $ty:ident = $func:path, $definition:expr) => {
+        tool_handlers!(@impl $ty, true, |client, params, _progress| $func(client, params), $definition);
+    };
+    (@handler query_with_progress $ty:ident = $func:path, $definition:expr) => {
+        tool_handlers!(@impl $ty, false, |client, params, progress| $func(client, params, progress), $definition);
+    };
+    (@handler mutation_with_progress $ty:ident = $func:path, $definition:expr) => {
+        tool_handlers!(@impl $ty, true, |client, params, progress| $func(client, params, progress), $definition);
+    };
+    (@impl $ty:ident, $mutation:expr, |$client:ident, $params:ident, $progress:ident| $call:expr, $definition:expr) => {
+        pub struct $ty;
+
+        impl $crate::tools::registry::ToolHandler for $ty {
+            fn definition(&self) -> $crate::tools::Tool {
+                $definition
+            }
+
+            fn is_mutation(&self) -> bool {
+                $mutation
+            }
+
+            fn call<'a>(
+                &'a self,
+                $client: &'a $crate::logseq_client::LogseqClient,
+                $params: serde_json::Value,
+                $progress: &'a $crate::progress::ProgressReporter,
+            ) -> futures::future::BoxFuture<'a, $crate::error::McpResult<serde_json::Value>> {
+                Box::pin($call)
+            }
+        }
+    };
+}
+
+pub(crate) use tool_handlers;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::builder::{ToolBuilder, simple_tool};
+    use serde_json::json;
+
+    struct Echo;
+
+    impl ToolHandler for Echo {
+        fn definition(&self) -> Tool {
+            ToolBuilder::new("echo")
+                .string_param("text", "Text to echo", true)
+                .build()
+        }
+
+        fn call<'a>(
+            &'a self,
+            _client: &'a LogseqClient,
+            params: Value,
+            _progress: &'a ProgressReporter,
+        ) -> BoxFuture<'a, McpResult<Value>> {
+            Box::pin(async move { Ok(params["text"].clone()) })
+        }
+    }
+
+    struct Noop;
+
+    impl ToolHandler for Noop {
+        fn definition(&self) -> Tool {
+            simple_tool("echo", "Clashes with Echo")
+        }
+
+        fn call<'a>(
+            &'a self,
+            _client: &'a LogseqClient,
+            _params: Value,
+            _progress: &'a ProgressReporter,
+        ) -> BoxFuture<'a, McpResult<Value>> {
+            Box::pin(async { Ok(Value::Null) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_validates_and_dispatches() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(Echo));
+        let client = LogseqClient::new(Config::default()).unwrap();
+        let progress = ProgressReporter::disabled();
+
+        let out = registry
+            .call("echo", &client, json!({"text": "hi"}), &progress)
+            .await
+            .unwrap();
+        assert_eq!(out, "hi");
+
+        let err = registry
+            .call("echo", &client, json!({}), &progress)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::InvalidParams(_)));
+
+        let err = registry
+            .call("nope", &client, json!({}), &progress)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid params: Unknown tool: nope");
+    }
+
+    #[test]
+    #[should_panic(expected = "tool 'echo' registered twice")]
+    fn test_duplicate_registration_panics() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(Echo));
+        registry.register(Box::new(Noop));
+    }
+}
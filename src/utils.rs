@@ -0,0 +1,472 @@
+//! # Utilities
+//!
+//! Small pure helpers shared by the query and mutation tools.
+
+use chrono::{Datelike, NaiveDate};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Every task marker Logseq recognizes at the start of a block, open ones
+/// first.
+pub const TASK_MARKERS: [&str; 8] = [
+    "TODO",
+    "LATER",
+    "IN-PROGRESS",
+    "WAITING",
+    "DOING",
+    "NOW",
+    "DONE",
+    "CANCELLED",
+];
+
+/// The [`TASK_MARKERS`] `find_incomplete_tasks` can look for.
+pub const INCOMPLETE_TASK_MARKERS: &[&str] = TASK_MARKERS.split_at(5).0;
+
+/// Formats a date as a Logseq journal page name.
+///
+/// Maps a subset of Logseq/Java date format tokens (`yyyy`, `MMM`, `dd`,
+/// `do`, `EEE`, ...) to chrono format strings and applies them to `date`.
+/// The result keeps its original case; Logseq page names are matched
+/// case-insensitively, so callers lowercase it when looking pages up.
+pub fn format_journal_date(date: NaiveDate, logseq_fmt: &str) -> String {
+    // Handle ordinal day format "do" specially (1st, 2nd, 3rd, etc.)
+    if logseq_fmt.contains("do") {
+        let day = date.day();
+        let ordinal = get_ordinal_suffix(day);
+        let day_with_ordinal = format!("{}{}", day, ordinal);
+
+        // First replace "do" with a placeholder, then do other replacements
+        let temp_fmt = logseq_fmt.replace("do", "<<DAY_ORDINAL>>");
+        let formatted = date.format(&chrono_format(&temp_fmt)).to_string();
+        formatted.replace("<<DAY_ORDINAL>>", &day_with_ordinal)
+    } else {
+        date.format(&chrono_format(logseq_fmt)).to_string()
+    }
+}
+
+/// Renders a block tree as a Logseq-style markdown outline (`- ` bullets,
+/// two spaces of indent per level, continuation lines aligned under the bullet).
+///
+/// Block content is copied verbatim, so task markers (`TODO`, `DONE`), block
+/// references (`((uuid))`) and page links (`[[Page]]`) survive unchanged.
+/// `depth` is the nesting level of the top-level blocks, usually 0.
+pub fn format_block_tree_as_markdown(blocks: &Value, depth: usize) -> String {
+    let indent = "  ".repeat(depth);
+    let mut out = String::new();
+    for block in blocks.as_array().into_iter().flatten() {
+        let content = block["content"].as_str().unwrap_or_default();
+        let mut lines = content.lines();
+        out.push_str(&format!(
+            "{}- {}\n",
+            indent,
+            lines.next().unwrap_or_default()
+        ));
+        for line in lines {
+            out.push_str(&format!("{}  {}\n", indent, line));
+        }
+        out.push_str(&format_block_tree_as_markdown(
+            &block["children"],
+            depth + 1,
+        ));
+    }
+    out
+}
+
+/// Recursively collects every block in a block tree in document (depth-first) order.
+pub fn flatten_blocks<'a>(blocks: &'a Value, out: &mut Vec<&'a Value>) {
+    for block in blocks.as_array().into_iter().flatten() {
+        out.push(block);
+        flatten_blocks(&block["children"], out);
+    }
+}
+
+/// UUIDs of a block's direct children, in order.
+///
+/// `getBlock` without `includeChildren` lists children as `["uuid", "<uuid>"]`
+/// pairs; expanded children are full block objects.
+pub fn child_block_uuids(block: &Value) -> Vec<String> {
+    block["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|child| match child {
+            Value::Array(pair) => pair.get(1).and_then(|u| u.as_str()),
+            other => other["uuid"].as_str(),
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Content metrics gathered over a block tree by [`analyze_block_tree`].
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockStats {
+    pub total_blocks: usize,
+    /// Blocks whose content is empty or only whitespace
+    pub empty_blocks: usize,
+    /// Characters of content summed over the non-empty blocks
+    pub total_content_length: usize,
+    /// UUID and character length of the longest block (first one on ties)
+    pub longest_block: Option<(String, usize)>,
+    pub blocks_with_properties: usize,
+    pub blocks_with_children: usize,
+    /// Deepest nesting level seen; top-level blocks are depth 1
+    pub max_depth: usize,
+    /// Task blocks per marker (`TODO`, `DONE`, ...)
+    pub task_counts: BTreeMap<String, usize>,
+}
+
+impl BlockStats {
+    /// Mean content length of the non-empty blocks (0 when there are none).
+    pub fn avg_content_length(&self) -> f64 {
+        match self.total_blocks - self.empty_blocks {
+            0 => 0.0,
+            n => self.total_content_length as f64 / n as f64,
+        }
+    }
+}
+
+/// Adds every block of `blocks` to `stats`, recursing into children.
+///
+/// `depth` is the nesting level of `blocks`; pass 1 for a page's top-level
+/// blocks.
+pub fn analyze_block_tree(blocks: &Value, depth: usize, stats: &mut BlockStats) {
+    for block in blocks.as_array().into_iter().flatten() {
+        stats.total_blocks += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+
+        let content = block["content"].as_str().unwrap_or("");
+        let length = content.chars().count();
+        if content.trim().is_empty() {
+            stats.empty_blocks += 1;
+        } else {
+            stats.total_content_length += length;
+        }
+        if let Some(uuid) = block["uuid"].as_str()
+            && stats
+                .longest_block
+                .as_ref()
+                .is_none_or(|(_, max)| length > *max)
+        {
+            stats.longest_block = Some((uuid.to_string(), length));
+        }
+
+        if block["properties"]
+            .as_object()
+            .is_some_and(|p| !p.is_empty())
+        {
+            stats.blocks_with_properties += 1;
+        }
+        if let Some(marker) = block["marker"].as_str() {
+            *stats.task_counts.entry(marker.to_string()).or_default() += 1;
+        }
+
+        let children = &block["children"];
+        if children.as_array().is_some_and(|c| !c.is_empty()) {
+            stats.blocks_with_children += 1;
+            analyze_block_tree(children, depth + 1, stats);
+        }
+    }
+}
+
+/// Cuts a block tree off below `max_depth`, returning whether anything was
+/// removed.
+///
+/// `depth` is the nesting level of `block` itself (0 for the root). Blocks
+/// at `max_depth` keep their other fields but get an empty `children` list.
+pub fn prune_tree(block: &mut Value, depth: usize, max_depth: usize) -> bool {
+    let Some(children) = block.get_mut("children").and_then(Value::as_array_mut) else {
+        return false;
+    };
+    if depth >= max_depth {
+        let truncated = !children.is_empty();
+        children.clear();
+        return truncated;
+    }
+    let mut truncated = false;
+    for child in children {
+        truncated |= prune_tree(child, depth + 1, max_depth);
+    }
+    truncated
+}
+
+/// Measures the wall-clock time of consecutive named phases of one request.
+#[derive(Debug)]
+pub struct Timer {
+    started: Instant,
+    phases: HashMap<String, Duration>,
+    current: Option<(String, Instant)>,
+}
+
+impl Timer {
+    /// Starts the clock for the total, with no phase running yet.
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: HashMap::new(),
+            current: None,
+        }
+    }
+
+    /// Ends the running phase, if any, and starts timing `name`.
+    ///
+    /// Starting a phase that already ran adds to its time.
+    pub fn start_phase(&mut self, name: &str) {
+        self.end_phase();
+        self.current = Some((name.to_string(), Instant::now()));
+    }
+
+    fn end_phase(&mut self) {
+        if let Some((name, started)) = self.current.take() {
+            *self.phases.entry(name).or_default() += started.elapsed();
+        }
+    }
+
+    /// Ends the running phase and returns each phase's time in whole
+    /// milliseconds, keyed `<phase>_ms`, plus `total_ms` since [`Timer::new`].
+    pub fn finish(mut self) -> HashMap<String, u64> {
+        self.end_phase();
+        let mut timings: HashMap<String, u64> = self
+            .phases
+            .into_iter()
+            .map(|(name, elapsed)| (format!("{}_ms", name), elapsed.as_millis() as u64))
+            .collect();
+        timings.insert(
+            "total_ms".to_string(),
+            self.started.elapsed().as_millis() as u64,
+        );
+        timings
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Redacts an API token wherever it appears in `s`, so it can be logged safely.
+///
+/// `Bearer <token>` becomes `Bearer [REDACTED]`, and any bare occurrence of
+/// the token becomes `[REDACTED]`. An empty token leaves `s` unchanged.
+pub fn mask_token(s: &str, token: &str) -> String {
+    if token.is_empty() {
+        return s.to_string();
+    }
+    s.replace(&format!("Bearer {}", token), "Bearer [REDACTED]")
+        .replace(token, "[REDACTED]")
+}
+
+/// Translates Java-style date tokens to chrono specifiers, longest first.
+fn chrono_format(logseq_fmt: &str) -> String {
+    logseq_fmt
+        .replace("yyyy", "%Y")
+        .replace("yy", "%y")
+        .replace("MMMM", "%B")
+        .replace("MMM", "%b")
+        .replace("MM", "%m")
+        .replace("dd", "%d")
+        .replace("EEEE", "%A")
+        .replace("EEE", "%a")
+}
+
+/// Returns the ordinal suffix for a day number (st, nd, rd, th).
+fn get_ordinal_suffix(day: u32) -> &'static str {
+    // Special cases: 11th, 12th, 13th (not 11st, 12nd, 13rd)
+    if (11..=13).contains(&day) {
+        return "th";
+    }
+
+    // Check last digit for other cases
+    match day % 10 {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_block_tree_as_markdown() {
+        let blocks = json!([
+            {"content": "Parent", "children": [
+                {"content": "Child\nsecond line", "children": []}
+            ]},
+            {"content": "Sibling"}
+        ]);
+        assert_eq!(
+            format_block_tree_as_markdown(&blocks, 0),
+            "- Parent\n  - Child\n    second line\n- Sibling\n"
+        );
+        assert_eq!(
+            format_block_tree_as_markdown(&json!([{"content": "Nested"}]), 2),
+            "    - Nested\n"
+        );
+    }
+
+    #[test]
+    fn test_format_block_tree_keeps_logseq_syntax() {
+        let blocks = json!([
+            {"content": "TODO Call [[Alice]]", "children": [
+                {"content": "DONE see ((64f0c0de-0000-4000-8000-000000000001))"}
+            ]}
+        ]);
+        assert_eq!(
+            format_block_tree_as_markdown(&blocks, 0),
+            "- TODO Call [[Alice]]\n  - DONE see ((64f0c0de-0000-4000-8000-000000000001))\n"
+        );
+    }
+
+    #[test]
+    fn test_mask_token() {
+        let header = "authorization: Bearer s3cret";
+        assert_eq!(
+            mask_token(header, "s3cret"),
+            "authorization: Bearer [REDACTED]"
+        );
+        assert_eq!(mask_token("token=s3cret", "s3cret"), "token=[REDACTED]");
+        assert_eq!(mask_token(header, ""), header);
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_list_pages_filter_applies() {
+        let result = format_journal_date(date(2026, 4, 9), "yyyy-MM-dd");
+        assert!(
+            result.len() == 10,
+            "Expected ISO date string, got: {}",
+            result
+        );
+        assert!(result.contains('-'));
+    }
+
+    #[test]
+    fn test_format_journal_date_iso() {
+        assert_eq!(
+            format_journal_date(date(2025, 1, 15), "yyyy-MM-dd"),
+            "2025-01-15"
+        );
+        assert_eq!(
+            format_journal_date(date(2025, 1, 15), "yyyy_MM_dd"),
+            "2025_01_15"
+        );
+    }
+
+    #[test]
+    fn test_format_journal_date_month_name() {
+        assert_eq!(
+            format_journal_date(date(2026, 4, 9), "MMM do, yyyy"),
+            "Apr 9th, 2026"
+        );
+        assert_eq!(
+            format_journal_date(date(2025, 1, 1), "MMM do, yyyy"),
+            "Jan 1st, 2025"
+        );
+        assert_eq!(
+            format_journal_date(date(2025, 1, 15), "EEEE, MMMM dd, yyyy"),
+            "Wednesday, January 15, 2025"
+        );
+        assert_eq!(format_journal_date(date(2025, 1, 15), "EEE dd"), "Wed 15");
+    }
+
+    #[test]
+    fn test_get_ordinal_suffix() {
+        assert_eq!(get_ordinal_suffix(1), "st");
+        assert_eq!(get_ordinal_suffix(2), "nd");
+        assert_eq!(get_ordinal_suffix(3), "rd");
+        assert_eq!(get_ordinal_suffix(4), "th");
+        assert_eq!(get_ordinal_suffix(11), "th"); // Special case
+        assert_eq!(get_ordinal_suffix(12), "th");
+        assert_eq!(get_ordinal_suffix(13), "th");
+        assert_eq!(get_ordinal_suffix(21), "st");
+        assert_eq!(get_ordinal_suffix(22), "nd");
+        assert_eq!(get_ordinal_suffix(23), "rd");
+        assert_eq!(get_ordinal_suffix(31), "st");
+    }
+
+    #[test]
+    fn test_prune_tree() {
+        let tree = json!({"content": "root", "children": [
+            {"content": "a", "children": [
+                {"content": "a1", "children": [{"content": "a1x", "children": []}]}
+            ]},
+            {"content": "b", "children": []}
+        ]});
+
+        let mut unlimited = tree.clone();
+        assert!(!prune_tree(&mut unlimited, 0, 3));
+        assert_eq!(unlimited, tree);
+
+        let mut two = tree.clone();
+        assert!(prune_tree(&mut two, 0, 2));
+        assert_eq!(two["children"][0]["children"][0]["content"], "a1");
+        assert_eq!(two["children"][0]["children"][0]["children"], json!([]));
+
+        let mut zero = tree.clone();
+        assert!(prune_tree(&mut zero, 0, 0));
+        assert_eq!(zero, json!({"content": "root", "children": []}));
+
+        // Leaves and blocks without a children array are never truncated
+        assert!(!prune_tree(&mut json!({"content": "leaf"}), 0, 0));
+        assert!(!prune_tree(&mut json!({"children": []}), 0, 0));
+    }
+
+    #[test]
+    fn test_analyze_block_tree() {
+        let tree = json!([
+            {"uuid": "a", "content": "TODO write", "marker": "TODO", "children": [
+                {"uuid": "b", "content": "  ", "children": []},
+                {"uuid": "c", "content": "DONE ship it", "marker": "DONE",
+                 "properties": {"owner": "me"}, "children": [
+                    {"uuid": "d", "content": "TODO test", "marker": "TODO"}
+                ]}
+            ]},
+            {"uuid": "e", "content": "", "properties": {}}
+        ]);
+        let mut stats = BlockStats::default();
+        analyze_block_tree(&tree, 1, &mut stats);
+
+        assert_eq!(stats.total_blocks, 5);
+        assert_eq!(stats.empty_blocks, 2);
+        assert_eq!(stats.total_content_length, 10 + 12 + 9);
+        assert_eq!(stats.longest_block, Some(("c".to_string(), 12)));
+        assert_eq!(stats.blocks_with_properties, 1);
+        assert_eq!(stats.blocks_with_children, 2);
+        assert_eq!(stats.max_depth, 3);
+        assert_eq!(stats.task_counts["TODO"], 2);
+        assert_eq!(stats.task_counts["DONE"], 1);
+        assert_eq!(stats.avg_content_length(), 31.0 / 3.0);
+
+        let mut empty = BlockStats::default();
+        analyze_block_tree(&json!([]), 1, &mut empty);
+        assert_eq!(empty, BlockStats::default());
+        assert_eq!(empty.avg_content_length(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_timer_phases() {
+        let mut timer = Timer::new();
+        timer.start_phase("validation");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        timer.start_phase("processing");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // Re-entering a phase adds to it
+        timer.start_phase("validation");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let timings = timer.finish();
+        assert_eq!(timings.len(), 3);
+        assert!(timings["validation_ms"] >= 10);
+        assert!(timings["processing_ms"] >= 20);
+        assert!(timings["total_ms"] >= timings["validation_ms"] + timings["processing_ms"]);
+    }
+}
@@ -0,0 +1,373 @@
+//! # Parameter Validation
+//!
+//! Checks tool arguments against the tool's declared input schema before the
+//! handler runs, so malformed calls fail with a message naming the field at
+//! fault instead of a generic "parameter is required".
+//!
+//! Only the subset of JSON Schema that `ToolBuilder` emits is understood:
+//! `required`, `type`, `enum`, `minLength`/`maxLength` for strings and
+//! `items`/`minItems`/`maxItems` for arrays, and `minimum`/`maximum` for
+//! integers and numbers. Arguments the
+//! schema doesn't declare are passed through untouched.
+
+use crate::{error::McpError, tools::ToolInputSchema};
+use serde_json::Value;
+
+/// Validates tool arguments against a tool's input schema.
+///
+/// Optional fields that are absent or `null` are skipped; required fields
+/// must be present and non-null.
+///
+/// # Errors
+///
+/// Returns `McpError::InvalidParams` describing the first violation found.
+pub fn validate_params(params: &Value, schema: &ToolInputSchema) -> Result<(), McpError> {
+    if !params.is_object() {
+        return Err(McpError::InvalidParams(
+            "Tool arguments must be an object".to_string(),
+        ));
+    }
+
+    for name in schema.required.iter().flatten() {
+        if params[name].is_null() {
+            return Err(McpError::InvalidParams(format!(
+                "Missing required parameter '{}'",
+                name
+            )));
+        }
+    }
+
+    // Check fields in name order so the reported violation is deterministic
+    let mut properties: Vec<_> = schema.properties.iter().flatten().collect();
+    properties.sort_by_key(|(name, _)| name.as_str());
+    for (name, property) in properties {
+        let value = &params[name.as_str()];
+        if !value.is_null() {
+            validate_value(name, value, property)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `s` is a hyphenated UUID, the form Logseq uses for block UUIDs.
+pub fn is_block_uuid(s: &str) -> bool {
+    s.len() == 36 && uuid::Uuid::parse_str(s).is_ok()
+}
+
+/// Rejects a malformed UUID argument before it reaches the Logseq API,
+/// which would otherwise fail with a less helpful error.
+///
+/// # Errors
+///
+/// Returns `McpError::InvalidParams` naming the parameter if `value` isn't
+/// a hyphenated UUID.
+pub fn require_block_uuid(name: &str, value: &str) -> Result<(), McpError> {
+    if is_block_uuid(value) {
+        Ok(())
+    } else {
+        Err(McpError::InvalidParams(format!(
+            "Parameter '{}' must be a block UUID, got '{}'",
+            name, value
+        )))
+    }
+}
+
+/// Checks one argument against its property definition.
+fn validate_value(name: &str, value: &Value, property: &Value) -> Result<(), McpError> {
+    let expected = property["type"].as_str().unwrap_or("");
+    if !matches_type(value, expected) {
+        return Err(McpError::InvalidParams(format!(
+            "Parameter '{}' must be of type {}, got {}",
+            name,
+            expected,
+            type_name(value)
+        )));
+    }
+
+    if let Some(allowed) = property["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+        return Err(McpError::InvalidParams(format!(
+            "Parameter '{}' must be one of {}, got {}",
+            name,
+            allowed.join(", "),
+            value
+        )));
+    }
+
+    if let Some(s) = value.as_str() {
+        let len = s.chars().count() as u64;
+        if let Some(min) = property["minLength"].as_u64().filter(|&min| len < min) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}' must be at least {} characters",
+                name, min
+            )));
+        }
+        if let Some(max) = property["maxLength"].as_u64().filter(|&max| len > max) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}' must be at most {} characters",
+                name, max
+            )));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = property["minimum"].as_f64().filter(|&min| n < min) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}' must be >= {}, got {}",
+                name, min, value
+            )));
+        }
+        if let Some(max) = property["maximum"].as_f64().filter(|&max| n > max) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}' must be <= {}, got {}",
+                name, max, value
+            )));
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        let item_type = property["items"]["type"].as_str().unwrap_or("");
+        if let Some(i) = items.iter().position(|item| !matches_type(item, item_type)) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}[{}]' must be of type {}, got {}",
+                name,
+                i,
+                item_type,
+                type_name(&items[i])
+            )));
+        }
+        let len = items.len() as u64;
+        if let Some(min) = property["minItems"].as_u64().filter(|&min| len < min) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}' must have at least {} item(s)",
+                name, min
+            )));
+        }
+        if let Some(max) = property["maxItems"].as_u64().filter(|&max| len > max) {
+            return Err(McpError::InvalidParams(format!(
+                "Parameter '{}' must have at most {} item(s)",
+                name, max
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `value` has the JSON Schema type `expected`. Unknown types pass.
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// The JSON Schema type name of a value, for error messages.
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::builder::ToolBuilder;
+    use serde_json::json;
+
+    fn schema() -> ToolInputSchema {
+        ToolBuilder::new("test")
+            .string_param("page_name", "Page", true)
+            .bool_param("exact", "Exact match", Some(false), false)
+            .int_param("limit", "Limit", false)
+            .min(1.0)
+            .max(100.0)
+            .number_param("score", "Score", false)
+            .min(0.0)
+            .max(1.0)
+            .enum_param("order", "Order", ["asc", "desc"], false)
+            .array_param("tags", "Tags", "string", false)
+            .min_items(1)
+            .max_items(2)
+            .build()
+            .input_schema
+    }
+
+    fn message(result: Result<(), McpError>) -> String {
+        match result {
+            Err(McpError::InvalidParams(msg)) => msg,
+            other => panic!("expected InvalidParams, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_valid_params_pass() {
+        assert!(validate_params(&json!({"page_name": "Rust"}), &schema()).is_ok());
+        assert!(
+            validate_params(
+                &json!({"page_name": "Rust", "exact": true, "limit": 5, "extra": [1]}),
+                &schema()
+            )
+            .is_ok()
+        );
+        // Optional fields may be null
+        assert!(validate_params(&json!({"page_name": "Rust", "limit": null}), &schema()).is_ok());
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        assert_eq!(
+            message(validate_params(&json!({"limit": 5}), &schema())),
+            "Missing required parameter 'page_name'"
+        );
+        assert_eq!(
+            message(validate_params(&json!({"page_name": null}), &schema())),
+            "Missing required parameter 'page_name'"
+        );
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        assert_eq!(
+            message(validate_params(&json!({"page_name": 42}), &schema())),
+            "Parameter 'page_name' must be of type string, got integer"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "Rust", "limit": 2.5}),
+                &schema()
+            )),
+            "Parameter 'limit' must be of type integer, got number"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "Rust", "exact": "yes"}),
+                &schema()
+            )),
+            "Parameter 'exact' must be of type boolean, got string"
+        );
+        assert!(validate_params(&json!(["Rust"]), &schema()).is_err());
+    }
+
+    #[test]
+    fn test_string_length_bounds() {
+        let mut schema = schema();
+        let props = schema.properties.as_mut().unwrap();
+        props.get_mut("page_name").unwrap()["minLength"] = json!(2);
+        props.get_mut("page_name").unwrap()["maxLength"] = json!(4);
+
+        assert!(validate_params(&json!({"page_name": "Rust"}), &schema).is_ok());
+        assert_eq!(
+            message(validate_params(&json!({"page_name": "R"}), &schema)),
+            "Parameter 'page_name' must be at least 2 characters"
+        );
+        assert_eq!(
+            message(validate_params(&json!({"page_name": "Rustacean"}), &schema)),
+            "Parameter 'page_name' must be at most 4 characters"
+        );
+    }
+
+    #[test]
+    fn test_enum_membership() {
+        assert!(validate_params(&json!({"page_name": "Rust", "order": "desc"}), &schema()).is_ok());
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "Rust", "order": "random"}),
+                &schema()
+            )),
+            r#"Parameter 'order' must be one of "asc", "desc", got "random""#
+        );
+    }
+
+    #[test]
+    fn test_array_items_and_bounds() {
+        assert!(validate_params(&json!({"page_name": "R", "tags": ["a", "b"]}), &schema()).is_ok());
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "tags": "a"}),
+                &schema()
+            )),
+            "Parameter 'tags' must be of type array, got string"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "tags": ["a", 1]}),
+                &schema()
+            )),
+            "Parameter 'tags[1]' must be of type string, got integer"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "tags": []}),
+                &schema()
+            )),
+            "Parameter 'tags' must have at least 1 item(s)"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "tags": ["a", "b", "c"]}),
+                &schema()
+            )),
+            "Parameter 'tags' must have at most 2 item(s)"
+        );
+    }
+
+    #[test]
+    fn test_numeric_ranges() {
+        let ok = json!({"page_name": "R", "limit": 100, "score": 0.5});
+        assert!(validate_params(&ok, &schema()).is_ok());
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "limit": 0}),
+                &schema()
+            )),
+            "Parameter 'limit' must be >= 1, got 0"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "limit": 101}),
+                &schema()
+            )),
+            "Parameter 'limit' must be <= 100, got 101"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "score": 1.5}),
+                &schema()
+            )),
+            "Parameter 'score' must be <= 1, got 1.5"
+        );
+        assert_eq!(
+            message(validate_params(
+                &json!({"page_name": "R", "score": -0.1}),
+                &schema()
+            )),
+            "Parameter 'score' must be >= 0, got -0.1"
+        );
+    }
+
+    #[test]
+    fn test_require_block_uuid() {
+        assert!(require_block_uuid("uuid", "6650a1b2-3c4d-4e5f-8a9b-0c1d2e3f4a5b").is_ok());
+        assert_eq!(
+            message(require_block_uuid("uuid", "not-a-uuid")),
+            "Parameter 'uuid' must be a block UUID, got 'not-a-uuid'"
+        );
+        // Unhyphenated UUIDs parse, but Logseq expects the hyphenated form
+        assert!(!is_block_uuid("6650a1b23c4d4e5f8a9b0c1d2e3f4a5b"));
+    }
+}
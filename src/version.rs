@@ -0,0 +1,38 @@
+//! # Server Version
+//!
+//! The server's name and version as reported to MCP clients, taken from
+//! `Cargo.toml` at compile time so they can't drift from the crate.
+
+use serde_json::{Value, json};
+use std::sync::LazyLock;
+
+/// The crate version, e.g. `1.2.0`.
+pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The crate name, `mcp-logseq-rust`.
+pub const SERVER_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Returns the MCP `serverInfo` block: `{ "name", "version" }`.
+pub fn get_server_info() -> Value {
+    static SERVER_INFO: LazyLock<Value> = LazyLock::new(|| {
+        json!({
+            "name": SERVER_NAME,
+            "version": SERVER_VERSION
+        })
+    });
+    SERVER_INFO.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_server_info_matches_cargo_metadata() {
+        let info = get_server_info();
+        assert_eq!(info["name"], "mcp-logseq-rust");
+        let version = info["version"].as_str().unwrap();
+        assert!(Regex::new(r"^\d+\.\d+\.\d+").unwrap().is_match(version));
+    }
+}
@@ -0,0 +1,63 @@
+//! End-to-end tests of `LogseqClient` against a mocked Logseq HTTP API.
+
+mod test_helpers;
+
+use mcp_logseq_rust::error::McpError;
+use serde_json::json;
+use test_helpers::{client_for, mock_logseq_server, single_request_body};
+
+#[tokio::test]
+async fn test_get_page_sends_method_and_args() {
+    let page = json!({"id": 42, "name": "rust", "originalName": "Rust"});
+    let server = mock_logseq_server(page.clone()).await;
+    let client = client_for(&server);
+
+    let result = client.get_page("Rust").await.unwrap();
+
+    assert_eq!(result, page);
+    assert_eq!(
+        single_request_body(&server).await,
+        json!({"method": "logseq.Editor.getPage", "args": ["Rust"]})
+    );
+}
+
+#[tokio::test]
+async fn test_insert_block_sends_sibling_option() {
+    let block = json!({"uuid": "new-block", "content": "Hello"});
+    let server = mock_logseq_server(block.clone()).await;
+    let client = client_for(&server);
+
+    let result = client
+        .insert_block("parent-uuid", "Hello", false)
+        .await
+        .unwrap();
+
+    assert_eq!(result, block);
+    assert_eq!(
+        single_request_body(&server).await,
+        json!({
+            "method": "logseq.Editor.insertBlock",
+            "args": ["parent-uuid", "Hello", {"sibling": false}]
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_error_response_becomes_err() {
+    let server = mock_logseq_server(json!({"error": "Page not found"})).await;
+    let client = client_for(&server);
+
+    let err = client.get_page("missing").await.unwrap_err();
+
+    match err {
+        McpError::LogseqApi { method, message } => {
+            assert_eq!(method, "logseq.Editor.getPage");
+            assert!(message.contains("Page not found"));
+        }
+        other => panic!("expected LogseqApi, got {other:?}"),
+    }
+    assert_eq!(
+        single_request_body(&server).await,
+        json!({"method": "logseq.Editor.getPage", "args": ["missing"]})
+    );
+}
@@ -0,0 +1,46 @@
+//! Shared fixtures for integration tests that run the client against a
+//! mocked Logseq HTTP API.
+
+use mcp_logseq_rust::{config::Config, logseq_client::LogseqClient};
+use serde_json::Value;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// API token the mock server expects in the `Authorization` header.
+pub const TEST_TOKEN: &str = "integration-test-token";
+
+/// Starts a mock Logseq server whose `POST /api` route answers every call
+/// with `response`.
+///
+/// The route only matches requests carrying `Authorization: Bearer
+/// <TEST_TOKEN>`, and the server fails the test on drop unless it matched
+/// at least once.
+pub async fn mock_logseq_server(response: Value) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(header("authorization", format!("Bearer {}", TEST_TOKEN)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response))
+        .expect(1..)
+        .mount(&server)
+        .await;
+    server
+}
+
+/// A client pointed at `server`, authenticating with [`TEST_TOKEN`].
+pub fn client_for(server: &MockServer) -> LogseqClient {
+    LogseqClient::new(Config {
+        logseq_api_token: TEST_TOKEN.to_string(),
+        logseq_api_url: server.uri(),
+        max_retries: 0,
+        ..Config::default()
+    })
+    .unwrap()
+}
+
+/// The JSON body of the only request `server` has received.
+pub async fn single_request_body(server: &MockServer) -> Value {
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1, "expected exactly one API call");
+    requests[0].body_json().unwrap()
+}